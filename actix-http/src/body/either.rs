@@ -7,7 +7,7 @@ use bytes::Bytes;
 use pin_project_lite::pin_project;
 
 use super::{BodySize, BoxBody, MessageBody};
-use crate::Error;
+use crate::{header::HeaderMap, Error};
 
 pin_project! {
     /// An "either" type specialized for body types.
@@ -87,6 +87,21 @@ where
         }
     }
 
+    #[inline]
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        match self.project() {
+            EitherBodyProj::Left { body } => body
+                .poll_trailers(cx)
+                .map_err(|err| Error::new_body().with_cause(err)),
+            EitherBodyProj::Right { body } => body
+                .poll_trailers(cx)
+                .map_err(|err| Error::new_body().with_cause(err)),
+        }
+    }
+
     #[inline]
     fn try_into_bytes(self) -> Result<Bytes, Self> {
         match self {
@@ -13,6 +13,7 @@ use futures_core::ready;
 use pin_project_lite::pin_project;
 
 use super::{BodySize, BoxBody};
+use crate::header::HeaderMap;
 
 /// An interface for types that can be used as a response body.
 ///
@@ -86,6 +87,22 @@ pub trait MessageBody {
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Bytes, Self::Error>>>;
 
+    /// Attempt to pull out the trailers sent after the body.
+    ///
+    /// This method is called once, after `poll_next` has returned `Ready(None)`. Trailers are sent
+    /// as a trailing `HEADERS` frame on HTTP/2 and HTTP/3. On HTTP/1.1, they are only sent when the
+    /// body uses chunked transfer encoding (i.e., its size is [`BodySize::Stream`]) and the client
+    /// indicated support for them with a `TE: trailers` request header; otherwise they are dropped.
+    ///
+    /// The default implementation returns no trailers.
+    #[inline]
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(None))
+    }
+
     /// Try to convert into the complete chunk of body bytes.
     ///
     /// Override this method if the complete body can be trivially extracted. This is useful for
@@ -140,6 +157,13 @@ mod foreign_impls {
         ) -> Poll<Option<Result<Bytes, Self::Error>>> {
             Pin::new(&mut **self).poll_next(cx)
         }
+
+        fn poll_trailers(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            Pin::new(&mut **self).poll_trailers(cx)
+        }
     }
 
     impl MessageBody for Infallible {
@@ -197,6 +221,14 @@ mod foreign_impls {
         ) -> Poll<Option<Result<Bytes, Self::Error>>> {
             Pin::new(self.get_mut().as_mut()).poll_next(cx)
         }
+
+        #[inline]
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            Pin::new(self.get_mut().as_mut()).poll_trailers(cx)
+        }
     }
 
     impl<T, B> MessageBody for Pin<T>
@@ -218,6 +250,14 @@ mod foreign_impls {
         ) -> Poll<Option<Result<Bytes, Self::Error>>> {
             self.get_mut().as_mut().poll_next(cx)
         }
+
+        #[inline]
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            self.get_mut().as_mut().poll_trailers(cx)
+        }
     }
 
     impl MessageBody for &'static [u8] {
@@ -520,6 +560,21 @@ where
         }
     }
 
+    fn poll_trailers(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        let this = self.as_mut().project();
+
+        match ready!(this.body.poll_trailers(cx)) {
+            Ok(trailers) => Poll::Ready(Ok(trailers)),
+            Err(err) => {
+                let f = self.as_mut().project().mapper.take().unwrap();
+                Poll::Ready(Err((f)(err)))
+            }
+        }
+    }
+
     #[inline]
     fn try_into_bytes(self) -> Result<Bytes, Self> {
         let Self { body, mapper } = self;
@@ -13,6 +13,7 @@ mod none;
 mod size;
 mod sized_stream;
 mod utils;
+mod with_trailers;
 
 pub(crate) use self::message_body::MessageBodyMapErr;
 pub use self::{
@@ -24,4 +25,5 @@ pub use self::{
     size::BodySize,
     sized_stream::SizedStream,
     utils::{to_bytes, to_bytes_limited, BodyLimitExceeded},
+    with_trailers::WithTrailers,
 };
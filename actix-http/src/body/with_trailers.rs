@@ -0,0 +1,117 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use pin_project_lite::pin_project;
+
+use super::{BodySize, MessageBody};
+use crate::header::HeaderMap;
+
+pin_project! {
+    /// Body wrapper that sends trailers after the wrapped body.
+    ///
+    /// The body is always streamed so that trailers can be sent using chunked transfer encoding on
+    /// HTTP/1.1 connections. See [`MessageBody::poll_trailers`] for when trailers are sent.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::{body::WithTrailers, header::HeaderMap, Response};
+    ///
+    /// let mut trailers = HeaderMap::new();
+    /// trailers.insert(
+    ///     "x-checksum".parse().unwrap(),
+    ///     "8f14e45f".parse().unwrap(),
+    /// );
+    ///
+    /// let res = Response::ok().set_body(WithTrailers::new("hello world", trailers));
+    /// ```
+    pub struct WithTrailers<B> {
+        #[pin]
+        body: B,
+        trailers: Option<HeaderMap>,
+    }
+}
+
+impl<B> WithTrailers<B>
+where
+    B: MessageBody,
+{
+    /// Constructs new body that sends `trailers` after `body`.
+    #[inline]
+    pub fn new(body: B, trailers: HeaderMap) -> Self {
+        Self {
+            body,
+            trailers: Some(trailers),
+        }
+    }
+}
+
+impl<B> MessageBody for WithTrailers<B>
+where
+    B: MessageBody,
+{
+    type Error = B::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        match self.body.size() {
+            BodySize::None => BodySize::None,
+            _ => BodySize::Stream,
+        }
+    }
+
+    #[inline]
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        self.project().body.poll_next(cx)
+    }
+
+    #[inline]
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        Poll::Ready(Ok(self.project().trailers.take()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_rt::pin;
+    use actix_utils::future::poll_fn;
+
+    use super::*;
+    use crate::header::{HeaderName, HeaderValue};
+
+    #[actix_rt::test]
+    async fn trailers_after_body() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert(
+            HeaderName::from_static("x-checksum"),
+            HeaderValue::from_static("abc"),
+        );
+
+        let body = WithTrailers::new("test", trailers);
+        assert_eq!(body.size(), BodySize::Stream);
+        pin!(body);
+
+        assert_eq!(
+            poll_fn(|cx| body.as_mut().poll_next(cx))
+                .await
+                .unwrap()
+                .ok(),
+            Some(Bytes::from("test")),
+        );
+        assert!(poll_fn(|cx| body.as_mut().poll_next(cx)).await.is_none());
+
+        let trailers = poll_fn(|cx| body.as_mut().poll_trailers(cx))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(trailers.get("x-checksum").unwrap(), "abc");
+    }
+}
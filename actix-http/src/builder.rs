@@ -1,13 +1,14 @@
-use std::{fmt, marker::PhantomData, net, rc::Rc, time::Duration};
+use std::{fmt, future::Future, marker::PhantomData, net, rc::Rc, time::Duration};
 
 use actix_codec::Framed;
 use actix_service::{IntoServiceFactory, Service, ServiceFactory};
 
 use crate::{
     body::{BoxBody, MessageBody},
+    config::ShutdownSignal,
     h1::{self, ExpectHandler, H1Service, UpgradeHandler},
     service::HttpService,
-    ConnectCallback, Extensions, KeepAlive, Request, Response, ServiceConfig,
+    ConnectCallback, Extensions, H1Limits, H2Settings, KeepAlive, Request, Response, ServiceConfig,
 };
 
 /// An HTTP service builder.
@@ -19,6 +20,10 @@ pub struct HttpServiceBuilder<T, S, X = ExpectHandler, U = UpgradeHandler> {
     client_disconnect_timeout: Duration,
     secure: bool,
     local_addr: Option<net::SocketAddr>,
+    h1_limits: H1Limits,
+    h2_settings: H2Settings,
+    defer_continue: bool,
+    shutdown_signal: Option<ShutdownSignal>,
     expect: X,
     upgrade: Option<U>,
     on_connect_ext: Option<Rc<ConnectCallback<T>>>,
@@ -40,6 +45,10 @@ where
             client_disconnect_timeout: Duration::ZERO,
             secure: false,
             local_addr: None,
+            h1_limits: H1Limits::default(),
+            h2_settings: H2Settings::default(),
+            defer_continue: false,
+            shutdown_signal: None,
 
             // dispatcher parts
             expect: ExpectHandler,
@@ -124,6 +133,47 @@ where
         self.client_disconnect_timeout(dur)
     }
 
+    /// Set limits on the size of HTTP/1 request heads.
+    ///
+    /// See [`H1Limits`] for the default limits.
+    pub fn h1_limits(mut self, limits: H1Limits) -> Self {
+        self.h1_limits = limits;
+        self
+    }
+
+    /// Set settings for HTTP/2 connections.
+    ///
+    /// See [`H2Settings`] for the default settings.
+    pub fn h2_settings(mut self, settings: H2Settings) -> Self {
+        self.h2_settings = settings;
+        self
+    }
+
+    /// Set whether `100 Continue` responses to HTTP/1.1 requests with an `Expect: 100-continue`
+    /// header are deferred until the service first reads the request payload.
+    ///
+    /// When enabled, a service can respond without reading the payload so that the client never
+    /// sends it; the connection is closed after such a response. By default, `100 Continue` is
+    /// sent as soon as the expect service accepts the request.
+    pub fn defer_continue(mut self, enable: bool) -> Self {
+        self.defer_continue = enable;
+        self
+    }
+
+    /// Set the signal that starts a graceful shutdown of connections.
+    ///
+    /// `signal` is called once per HTTP/2 connection. When the returned future resolves, the
+    /// connection sends a `GOAWAY` frame, stops accepting new streams and closes once its in-flight
+    /// streams are finished.
+    pub fn graceful_shutdown_signal<F, Fut>(mut self, signal: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.shutdown_signal = Some(ShutdownSignal::new(signal));
+        self
+    }
+
     /// Provide service for `EXPECT: 100-Continue` support.
     ///
     /// Service get called with request that contains `EXPECT` header.
@@ -142,6 +192,10 @@ where
             client_disconnect_timeout: self.client_disconnect_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            h1_limits: self.h1_limits,
+            h2_settings: self.h2_settings,
+            defer_continue: self.defer_continue,
+            shutdown_signal: self.shutdown_signal,
             expect: expect.into_factory(),
             upgrade: self.upgrade,
             on_connect_ext: self.on_connect_ext,
@@ -166,6 +220,10 @@ where
             client_disconnect_timeout: self.client_disconnect_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            h1_limits: self.h1_limits,
+            h2_settings: self.h2_settings,
+            defer_continue: self.defer_continue,
+            shutdown_signal: self.shutdown_signal,
             expect: self.expect,
             upgrade: Some(upgrade.into_factory()),
             on_connect_ext: self.on_connect_ext,
@@ -201,7 +259,11 @@ where
             self.client_disconnect_timeout,
             self.secure,
             self.local_addr,
-        );
+        )
+        .with_h1_limits(self.h1_limits)
+        .with_h2_settings(self.h2_settings)
+        .with_defer_continue(self.defer_continue)
+        .with_shutdown_signal(self.shutdown_signal);
 
         H1Service::with_config(cfg, service.into_factory())
             .expect(self.expect)
@@ -226,7 +288,11 @@ where
             self.client_disconnect_timeout,
             self.secure,
             self.local_addr,
-        );
+        )
+        .with_h1_limits(self.h1_limits)
+        .with_h2_settings(self.h2_settings)
+        .with_defer_continue(self.defer_continue)
+        .with_shutdown_signal(self.shutdown_signal);
 
         crate::h2::H2Service::with_config(cfg, service.into_factory())
             .on_connect_ext(self.on_connect_ext)
@@ -248,7 +314,11 @@ where
             self.client_disconnect_timeout,
             self.secure,
             self.local_addr,
-        );
+        )
+        .with_h1_limits(self.h1_limits)
+        .with_h2_settings(self.h2_settings)
+        .with_defer_continue(self.defer_continue)
+        .with_shutdown_signal(self.shutdown_signal);
 
         HttpService::with_config(cfg, service.into_factory())
             .expect(self.expect)
@@ -256,3 +326,39 @@ where
             .on_connect_ext(self.on_connect_ext)
     }
 }
+
+#[cfg(feature = "http3")]
+impl<S, X, U> HttpServiceBuilder<quinn::Connection, S, X, U>
+where
+    S: ServiceFactory<Request, Config = ()>,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::InitError: fmt::Debug,
+    <S::Service as Service<Request>>::Future: 'static,
+{
+    /// Finish service configuration and create a service for the HTTP/3 protocol.
+    ///
+    /// QUIC connections are always encrypted, so the service is configured as secure. The on-connect
+    /// callback is called with the established QUIC connection.
+    pub fn h3<F, B>(self, service: F) -> crate::h3::H3Service<S, B>
+    where
+        F: IntoServiceFactory<S, Request>,
+        S::Response: Into<Response<B>> + 'static,
+
+        B: MessageBody + 'static,
+    {
+        let cfg = ServiceConfig::new(
+            self.keep_alive,
+            self.client_request_timeout,
+            self.client_disconnect_timeout,
+            true,
+            self.local_addr,
+        )
+        .with_h1_limits(self.h1_limits)
+        .with_h2_settings(self.h2_settings)
+        .with_defer_continue(self.defer_continue)
+        .with_shutdown_signal(self.shutdown_signal);
+
+        crate::h3::H3Service::with_config(cfg, service.into_factory())
+            .on_connect_ext(self.on_connect_ext)
+    }
+}
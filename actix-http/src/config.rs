@@ -1,5 +1,8 @@
 use std::{
+    fmt,
+    future::Future,
     net,
+    pin::Pin,
     rc::Rc,
     time::{Duration, Instant},
 };
@@ -8,6 +11,265 @@ use bytes::BytesMut;
 
 use crate::{date::DateService, KeepAlive};
 
+/// Limits on the size of HTTP/1 request heads.
+///
+/// Requests with too many headers, a header value that is too large or a head that is too large
+/// overall are rejected with a `431 Request Header Fields Too Large` response. Requests with a
+/// request line that is too long are rejected with a `414 URI Too Long` response. In both cases,
+/// the connection is closed after the response is sent.
+///
+/// # Examples
+/// ```
+/// use actix_http::{H1Limits, HttpService, Response};
+///
+/// let limits = H1Limits::new()
+///     .max_headers(128)
+///     .max_head_size(16 * 1024)
+///     .max_request_line_size(4 * 1024);
+///
+/// # actix_rt::System::new().block_on(async {
+/// let srv = HttpService::<actix_rt::net::TcpStream, _, _>::build()
+///     .h1_limits(limits)
+///     .finish(|_req| async { Ok::<_, std::convert::Infallible>(Response::ok()) });
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct H1Limits {
+    pub(crate) max_headers: usize,
+    pub(crate) max_head_size: usize,
+    pub(crate) max_request_line_size: usize,
+    pub(crate) max_header_value_size: usize,
+    pub(crate) max_trailer_size: usize,
+}
+
+impl Default for H1Limits {
+    fn default() -> Self {
+        Self {
+            max_headers: 96,
+            max_head_size: 131_072,
+            max_request_line_size: usize::MAX,
+            max_header_value_size: usize::MAX,
+            max_trailer_size: 16_384,
+        }
+    }
+}
+
+impl H1Limits {
+    /// Constructs limits with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of headers in a request head.
+    ///
+    /// By default, the limit is 96 headers.
+    pub fn max_headers(mut self, max_headers: usize) -> Self {
+        self.max_headers = max_headers;
+        self
+    }
+
+    /// Sets the maximum size of a request head, including the request line, in bytes.
+    ///
+    /// By default, the limit is 128 KiB.
+    pub fn max_head_size(mut self, max_head_size: usize) -> Self {
+        self.max_head_size = max_head_size;
+        self
+    }
+
+    /// Sets the maximum size of a request line, like `GET /index.html HTTP/1.1`, in bytes.
+    ///
+    /// By default, request lines are only limited by the maximum head size.
+    pub fn max_request_line_size(mut self, max_request_line_size: usize) -> Self {
+        self.max_request_line_size = max_request_line_size;
+        self
+    }
+
+    /// Sets the maximum size of a single header value, in bytes.
+    ///
+    /// By default, header values are only limited by the maximum head size.
+    pub fn max_header_value_size(mut self, max_header_value_size: usize) -> Self {
+        self.max_header_value_size = max_header_value_size;
+        self
+    }
+
+    /// Sets the maximum size of the trailer section of a chunked request body, in bytes.
+    ///
+    /// The number of trailer fields is limited by [`max_headers`](Self::max_headers).
+    ///
+    /// By default, the limit is 16 KiB.
+    pub fn max_trailer_size(mut self, max_trailer_size: usize) -> Self {
+        self.max_trailer_size = max_trailer_size;
+        self
+    }
+}
+
+/// Settings for HTTP/2 connections.
+///
+/// Settings that are not set use the defaults of the underlying HTTP/2 implementation.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use actix_http::{H2Settings, HttpService, Response};
+///
+/// let settings = H2Settings::new()
+///     .max_concurrent_streams(100)
+///     .initial_window_size(1024 * 1024)
+///     .initial_connection_window_size(4 * 1024 * 1024)
+///     .ping_interval(Duration::from_secs(10));
+///
+/// # actix_rt::System::new().block_on(async {
+/// let srv = HttpService::<actix_rt::net::TcpStream, _, _>::build()
+///     .h2_settings(settings)
+///     .finish(|_req| async { Ok::<_, std::convert::Infallible>(Response::ok()) });
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct H2Settings {
+    pub(crate) max_concurrent_streams: Option<u32>,
+    pub(crate) initial_window_size: Option<u32>,
+    pub(crate) initial_connection_window_size: Option<u32>,
+    pub(crate) max_frame_size: Option<u32>,
+    pub(crate) max_header_list_size: Option<u32>,
+    pub(crate) ping_interval: Option<Duration>,
+    pub(crate) enable_connect_protocol: bool,
+    pub(crate) h2c_upgrade: bool,
+}
+
+impl Default for H2Settings {
+    fn default() -> Self {
+        Self {
+            max_concurrent_streams: None,
+            initial_window_size: None,
+            initial_connection_window_size: None,
+            max_frame_size: None,
+            max_header_list_size: None,
+            ping_interval: None,
+            enable_connect_protocol: true,
+            h2c_upgrade: true,
+        }
+    }
+}
+
+impl H2Settings {
+    /// Constructs settings with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of concurrent streams a client may open on a connection.
+    ///
+    /// By default, the number of concurrent streams is not limited.
+    pub fn max_concurrent_streams(mut self, max: u32) -> Self {
+        self.max_concurrent_streams = Some(max);
+        self
+    }
+
+    /// Sets the initial window size (in bytes) for stream-level flow control for received data.
+    ///
+    /// The default value is 65,535 and is good for APIs, but not for big objects.
+    pub fn initial_window_size(mut self, size: u32) -> Self {
+        self.initial_window_size = Some(size);
+        self
+    }
+
+    /// Sets the initial window size (in bytes) for connection-level flow control for received
+    /// data.
+    ///
+    /// The default value is 65,535 and is good for APIs, but not for big objects.
+    pub fn initial_connection_window_size(mut self, size: u32) -> Self {
+        self.initial_connection_window_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of frame payloads the server is willing to receive.
+    ///
+    /// The default value is 16,384.
+    ///
+    /// # Panics
+    /// Panics if `size` is not between 16,384 and 16,777,215, inclusive.
+    pub fn max_frame_size(mut self, size: u32) -> Self {
+        assert!(
+            (16_384..=16_777_215).contains(&size),
+            "HTTP/2 max frame size must be between 16,384 and 16,777,215 bytes"
+        );
+
+        self.max_frame_size = Some(size);
+        self
+    }
+
+    /// Sets the maximum size (in bytes) of request header lists the server is willing to accept.
+    ///
+    /// The default value is 16 MiB.
+    pub fn max_header_list_size(mut self, size: u32) -> Self {
+        self.max_header_list_size = Some(size);
+        self
+    }
+
+    /// Sets the interval between keep-alive pings.
+    ///
+    /// Connections whose peer does not acknowledge a ping within the interval are closed.
+    ///
+    /// By default, pings are sent at the connection's keep-alive duration and are disabled when
+    /// keep-alive is not a timeout.
+    pub fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Sets whether extended CONNECT requests (RFC 8441) are accepted.
+    ///
+    /// Extended CONNECT is used to open WebSockets over HTTP/2 connections. See
+    /// [`RequestHead::protocol`](crate::RequestHead::protocol) for how these requests are
+    /// presented to services.
+    ///
+    /// By default, extended CONNECT is enabled.
+    pub fn enable_connect_protocol(mut self, enable: bool) -> Self {
+        self.enable_connect_protocol = enable;
+        self
+    }
+
+    /// Sets whether plaintext HTTP/1.1 connections may be upgraded to HTTP/2 using
+    /// `Upgrade: h2c` (RFC 7540 §3.2).
+    ///
+    /// Upgrades are only attempted on connections accepted by
+    /// [`HttpService::tcp_auto_h2c`](crate::HttpService::tcp_auto_h2c). Requests with a body are
+    /// served over HTTP/1.1 without upgrading.
+    ///
+    /// By default, `h2c` upgrades are enabled.
+    pub fn h2c_upgrade(mut self, enable: bool) -> Self {
+        self.h2c_upgrade = enable;
+        self
+    }
+}
+
+/// Future factory used to notify connections of a graceful server shutdown.
+#[derive(Clone)]
+pub(crate) struct ShutdownSignal(Rc<dyn Fn() -> Pin<Box<dyn Future<Output = ()>>>>);
+
+impl ShutdownSignal {
+    pub(crate) fn new<F, Fut>(signal: F) -> Self
+    where
+        F: Fn() -> Fut + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        Self(Rc::new(move || Box::pin(signal())))
+    }
+
+    /// Returns a future that resolves when graceful shutdown starts.
+    #[allow(unused)] // used with `http2` feature flag
+    pub(crate) fn notified(&self) -> Pin<Box<dyn Future<Output = ()>>> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for ShutdownSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShutdownSignal").finish_non_exhaustive()
+    }
+}
+
 /// HTTP service configuration.
 #[derive(Debug, Clone)]
 pub struct ServiceConfig(Rc<Inner>);
@@ -19,6 +281,10 @@ struct Inner {
     client_disconnect_timeout: Duration,
     secure: bool,
     local_addr: Option<std::net::SocketAddr>,
+    h1_limits: H1Limits,
+    h2_settings: H2Settings,
+    defer_continue: bool,
+    shutdown_signal: Option<ShutdownSignal>,
     date_service: DateService,
 }
 
@@ -49,10 +315,58 @@ impl ServiceConfig {
             client_disconnect_timeout,
             secure,
             local_addr,
+            h1_limits: H1Limits::default(),
+            h2_settings: H2Settings::default(),
+            defer_continue: false,
+            shutdown_signal: None,
             date_service: DateService::new(),
         }))
     }
 
+    /// Sets limits on the size of HTTP/1 request heads.
+    pub fn with_h1_limits(self, h1_limits: H1Limits) -> Self {
+        self.update(|inner| inner.h1_limits = h1_limits)
+    }
+
+    /// Sets settings for HTTP/2 connections.
+    pub fn with_h2_settings(self, h2_settings: H2Settings) -> Self {
+        self.update(|inner| inner.h2_settings = h2_settings)
+    }
+
+    /// Sets whether `100 Continue` responses are deferred until the request payload is first read.
+    pub fn with_defer_continue(self, defer_continue: bool) -> Self {
+        self.update(|inner| inner.defer_continue = defer_continue)
+    }
+
+    /// Sets the signal that starts a graceful shutdown of HTTP/2 connections.
+    pub(crate) fn with_shutdown_signal(self, signal: Option<ShutdownSignal>) -> Self {
+        self.update(|inner| inner.shutdown_signal = signal)
+    }
+
+    fn update(mut self, f: impl FnOnce(&mut Inner)) -> Self {
+        if Rc::get_mut(&mut self.0).is_none() {
+            // config is shared; construct a new one with the same settings
+            let mut config = ServiceConfig::new(
+                self.0.keep_alive,
+                self.0.client_request_timeout,
+                self.0.client_disconnect_timeout,
+                self.0.secure,
+                self.0.local_addr,
+            );
+
+            let inner = Rc::get_mut(&mut config.0).unwrap();
+            inner.h1_limits = self.0.h1_limits;
+            inner.h2_settings = self.0.h2_settings;
+            inner.defer_continue = self.0.defer_continue;
+            inner.shutdown_signal = self.0.shutdown_signal.clone();
+
+            self = config;
+        }
+
+        f(Rc::get_mut(&mut self.0).unwrap());
+        self
+    }
+
     /// Returns `true` if connection is secure (i.e., using TLS / HTTPS).
     #[inline]
     pub fn secure(&self) -> bool {
@@ -73,6 +387,30 @@ impl ServiceConfig {
         self.0.keep_alive
     }
 
+    /// Limits on the size of HTTP/1 request heads.
+    #[inline]
+    pub fn h1_limits(&self) -> H1Limits {
+        self.0.h1_limits
+    }
+
+    /// Settings for HTTP/2 connections.
+    #[inline]
+    pub fn h2_settings(&self) -> H2Settings {
+        self.0.h2_settings
+    }
+
+    /// Returns `true` if `100 Continue` responses are deferred until the request payload is first
+    /// read.
+    #[inline]
+    pub fn defer_continue(&self) -> bool {
+        self.0.defer_continue
+    }
+
+    #[allow(unused)] // used with `http2` feature flag
+    pub(crate) fn shutdown_signal(&self) -> Option<&ShutdownSignal> {
+        self.0.shutdown_signal.as_ref()
+    }
+
     /// Creates a time object representing the deadline for this connection's keep-alive period, if
     /// enabled.
     ///
@@ -0,0 +1,81 @@
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::Rc,
+    task::{Context, Waker},
+};
+
+use crate::header::HeaderMap;
+
+/// Handle for sending `103 Early Hints` informational responses before the final response.
+///
+/// Dispatchers that support informational responses add this handle to the extensions of each
+/// request. Hints are typically `Link` headers with `rel=preload` that allow clients to start
+/// fetching resources while the final response is still being prepared.
+///
+/// Currently, early hints are only sent to HTTP/1.1 clients.
+#[derive(Clone)]
+pub struct EarlyHints {
+    inner: Rc<RefCell<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    queue: Vec<HeaderMap>,
+    closed: bool,
+    waker: Option<Waker>,
+}
+
+impl EarlyHints {
+    pub(crate) fn new() -> Self {
+        Self {
+            inner: Rc::new(RefCell::new(Inner::default())),
+        }
+    }
+
+    /// Queues an informational response with the given headers.
+    ///
+    /// Returns false if the final response has already been sent, in which case the hints are
+    /// discarded.
+    pub fn send(&self, headers: HeaderMap) -> bool {
+        let mut inner = self.inner.borrow_mut();
+
+        if inner.closed {
+            return false;
+        }
+
+        inner.queue.push(headers);
+
+        if let Some(waker) = inner.waker.as_ref() {
+            waker.wake_by_ref();
+        }
+
+        true
+    }
+
+    /// Registers the dispatcher task to be woken when hints are queued from another task.
+    pub(crate) fn register(&self, cx: &Context<'_>) {
+        self.inner.borrow_mut().waker = Some(cx.waker().clone());
+    }
+
+    /// Takes all queued hints.
+    pub(crate) fn take(&self) -> Vec<HeaderMap> {
+        std::mem::take(&mut self.inner.borrow_mut().queue)
+    }
+
+    /// Stops accepting hints once the final response is sent.
+    pub(crate) fn close(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.closed = true;
+        inner.queue.clear();
+        inner.waker = None;
+    }
+}
+
+impl fmt::Debug for EarlyHints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EarlyHints")
+            .field("closed", &self.inner.borrow().closed)
+            .finish_non_exhaustive()
+    }
+}
@@ -22,7 +22,7 @@ use zstd::stream::write::Encoder as ZstdEncoder;
 use super::Writer;
 use crate::{
     body::{self, BodySize, MessageBody},
-    header::{self, ContentEncoding, HeaderValue, CONTENT_ENCODING},
+    header::{self, ContentEncoding, HeaderMap, HeaderValue, CONTENT_ENCODING},
     ResponseHead, StatusCode,
 };
 
@@ -141,6 +141,18 @@ where
         }
     }
 
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        match self.project() {
+            EncoderBodyProj::None { .. } | EncoderBodyProj::Full { .. } => Poll::Ready(Ok(None)),
+            EncoderBodyProj::Stream { body } => body
+                .poll_trailers(cx)
+                .map_err(|err| EncoderError::Body(err.into())),
+        }
+    }
+
     #[inline]
     fn try_into_bytes(self) -> Result<Bytes, Self>
     where
@@ -243,6 +255,14 @@ where
         }
     }
 
+    #[inline]
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().body.poll_trailers(cx)
+    }
+
     #[inline]
     fn try_into_bytes(mut self) -> Result<Bytes, Self>
     where
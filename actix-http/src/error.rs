@@ -179,6 +179,18 @@ pub enum ParseError {
     #[display(fmt = "message head is too large")]
     TooLarge,
 
+    /// A message head contains more headers than allowed.
+    #[display(fmt = "message head contains too many headers")]
+    TooManyHeaders,
+
+    /// A header value is larger than allowed.
+    #[display(fmt = "header value is too large")]
+    HeaderValueTooLarge,
+
+    /// A request line is longer than allowed.
+    #[display(fmt = "request line is too long")]
+    RequestLineTooLong,
+
     /// A message reached EOF, but is not complete.
     #[display(fmt = "message is incomplete")]
     Incomplete,
@@ -233,7 +245,7 @@ impl From<httparse::Error> for ParseError {
             | httparse::Error::NewLine
             | httparse::Error::Token => ParseError::Header,
             httparse::Error::Status => ParseError::Status,
-            httparse::Error::TooManyHeaders => ParseError::TooLarge,
+            httparse::Error::TooManyHeaders => ParseError::TooManyHeaders,
             httparse::Error::Version => ParseError::Version,
         }
     }
@@ -276,6 +288,11 @@ pub enum PayloadError {
     #[display(fmt = "{}", _0)]
     Http2Payload(::h2::Error),
 
+    /// HTTP/3 payload error.
+    #[cfg(feature = "http3")]
+    #[display(fmt = "{}", _0)]
+    Http3Payload(::h3::error::StreamError),
+
     /// Generic I/O error.
     #[display(fmt = "{}", _0)]
     Io(io::Error),
@@ -291,6 +308,8 @@ impl std::error::Error for PayloadError {
             PayloadError::UnknownLength => None,
             #[cfg(feature = "http2")]
             PayloadError::Http2Payload(err) => Some(err),
+            #[cfg(feature = "http3")]
+            PayloadError::Http3Payload(err) => Some(err),
             PayloadError::Io(err) => Some(err),
         }
     }
@@ -303,6 +322,13 @@ impl From<::h2::Error> for PayloadError {
     }
 }
 
+#[cfg(feature = "http3")]
+impl From<::h3::error::StreamError> for PayloadError {
+    fn from(err: ::h3::error::StreamError) -> Self {
+        PayloadError::Http3Payload(err)
+    }
+}
+
 impl From<Option<io::Error>> for PayloadError {
     fn from(err: Option<io::Error>) -> Self {
         PayloadError::Incomplete(err)
@@ -350,6 +376,11 @@ pub enum DispatchError {
     #[cfg(feature = "http2")]
     H2(h2::Error),
 
+    /// HTTP/3 error.
+    #[display(fmt = "{}", _0)]
+    #[cfg(feature = "http3")]
+    H3(::h3::error::ConnectionError),
+
     /// The first request did not complete within the specified timeout.
     #[display(fmt = "request did not complete within the specified timeout")]
     SlowRequestTimeout,
@@ -378,6 +409,9 @@ impl StdError for DispatchError {
             #[cfg(feature = "http2")]
             DispatchError::H2(err) => Some(err),
 
+            #[cfg(feature = "http3")]
+            DispatchError::H3(err) => Some(err),
+
             _ => None,
         }
     }
@@ -482,7 +516,7 @@ mod tests {
         from!(httparse::Error::NewLine => ParseError::Header);
         from!(httparse::Error::Status => ParseError::Status);
         from!(httparse::Error::Token => ParseError::Header);
-        from!(httparse::Error::TooManyHeaders => ParseError::TooLarge);
+        from!(httparse::Error::TooManyHeaders => ParseError::TooManyHeaders);
         from!(httparse::Error::Version => ParseError::Version);
     }
 }
@@ -1,8 +1,11 @@
 use std::{io, task::Poll};
 
 use bytes::{Buf as _, Bytes, BytesMut};
+use http::header::{self, HeaderName, HeaderValue};
 use tracing::{debug, trace};
 
+use crate::{header::HeaderMap, H1Limits};
+
 macro_rules! byte (
     ($rdr:ident) => ({
         if $rdr.len() > 0 {
@@ -24,8 +27,7 @@ pub(super) enum ChunkedState {
     Body,
     BodyCr,
     BodyLf,
-    EndCr,
-    EndLf,
+    Trailers,
     End,
 }
 
@@ -35,6 +37,8 @@ impl ChunkedState {
         body: &mut BytesMut,
         size: &mut u64,
         buf: &mut Option<Bytes>,
+        limits: &H1Limits,
+        trailers: &mut Option<HeaderMap>,
     ) -> Poll<Result<ChunkedState, io::Error>> {
         use self::ChunkedState::*;
         match *self {
@@ -45,8 +49,7 @@ impl ChunkedState {
             Body => ChunkedState::read_body(body, size, buf),
             BodyCr => ChunkedState::read_body_cr(body),
             BodyLf => ChunkedState::read_body_lf(body),
-            EndCr => ChunkedState::read_end_cr(body),
-            EndLf => ChunkedState::read_end_lf(body),
+            Trailers => ChunkedState::read_trailers(body, limits, trailers),
             End => Poll::Ready(Ok(ChunkedState::End)),
         }
     }
@@ -112,7 +115,7 @@ impl ChunkedState {
     fn read_size_lf(rdr: &mut BytesMut, size: u64) -> Poll<Result<ChunkedState, io::Error>> {
         match byte!(rdr) {
             b'\n' if size > 0 => Poll::Ready(Ok(ChunkedState::Body)),
-            b'\n' if size == 0 => Poll::Ready(Ok(ChunkedState::EndCr)),
+            b'\n' if size == 0 => Poll::Ready(Ok(ChunkedState::Trailers)),
             _ => Poll::Ready(Err(io::Error::new(
                 io::ErrorKind::InvalidInput,
                 "Invalid chunk size LF",
@@ -166,36 +169,81 @@ impl ChunkedState {
             ))),
         }
     }
-    fn read_end_cr(rdr: &mut BytesMut) -> Poll<Result<ChunkedState, io::Error>> {
-        match byte!(rdr) {
-            b'\r' => Poll::Ready(Ok(ChunkedState::EndLf)),
-            _ => Poll::Ready(Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Invalid chunk end CR",
-            ))),
+    fn read_trailers(
+        rdr: &mut BytesMut,
+        limits: &H1Limits,
+        trailers: &mut Option<HeaderMap>,
+    ) -> Poll<Result<ChunkedState, io::Error>> {
+        // fast path for the common case of an empty trailer section
+        if rdr.starts_with(b"\r\n") {
+            rdr.advance(2);
+            return Poll::Ready(Ok(ChunkedState::End));
         }
-    }
-    fn read_end_lf(rdr: &mut BytesMut) -> Poll<Result<ChunkedState, io::Error>> {
-        match byte!(rdr) {
-            b'\n' => Poll::Ready(Ok(ChunkedState::End)),
-            _ => Poll::Ready(Err(io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "Invalid chunk end LF",
-            ))),
+
+        let mut parsed = vec![httparse::EMPTY_HEADER; limits.max_headers];
+
+        let (len, fields) = match httparse::parse_headers(rdr, &mut parsed) {
+            Ok(httparse::Status::Complete((len, _))) if len > limits.max_trailer_size => {
+                return Poll::Ready(Err(trailers_too_large()));
+            }
+            Ok(httparse::Status::Complete(res)) => res,
+            Ok(httparse::Status::Partial) if rdr.len() >= limits.max_trailer_size => {
+                return Poll::Ready(Err(trailers_too_large()));
+            }
+            Ok(httparse::Status::Partial) => return Poll::Pending,
+            Err(err) => {
+                debug!("invalid chunked trailer section: {}", err);
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Invalid chunked trailers",
+                )));
+            }
+        };
+
+        let mut map = HeaderMap::with_capacity(fields.len());
+
+        for field in fields {
+            let name = HeaderName::from_bytes(field.name.as_bytes());
+            let value = HeaderValue::from_bytes(field.value);
+
+            match (name, value) {
+                // framing fields are not allowed in trailers and are discarded
+                (Ok(header::CONTENT_LENGTH | header::TRANSFER_ENCODING), _) => {}
+                (Ok(name), Ok(value)) => map.append(name, value),
+                _ => {
+                    return Poll::Ready(Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Invalid chunked trailer field",
+                    )));
+                }
+            }
         }
+
+        rdr.advance(len);
+        *trailers = Some(map);
+
+        Poll::Ready(Ok(ChunkedState::End))
     }
 }
 
+fn trailers_too_large() -> io::Error {
+    debug!("chunked trailer section exceeds maximum size");
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Chunked trailers exceed maximum size",
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use actix_codec::Decoder as _;
     use bytes::{Bytes, BytesMut};
-    use http::Method;
+    use http::{header, Method};
 
     use crate::{
         error::ParseError,
-        h1::decoder::{MessageDecoder, PayloadItem},
-        HttpMessage as _, Request,
+        h1::decoder::{MessageDecoder, PayloadDecoder, PayloadItem},
+        H1Limits, HttpMessage as _, Request,
     };
 
     macro_rules! parse_ready {
@@ -362,6 +410,56 @@ mod tests {
         assert!(pl.decode(&mut buf).unwrap().unwrap().eof());
     }
 
+    #[test]
+    fn test_http_request_chunked_payload_trailers() {
+        let mut buf = BytesMut::from(
+            "POST /test HTTP/1.1\r\n\
+             transfer-encoding: chunked\r\n\r\n",
+        );
+        let mut reader = MessageDecoder::<Request>::default();
+        let (_req, pl) = reader.decode(&mut buf).unwrap().unwrap();
+        let mut pl = pl.unwrap();
+
+        buf.extend(b"4\r\ndata\r\n0\r\nx-checksum: abc\r\n");
+        assert_eq!(
+            pl.decode(&mut buf).unwrap().unwrap().chunk().as_ref(),
+            b"data"
+        );
+        assert!(pl.decode(&mut buf).unwrap().is_none());
+
+        buf.extend(b"content-length: 4\r\nx-count: 1\r\n\r\nGET");
+        assert!(pl.decode(&mut buf).unwrap().unwrap().eof());
+        assert_eq!(&buf[..], b"GET");
+
+        let trailers = pl.take_trailers().unwrap();
+        assert_eq!(trailers.len(), 2);
+        assert_eq!(trailers.get("x-checksum").unwrap(), "abc");
+        assert_eq!(trailers.get("x-count").unwrap(), "1");
+        assert!(!trailers.contains_key(header::CONTENT_LENGTH));
+    }
+
+    #[test]
+    fn test_http_request_chunked_payload_trailers_limits() {
+        let limits = H1Limits::new().max_trailer_size(16);
+
+        let mut buf = BytesMut::from("0\r\nx-checksum: 0123456789abcdef\r\n\r\n");
+        let mut pl = PayloadDecoder::chunked(limits);
+        pl.decode(&mut buf).unwrap_err();
+
+        let mut buf = BytesMut::from("0\r\nx-checksum: 01234567");
+        let mut pl = PayloadDecoder::chunked(limits);
+        pl.decode(&mut buf).unwrap_err();
+
+        let mut buf = BytesMut::from("0\r\nx-a: 1\r\nx-b: 2\r\n\r\n");
+        let mut pl = PayloadDecoder::chunked(H1Limits::new().max_headers(1));
+        pl.decode(&mut buf).unwrap_err();
+
+        let mut buf = BytesMut::from("0\r\nx-a: 1\r\n\r\n");
+        let mut pl = PayloadDecoder::chunked(limits);
+        assert!(pl.decode(&mut buf).unwrap().unwrap().eof());
+        assert_eq!(pl.take_trailers().unwrap().get("x-a").unwrap(), "1");
+    }
+
     #[test]
     fn chunk_extension_quoted() {
         let mut buf = BytesMut::from(
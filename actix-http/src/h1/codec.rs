@@ -9,7 +9,12 @@ use super::{
     decoder::{self, PayloadDecoder, PayloadItem, PayloadType},
     encoder, Message, MessageType,
 };
-use crate::{body::BodySize, error::ParseError, ConnectionType, Request, Response, ServiceConfig};
+use crate::{
+    body::BodySize,
+    error::ParseError,
+    header::{self, HeaderMap},
+    ConnectionType, Request, Response, ServiceConfig,
+};
 
 bitflags! {
     #[derive(Debug, Clone, Copy)]
@@ -17,6 +22,7 @@ bitflags! {
         const HEAD               = 0b0000_0001;
         const KEEP_ALIVE_ENABLED = 0b0000_0010;
         const STREAM             = 0b0000_0100;
+        const TRAILERS           = 0b0000_1000;
     }
 }
 
@@ -25,6 +31,7 @@ pub struct Codec {
     config: ServiceConfig,
     decoder: decoder::MessageDecoder<Request>,
     payload: Option<PayloadDecoder>,
+    trailers: Option<HeaderMap>,
     version: Version,
     conn_type: ConnectionType,
 
@@ -59,10 +66,11 @@ impl Codec {
         };
 
         Codec {
+            decoder: decoder::MessageDecoder::new(config.h1_limits()),
             config,
             flags,
-            decoder: decoder::MessageDecoder::default(),
             payload: None,
+            trailers: None,
             version: Version::HTTP_11,
             conn_type: ConnectionType::Close,
             encoder: encoder::MessageEncoder::default(),
@@ -103,6 +111,37 @@ impl Codec {
     pub fn config(&self) -> &ServiceConfig {
         &self.config
     }
+
+    /// Takes the trailer fields received at the end of the last request's chunked payload.
+    pub(crate) fn take_trailers(&mut self) -> Option<HeaderMap> {
+        self.trailers.take()
+    }
+
+    /// Encodes the end of the response body, followed by `trailers` if they can be sent.
+    ///
+    /// Trailers are only sent when the body is chunked and the client sent `TE: trailers`.
+    pub(crate) fn encode_trailers(
+        &mut self,
+        trailers: Option<&HeaderMap>,
+        dst: &mut BytesMut,
+    ) -> io::Result<()> {
+        match trailers {
+            Some(trailers) if self.flags.contains(Flags::TRAILERS) => {
+                self.encoder.encode_trailers(trailers, dst)
+            }
+            _ => self.encoder.encode_eof(dst),
+        }
+    }
+}
+
+/// Returns true if the `TE` header of a request contains `trailers`.
+fn accepts_trailers(headers: &HeaderMap) -> bool {
+    headers.get_all(header::TE).any(|val| {
+        val.to_str().is_ok_and(|val| {
+            val.split(',')
+                .any(|item| item.trim().eq_ignore_ascii_case("trailers"))
+        })
+    })
 }
 
 impl Decoder for Codec {
@@ -114,7 +153,7 @@ impl Decoder for Codec {
             Ok(match payload.decode(src)? {
                 Some(PayloadItem::Chunk(chunk)) => Some(Message::Chunk(Some(chunk))),
                 Some(PayloadItem::Eof) => {
-                    self.payload.take();
+                    self.trailers = self.payload.take().and_then(|mut pl| pl.take_trailers());
                     Some(Message::Chunk(None))
                 }
                 None => None,
@@ -122,6 +161,8 @@ impl Decoder for Codec {
         } else if let Some((req, payload)) = self.decoder.decode(src)? {
             let head = req.head();
             self.flags.set(Flags::HEAD, head.method == Method::HEAD);
+            self.flags
+                .set(Flags::TRAILERS, accepts_trailers(&head.headers));
             self.version = head.version;
             self.conn_type = head.connection_type();
 
@@ -9,13 +9,20 @@ use http::{
 use tracing::{debug, error, trace};
 
 use super::chunked::ChunkedState;
-use crate::{error::ParseError, header::HeaderMap, ConnectionType, Request, ResponseHead};
+use crate::{
+    error::ParseError, header::HeaderMap, ConnectionType, H1Limits, Request, ResponseHead,
+};
 
 pub(crate) const MAX_BUFFER_SIZE: usize = 131_072;
+
+/// Number of headers that can be parsed without allocating.
 const MAX_HEADERS: usize = 96;
 
 /// Incoming message decoder
-pub(crate) struct MessageDecoder<T: MessageType>(PhantomData<T>);
+pub(crate) struct MessageDecoder<T: MessageType> {
+    limits: H1Limits,
+    _message: PhantomData<T>,
+}
 
 #[derive(Debug)]
 /// Incoming request type
@@ -27,7 +34,16 @@ pub(crate) enum PayloadType {
 
 impl<T: MessageType> Default for MessageDecoder<T> {
     fn default() -> Self {
-        MessageDecoder(PhantomData)
+        MessageDecoder::new(H1Limits::default())
+    }
+}
+
+impl<T: MessageType> MessageDecoder<T> {
+    pub(crate) fn new(limits: H1Limits) -> Self {
+        MessageDecoder {
+            limits,
+            _message: PhantomData,
+        }
     }
 }
 
@@ -36,7 +52,7 @@ impl<T: MessageType> Decoder for MessageDecoder<T> {
     type Error = ParseError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
-        T::decode(src)
+        T::decode(src, &self.limits)
     }
 }
 
@@ -70,13 +86,17 @@ pub(crate) trait MessageType: Sized {
 
     fn headers_mut(&mut self) -> &mut HeaderMap;
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError>;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &H1Limits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError>;
 
     fn set_headers(
         &mut self,
         slice: &Bytes,
         raw_headers: &[HeaderIndex],
         version: Version,
+        limits: &H1Limits,
     ) -> Result<PayloadLength, ParseError> {
         let mut ka = None;
         let mut has_upgrade_websocket = false;
@@ -198,7 +218,7 @@ pub(crate) trait MessageType: Sized {
         if chunked {
             // Chunked encoding
             Ok(PayloadLength::Payload(PayloadType::Payload(
-                PayloadDecoder::chunked(),
+                PayloadDecoder::chunked(*limits),
             )))
         } else if has_upgrade_websocket {
             Ok(PayloadLength::UpgradeWebSocket)
@@ -228,22 +248,46 @@ impl MessageType for Request {
         &mut self.head_mut().headers
     }
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError> {
-        let mut headers: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &H1Limits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError> {
+        check_request_line(src, limits.max_request_line_size)?;
+
+        let mut headers_stack: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+        let mut headers_heap = Vec::new();
+        let headers = buffer(
+            &mut headers_stack,
+            &mut headers_heap,
+            limits.max_headers,
+            EMPTY_HEADER_INDEX,
+        );
 
         let (len, method, uri, ver, h_len) = {
             // SAFETY:
             // Create an uninitialized array of `MaybeUninit`. The `assume_init` is safe because the
             // type we are claiming to have initialized here is a bunch of `MaybeUninit`s, which
             // do not require initialization.
-            let mut parsed = unsafe {
+            let mut parsed_stack = unsafe {
                 MaybeUninit::<[MaybeUninit<httparse::Header<'_>>; MAX_HEADERS]>::uninit()
                     .assume_init()
             };
+            let mut parsed_heap = Vec::new();
+            let parsed = buffer(
+                &mut parsed_stack,
+                &mut parsed_heap,
+                limits.max_headers,
+                MaybeUninit::uninit(),
+            );
 
             let mut req = httparse::Request::new(&mut []);
 
-            match req.parse_with_uninit_headers(src, &mut parsed)? {
+            match req.parse_with_uninit_headers(src, parsed)? {
+                httparse::Status::Complete(len) if len > limits.max_head_size => {
+                    trace!("request head exceeds maximum size");
+                    return Err(ParseError::TooLarge);
+                }
+
                 httparse::Status::Complete(len) => {
                     let method = Method::from_bytes(req.method.unwrap().as_bytes())
                         .map_err(|_| ParseError::Method)?;
@@ -253,14 +297,14 @@ impl MessageType for Request {
                     } else {
                         Version::HTTP_10
                     };
-                    HeaderIndex::record(src, req.headers, &mut headers);
+                    HeaderIndex::record(src, req.headers, headers);
 
                     (len, method, uri, version, req.headers.len())
                 }
 
                 httparse::Status::Partial => {
-                    return if src.len() >= MAX_BUFFER_SIZE {
-                        trace!("MAX_BUFFER_SIZE unprocessed data reached, closing");
+                    return if src.len() >= limits.max_head_size {
+                        trace!("maximum head size of unprocessed data reached, closing");
                         Err(ParseError::TooLarge)
                     } else {
                         // Return None to notify more read are needed for parsing request
@@ -270,10 +314,19 @@ impl MessageType for Request {
             }
         };
 
+        if headers[..h_len]
+            .iter()
+            .any(|idx| idx.value.1 - idx.value.0 > limits.max_header_value_size)
+        {
+            trace!("request header value exceeds maximum size");
+            return Err(ParseError::HeaderValueTooLarge);
+        }
+
         let mut msg = Request::new();
 
         // convert headers
-        let mut length = msg.set_headers(&src.split_to(len).freeze(), &headers[..h_len], ver)?;
+        let mut length =
+            msg.set_headers(&src.split_to(len).freeze(), &headers[..h_len], ver, limits)?;
 
         // disallow HTTP/1.0 POST requests that do not contain a Content-Length headers
         // see https://datatracker.ietf.org/doc/html/rfc1945#section-7.2.2
@@ -327,25 +380,42 @@ impl MessageType for ResponseHead {
         &mut self.headers
     }
 
-    fn decode(src: &mut BytesMut) -> Result<Option<(Self, PayloadType)>, ParseError> {
-        let mut headers: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+    fn decode(
+        src: &mut BytesMut,
+        limits: &H1Limits,
+    ) -> Result<Option<(Self, PayloadType)>, ParseError> {
+        let mut headers_stack: [HeaderIndex; MAX_HEADERS] = EMPTY_HEADER_INDEX_ARRAY;
+        let mut headers_heap = Vec::new();
+        let headers = buffer(
+            &mut headers_stack,
+            &mut headers_heap,
+            limits.max_headers,
+            EMPTY_HEADER_INDEX,
+        );
 
         let (len, ver, status, h_len) = {
             // SAFETY:
             // Create an uninitialized array of `MaybeUninit`. The `assume_init` is safe because the
             // type we are claiming to have initialized here is a bunch of `MaybeUninit`s, which
             // do not require initialization.
-            let mut parsed = unsafe {
+            let mut parsed_stack = unsafe {
                 MaybeUninit::<[MaybeUninit<httparse::Header<'_>>; MAX_HEADERS]>::uninit()
                     .assume_init()
             };
+            let mut parsed_heap = Vec::new();
+            let parsed = buffer(
+                &mut parsed_stack,
+                &mut parsed_heap,
+                limits.max_headers,
+                MaybeUninit::uninit(),
+            );
 
             let mut res = httparse::Response::new(&mut []);
 
             let mut config = httparse::ParserConfig::default();
             config.allow_spaces_after_header_name_in_responses(true);
 
-            match config.parse_response_with_uninit_headers(&mut res, src, &mut parsed)? {
+            match config.parse_response_with_uninit_headers(&mut res, src, parsed)? {
                 httparse::Status::Complete(len) => {
                     let version = if res.version.unwrap() == 1 {
                         Version::HTTP_11
@@ -355,14 +425,14 @@ impl MessageType for ResponseHead {
 
                     let status =
                         StatusCode::from_u16(res.code.unwrap()).map_err(|_| ParseError::Status)?;
-                    HeaderIndex::record(src, res.headers, &mut headers);
+                    HeaderIndex::record(src, res.headers, headers);
 
                     (len, version, status, res.headers.len())
                 }
 
                 httparse::Status::Partial => {
-                    return if src.len() >= MAX_BUFFER_SIZE {
-                        error!("MAX_BUFFER_SIZE unprocessed data reached, closing");
+                    return if src.len() >= limits.max_head_size {
+                        error!("maximum head size of unprocessed data reached, closing");
                         Err(ParseError::TooLarge)
                     } else {
                         Ok(None)
@@ -375,7 +445,8 @@ impl MessageType for ResponseHead {
         msg.version = ver;
 
         // convert headers
-        let mut length = msg.set_headers(&src.split_to(len).freeze(), &headers[..h_len], ver)?;
+        let mut length =
+            msg.set_headers(&src.split_to(len).freeze(), &headers[..h_len], ver, limits)?;
 
         // Remove CL value if 0 now that all headers and HTTP/1.0 special cases are processed.
         // Protects against some request smuggling attacks.
@@ -404,6 +475,44 @@ impl MessageType for ResponseHead {
     }
 }
 
+/// Returns a buffer of `len` copies of `val`, using `stack` unless it is too small.
+fn buffer<'a, T: Copy>(
+    stack: &'a mut [T],
+    heap: &'a mut Vec<T>,
+    len: usize,
+    val: T,
+) -> &'a mut [T] {
+    if len <= stack.len() {
+        &mut stack[..len]
+    } else {
+        *heap = vec![val; len];
+        heap
+    }
+}
+
+/// Checks that the (possibly partial) request line at the start of `src` is not too long.
+fn check_request_line(src: &[u8], max_size: usize) -> Result<(), ParseError> {
+    if max_size == usize::MAX {
+        return Ok(());
+    }
+
+    // only the part of the buffer that could contain a valid request line needs to be scanned
+    let scan = &src[..src.len().min(max_size.saturating_add(2))];
+
+    let size = match scan.iter().position(|&byte| byte == b'\n') {
+        Some(end) if end > 0 && scan[end - 1] == b'\r' => end - 1,
+        Some(end) => end,
+        None => scan.len(),
+    };
+
+    if size > max_size {
+        trace!("request line exceeds maximum size");
+        Err(ParseError::RequestLineTooLong)
+    } else {
+        Ok(())
+    }
+}
+
 #[derive(Clone, Copy)]
 pub(crate) struct HeaderIndex {
     pub(crate) name: (usize, usize),
@@ -446,7 +555,7 @@ pub enum PayloadItem {
 /// Decoder that can handle different payload types.
 ///
 /// If a message body does not use `Transfer-Encoding`, it should include a `Content-Length`.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct PayloadDecoder {
     kind: Kind,
 }
@@ -459,10 +568,15 @@ impl PayloadDecoder {
         }
     }
 
-    /// Constructs a chunked encoding decoder.
-    pub fn chunked() -> PayloadDecoder {
+    /// Constructs a chunked encoding decoder whose trailer section is restricted by `limits`.
+    pub fn chunked(limits: H1Limits) -> PayloadDecoder {
         PayloadDecoder {
-            kind: Kind::Chunked(ChunkedState::Size, 0),
+            kind: Kind::Chunked {
+                state: ChunkedState::Size,
+                size: 0,
+                limits,
+                trailers: None,
+            },
         }
     }
 
@@ -470,15 +584,30 @@ impl PayloadDecoder {
     pub fn eof() -> PayloadDecoder {
         PayloadDecoder { kind: Kind::Eof }
     }
+
+    /// Takes the trailer fields decoded at the end of a chunked payload.
+    pub(crate) fn take_trailers(&mut self) -> Option<HeaderMap> {
+        match self.kind {
+            Kind::Chunked {
+                ref mut trailers, ..
+            } => trailers.take(),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 enum Kind {
     /// A reader used when a `Content-Length` header is passed with a positive integer.
     Length(u64),
 
     /// A reader used when `Transfer-Encoding` is `chunked`.
-    Chunked(ChunkedState, u64),
+    Chunked {
+        state: ChunkedState,
+        size: u64,
+        limits: H1Limits,
+        trailers: Option<HeaderMap>,
+    },
 
     /// A reader used for responses that don't indicate a length or chunked.
     ///
@@ -524,12 +653,17 @@ impl Decoder for PayloadDecoder {
                 }
             }
 
-            Kind::Chunked(ref mut state, ref mut size) => {
+            Kind::Chunked {
+                ref mut state,
+                ref mut size,
+                ref limits,
+                ref mut trailers,
+            } => {
                 loop {
                     let mut buf = None;
 
                     // advances the chunked state
-                    *state = match state.step(src, size, &mut buf) {
+                    *state = match state.step(src, size, &mut buf, limits, trailers) {
                         Poll::Pending => return Ok(None),
                         Poll::Ready(Ok(state)) => state,
                         Poll::Ready(Err(err)) => return Err(err),
@@ -965,7 +1099,9 @@ mod tests {
         let (_req, pl) = reader.decode(&mut buf).unwrap().unwrap();
         assert!(matches!(
             pl,
-            PayloadType::Payload(pl) if pl == PayloadDecoder::length(11)
+            PayloadType::Payload(PayloadDecoder {
+                kind: Kind::Length(11)
+            })
         ));
     }
 
@@ -1182,4 +1318,68 @@ mod tests {
         let chunk = pl.decode(&mut buf).unwrap().unwrap();
         assert_eq!(chunk, PayloadItem::Chunk(Bytes::from_static(b"0\r\n")));
     }
+
+    #[test]
+    fn head_limits() {
+        let decode = |limits: H1Limits, head: &str| {
+            MessageDecoder::<Request>::new(limits).decode(&mut BytesMut::from(head))
+        };
+
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            (0..120)
+                .map(|n| format!("x-header-{n}: {n}\r\n"))
+                .collect::<String>()
+        );
+
+        assert!(matches!(
+            decode(H1Limits::default(), &many_headers),
+            Err(ParseError::TooManyHeaders)
+        ));
+        let (req, _) = decode(H1Limits::new().max_headers(128), &many_headers)
+            .unwrap()
+            .unwrap();
+        assert_eq!(req.headers().len(), 120);
+        assert!(matches!(
+            decode(
+                H1Limits::new().max_headers(2),
+                "GET / HTTP/1.1\r\na: 1\r\nb: 2\r\nc: 3\r\n\r\n"
+            ),
+            Err(ParseError::TooManyHeaders)
+        ));
+
+        let limits = H1Limits::new().max_request_line_size(18);
+        assert!(decode(limits, "GET /1234 HTTP/1.1\r\n\r\n")
+            .unwrap()
+            .is_some());
+        assert!(matches!(
+            decode(limits, "GET /12345 HTTP/1.1\r\n\r\n"),
+            Err(ParseError::RequestLineTooLong)
+        ));
+        assert!(matches!(
+            decode(limits, "GET /1234567890123456789"),
+            Err(ParseError::RequestLineTooLong)
+        ));
+        assert!(decode(limits, "GET /1234").unwrap().is_none());
+
+        let limits = H1Limits::new().max_header_value_size(4);
+        assert!(decode(limits, "GET / HTTP/1.1\r\na: 1234\r\n\r\n")
+            .unwrap()
+            .is_some());
+        assert!(matches!(
+            decode(limits, "GET / HTTP/1.1\r\na: 12345\r\n\r\n"),
+            Err(ParseError::HeaderValueTooLarge)
+        ));
+
+        let limits = H1Limits::new().max_head_size(32);
+        assert!(matches!(
+            decode(limits, "GET / HTTP/1.1\r\nx-long: 0123456789\r\n\r\n"),
+            Err(ParseError::TooLarge)
+        ));
+        assert!(matches!(
+            decode(limits, "GET / HTTP/1.1\r\nx-long: 0123456789"),
+            Err(ParseError::TooLarge)
+        ));
+        assert!(decode(limits, "GET / HTTP/1.1\r\n\r\n").unwrap().is_some());
+    }
 }
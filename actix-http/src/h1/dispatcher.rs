@@ -6,6 +6,7 @@ use std::{
     pin::Pin,
     rc::Rc,
     task::{Context, Poll},
+    time::Instant,
 };
 
 use actix_codec::{Framed, FramedParts};
@@ -31,7 +32,8 @@ use crate::{
     config::ServiceConfig,
     error::{DispatchError, ParseError, PayloadError},
     service::HttpFlow,
-    Error, Extensions, OnConnectData, Request, Response, StatusCode,
+    ConnectionType, EarlyHints, Error, Extensions, HttpMessage as _, OnConnectData, Request,
+    Response, StatusCode, Version,
 };
 
 const LW_BUFFER_SIZE: usize = 1024;
@@ -58,6 +60,9 @@ bitflags! {
 
         /// Set if write-half is disconnected.
         const WRITE_DISCONNECT = 0b0010_0000;
+
+        /// Set when the response body is finished and its trailers are being polled.
+        const BODY_EOF         = 0b0100_0000;
     }
 }
 
@@ -157,6 +162,8 @@ pin_project! {
         pub(super) state: State<S, B, X>,
         // when Some(_) dispatcher is in state of receiving request payload
         payload: Option<PayloadSender>,
+        // when Some(_) service can send early hints before its response
+        early_hints: Option<EarlyHints>,
         messages: VecDeque<DispatcherMessage>,
 
         head_timer: TimerState,
@@ -257,11 +264,45 @@ where
         peer_addr: Option<net::SocketAddr>,
         conn_data: OnConnectData,
     ) -> Self {
+        Self::with_read_buf(
+            io,
+            flow,
+            config,
+            peer_addr,
+            conn_data,
+            BytesMut::with_capacity(HW_BUFFER_SIZE),
+            None,
+        )
+    }
+
+    /// Create HTTP/1 dispatcher for a connection that has already been read from.
+    pub(crate) fn with_read_buf(
+        io: T,
+        flow: Rc<HttpFlow<S, X, U>>,
+        config: ServiceConfig,
+        peer_addr: Option<net::SocketAddr>,
+        conn_data: OnConnectData,
+        read_buf: BytesMut,
+        head_deadline: Option<Instant>,
+    ) -> Self {
+        // a deadline for the first request head was already set while reading from the connection
+        let (flags, head_timer) = match head_deadline {
+            Some(deadline) => {
+                let mut timer = TimerState::new(true);
+                timer.set(sleep_until(deadline.into()), line!());
+                (Flags::STARTED, timer)
+            }
+            None => (
+                Flags::empty(),
+                TimerState::new(config.client_request_deadline().is_some()),
+            ),
+        };
+
         Dispatcher {
             inner: DispatcherState::Normal {
                 inner: InnerDispatcher {
                     flow,
-                    flags: Flags::empty(),
+                    flags,
                     peer_addr,
                     conn_data: conn_data.0.map(Rc::new),
                     config: config.clone(),
@@ -269,14 +310,15 @@ where
 
                     state: State::None,
                     payload: None,
+                    early_hints: None,
                     messages: VecDeque::new(),
 
-                    head_timer: TimerState::new(config.client_request_deadline().is_some()),
+                    head_timer,
                     ka_timer: TimerState::new(config.keep_alive().enabled()),
                     shutdown_timer: TimerState::new(config.client_disconnect_deadline().is_some()),
 
                     io: Some(io),
-                    read_buf: BytesMut::with_capacity(HW_BUFFER_SIZE),
+                    read_buf,
                     write_buf: BytesMut::with_capacity(HW_BUFFER_SIZE),
                     codec: Codec::new(config),
                 },
@@ -356,12 +398,27 @@ where
     }
 
     fn send_response_inner(
-        self: Pin<&mut Self>,
-        res: Response<()>,
+        mut self: Pin<&mut Self>,
+        mut res: Response<()>,
         body: &impl MessageBody,
     ) -> Result<BodySize, DispatchError> {
+        self.as_mut().write_informational();
+
         let this = self.project();
 
+        // no more early hints can be sent once the final response is written
+        if let Some(early_hints) = this.early_hints.take() {
+            early_hints.close();
+        }
+
+        if let Some(payload) = this.payload.as_mut() {
+            // payload was never read so the client is still waiting for `100 Continue`; it might
+            // or might not send the payload now so the connection can not be reused
+            if payload.cancel_continue() {
+                res.head_mut().set_connection_type(ConnectionType::Close);
+            }
+        }
+
         let size = body.size();
 
         this.codec
@@ -413,10 +470,62 @@ where
         Ok(())
     }
 
-    fn send_continue(self: Pin<&mut Self>) {
-        self.project()
-            .write_buf
-            .extend_from_slice(b"HTTP/1.1 100 Continue\r\n\r\n");
+    /// Sends `100 Continue` after the expect service accepted a request, unless configured to
+    /// wait until the service reads the request payload.
+    fn send_continue(self: Pin<&mut Self>, cx: &mut Context<'_>) {
+        let this = self.project();
+
+        match this.payload {
+            Some(payload) if this.codec.config().defer_continue() => payload.defer_continue(cx),
+            _ => this
+                .write_buf
+                .extend_from_slice(b"HTTP/1.1 100 Continue\r\n\r\n"),
+        }
+    }
+
+    /// Writes informational responses requested by the service while its response is pending:
+    /// a deferred `100 Continue` once the request payload is read and any queued early hints.
+    fn write_informational(self: Pin<&mut Self>) {
+        let this = self.project();
+
+        if let Some(payload) = this.payload.as_mut() {
+            if payload.take_continue() {
+                this.write_buf
+                    .extend_from_slice(b"HTTP/1.1 100 Continue\r\n\r\n");
+            }
+        }
+
+        if let Some(early_hints) = this.early_hints.as_ref() {
+            for headers in early_hints.take() {
+                this.write_buf
+                    .extend_from_slice(b"HTTP/1.1 103 Early Hints\r\n");
+
+                for (name, value) in headers.iter() {
+                    this.write_buf.extend_from_slice(name.as_str().as_bytes());
+                    this.write_buf.extend_from_slice(b": ");
+                    this.write_buf.extend_from_slice(value.as_bytes());
+                    this.write_buf.extend_from_slice(b"\r\n");
+                }
+
+                this.write_buf.extend_from_slice(b"\r\n");
+            }
+        }
+    }
+
+    /// Calls the service with a request, giving HTTP/1.1 requests a handle for sending early hints.
+    fn call_service(self: Pin<&mut Self>, req: Request, cx: &Context<'_>) {
+        let mut this = self.project();
+
+        // informational responses must not be sent to HTTP/1.0 clients
+        if req.version() == Version::HTTP_11 {
+            let early_hints = EarlyHints::new();
+            early_hints.register(cx);
+            req.extensions_mut().insert(early_hints.clone());
+            *this.early_hints = Some(early_hints);
+        }
+
+        let fut = this.flow.service.call(req);
+        this.state.set(State::ServiceCall { fut });
     }
 
     fn poll_response(
@@ -437,8 +546,7 @@ where
                             this.state.set(State::ExpectCall { fut });
                         } else {
                             // set InnerDispatcher state and continue loop to poll it
-                            let fut = this.flow.service.call(req);
-                            this.state.set(State::ServiceCall { fut });
+                            self.as_mut().call_service(req, cx);
                         };
                     }
 
@@ -480,6 +588,8 @@ where
                         // service call pending and could be waiting for more chunk messages
                         // (pipeline message limit and/or payload can_read limit)
                         Poll::Pending => {
+                            self.as_mut().write_informational();
+
                             // no new message is decoded and no new payload is fed
                             // nothing to do except waiting for new incoming data from client
                             if !self.as_mut().poll_request(cx)? {
@@ -494,22 +604,38 @@ where
                     // keep populate writer buffer until buffer size limit hit,
                     // get blocked or finished.
                     while this.write_buf.len() < super::payload::MAX_BUFFER_SIZE {
+                        if this.flags.contains(Flags::BODY_EOF) {
+                            let trailers = match body.as_mut().poll_trailers(cx) {
+                                Poll::Ready(Ok(trailers)) => trailers,
+                                Poll::Ready(Err(err)) => {
+                                    let err = err.into();
+                                    tracing::error!("Response trailers error: {err:?}");
+                                    this.flags.insert(Flags::FINISHED);
+                                    return Err(DispatchError::Body(err));
+                                }
+                                Poll::Pending => return Ok(PollResponse::DoNothing),
+                            };
+
+                            this.codec
+                                .encode_trailers(trailers.as_ref(), this.write_buf)?;
+
+                            // payload stream finished.
+                            // set state to None and handle next message
+                            this.state.set(State::None);
+                            this.flags.remove(Flags::BODY_EOF);
+                            this.flags.insert(Flags::FINISHED);
+
+                            continue 'res;
+                        }
+
                         match body.as_mut().poll_next(cx) {
                             Poll::Ready(Some(Ok(item))) => {
                                 this.codec
                                     .encode(Message::Chunk(Some(item)), this.write_buf)?;
                             }
 
-                            Poll::Ready(None) => {
-                                this.codec.encode(Message::Chunk(None), this.write_buf)?;
-
-                                // payload stream finished.
-                                // set state to None and handle next message
-                                this.state.set(State::None);
-                                this.flags.insert(Flags::FINISHED);
-
-                                continue 'res;
-                            }
+                            // poll trailers before finishing the response
+                            Poll::Ready(None) => this.flags.insert(Flags::BODY_EOF),
 
                             Poll::Ready(Some(Err(err))) => {
                                 let err = err.into();
@@ -574,10 +700,8 @@ where
                         // expect resolved. write continue to buffer and set InnerDispatcher state
                         // to service call.
                         Poll::Ready(Ok(req)) => {
-                            this.write_buf
-                                .extend_from_slice(b"HTTP/1.1 100 Continue\r\n\r\n");
-                            let fut = this.flow.service.call(req);
-                            this.state.set(State::ServiceCall { fut });
+                            self.as_mut().send_continue(cx);
+                            self.as_mut().call_service(req, cx);
                         }
 
                         // send expect error as response
@@ -611,8 +735,7 @@ where
                 this.state.set(State::ExpectCall { fut });
             } else {
                 // set dispatcher state to call service handler
-                let fut = this.flow.service.call(req);
-                this.state.set(State::ServiceCall { fut });
+                self.as_mut().call_service(req, cx);
             };
         };
 
@@ -623,11 +746,8 @@ where
                     match fut.poll(cx) {
                         // expect is resolved; continue loop and poll the service call branch.
                         Poll::Ready(Ok(req)) => {
-                            self.as_mut().send_continue();
-
-                            let mut this = self.as_mut().project();
-                            let fut = this.flow.service.call(req);
-                            this.state.set(State::ServiceCall { fut });
+                            self.as_mut().send_continue(cx);
+                            self.as_mut().call_service(req, cx);
 
                             continue;
                         }
@@ -659,7 +779,10 @@ where
                         }
 
                         // see the comment on ExpectCall state branch's Pending
-                        Poll::Pending => Ok(()),
+                        Poll::Pending => {
+                            self.as_mut().write_informational();
+                            Ok(())
+                        }
 
                         // see the comment on ExpectCall state branch's Ready(Err(_))
                         Poll::Ready(Err(err)) => {
@@ -758,6 +881,9 @@ where
 
                         Message::Chunk(None) => {
                             if let Some(mut payload) = this.payload.take() {
+                                if let Some(trailers) = this.codec.take_trailers() {
+                                    payload.feed_trailers(trailers);
+                                }
                                 payload.feed_eof();
                             } else {
                                 error!("Internal server error: unexpected eof");
@@ -784,22 +910,29 @@ where
                     break;
                 }
 
-                Err(ParseError::TooLarge) => {
-                    trace!("request head was too big; returning 431 response");
+                Err(
+                    err @ (ParseError::TooLarge
+                    | ParseError::TooManyHeaders
+                    | ParseError::HeaderValueTooLarge
+                    | ParseError::RequestLineTooLong),
+                ) => {
+                    trace!("request head exceeded limits: {}", &err);
 
                     if let Some(mut payload) = this.payload.take() {
                         payload.set_error(PayloadError::Overflow);
                     }
 
-                    // request heads that overflow buffer size return a 431 error
+                    // request heads that exceed limits return a 414 or 431 error
+                    let status = match err {
+                        ParseError::RequestLineTooLong => StatusCode::URI_TOO_LONG,
+                        _ => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                    };
+
                     this.messages
-                        .push_back(DispatcherMessage::Error(Response::with_body(
-                            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
-                            (),
-                        )));
+                        .push_back(DispatcherMessage::Error(Response::with_body(status, ())));
 
                     this.flags.insert(Flags::READ_DISCONNECT);
-                    *this.error = Some(ParseError::TooLarge.into());
+                    *this.error = Some(err.into());
 
                     break;
                 }
@@ -940,9 +1073,12 @@ where
 
         let mut read_some = false;
 
+        // allow reading request heads up to the configured maximum size
+        let max_buffer_size = MAX_BUFFER_SIZE.max(this.codec.config().h1_limits().max_head_size);
+
         loop {
             // Return early when read buf exceed decoder's max buffer size.
-            if this.read_buf.len() >= MAX_BUFFER_SIZE {
+            if this.read_buf.len() >= max_buffer_size {
                 // At this point it's not known IO stream is still scheduled to be waked up so
                 // force wake up dispatcher just in case.
                 //
@@ -1,4 +1,4 @@
-use std::{future::Future, str, task::Poll, time::Duration};
+use std::{cell::RefCell, future::Future, rc::Rc, str, task::Poll, time::Duration};
 
 use actix_codec::Framed;
 use actix_rt::{pin, time::sleep};
@@ -12,9 +12,11 @@ use crate::{
     body::MessageBody,
     config::ServiceConfig,
     h1::{Codec, ExpectHandler, UpgradeHandler},
+    header::{HeaderMap, HeaderValue, LINK},
     service::HttpFlow,
     test::{TestBuffer, TestSeqBuffer},
-    Error, HttpMessage, KeepAlive, Method, OnConnectData, Request, Response, StatusCode,
+    EarlyHints, Error, HttpMessage, KeepAlive, Method, OnConnectData, Request, Response,
+    StatusCode,
 };
 
 fn find_slice(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
@@ -721,6 +723,223 @@ async fn expect_eager() {
     .await;
 }
 
+#[actix_rt::test]
+async fn expect_deferred() {
+    lazy(|cx| {
+        let mut buf = TestSeqBuffer::empty();
+        let cfg = ServiceConfig::new(
+            KeepAlive::Disabled,
+            Duration::ZERO,
+            Duration::ZERO,
+            false,
+            None,
+        )
+        .with_defer_continue(true);
+
+        let services = HttpFlow::new(echo_payload_service(), ExpectHandler, None);
+
+        let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+            buf.clone(),
+            services,
+            cfg,
+            None,
+            OnConnectData::default(),
+        );
+
+        buf.extend_read_buf(
+            "\
+                POST /upload HTTP/1.1\r\n\
+                Content-Length: 5\r\n\
+                Expect: 100-continue\r\n\
+                \r\n\
+                ",
+        );
+
+        pin!(h1);
+
+        assert!(h1.as_mut().poll(cx).is_pending());
+
+        // service has started reading the payload
+        if let DispatcherState::Normal { ref inner } = h1.inner {
+            let io = inner.io.as_ref().unwrap();
+            let res = &io.write_buf()[..];
+            assert_eq!(
+                str::from_utf8(res).unwrap(),
+                "HTTP/1.1 100 Continue\r\n\r\n"
+            );
+        }
+
+        buf.extend_read_buf("12345");
+        assert!(h1.as_mut().poll(cx).is_ready());
+
+        if let DispatcherState::Normal { ref inner } = h1.inner {
+            let io = inner.io.as_ref().unwrap();
+            let mut res = io.write_buf()[..].to_owned();
+            stabilize_date_header(&mut res);
+
+            assert_eq!(
+                str::from_utf8(&res).unwrap(),
+                "\
+                    HTTP/1.1 100 Continue\r\n\
+                    \r\n\
+                    HTTP/1.1 200 OK\r\n\
+                    content-length: 5\r\n\
+                    connection: close\r\n\
+                    date: Thu, 01 Jan 1970 12:34:56 UTC\r\n\
+                    \r\n\
+                    12345\
+                    "
+            );
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn expect_deferred_unread() {
+    lazy(|cx| {
+        let mut buf = TestSeqBuffer::empty();
+        let cfg = ServiceConfig::new(KeepAlive::Os, Duration::ZERO, Duration::ZERO, false, None)
+            .with_defer_continue(true);
+
+        let services = HttpFlow::new(echo_path_service(), ExpectHandler, None);
+
+        let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+            buf.clone(),
+            services,
+            cfg,
+            None,
+            OnConnectData::default(),
+        );
+
+        buf.extend_read_buf(
+            "\
+                POST /upload HTTP/1.1\r\n\
+                Content-Length: 5\r\n\
+                Expect: 100-continue\r\n\
+                \r\n\
+                ",
+        );
+
+        pin!(h1);
+
+        // connection is closed since the client might not send the payload
+        assert!(h1.as_mut().poll(cx).is_ready());
+
+        if let DispatcherState::Normal { ref inner } = h1.inner {
+            let io = inner.io.as_ref().unwrap();
+            let mut res = io.write_buf()[..].to_owned();
+            stabilize_date_header(&mut res);
+
+            // service did not read the payload so `100 Continue` is never sent
+            assert_eq!(
+                str::from_utf8(&res).unwrap(),
+                "\
+                    HTTP/1.1 200 OK\r\n\
+                    content-length: 7\r\n\
+                    connection: close\r\n\
+                    date: Thu, 01 Jan 1970 12:34:56 UTC\r\n\
+                    \r\n\
+                    /upload\
+                    "
+            );
+        }
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn early_hints() {
+    let handles = Rc::new(RefCell::new(Vec::new()));
+
+    let service = {
+        let handles = Rc::clone(&handles);
+
+        fn_service(move |req: Request| {
+            let early_hints = req.extensions().get::<EarlyHints>().cloned();
+            handles.borrow_mut().push(early_hints.clone());
+
+            async move {
+                if let Some(early_hints) = early_hints {
+                    let mut headers = HeaderMap::new();
+                    headers.append(
+                        LINK,
+                        HeaderValue::from_static("</style.css>; rel=preload; as=style"),
+                    );
+                    headers.append(
+                        LINK,
+                        HeaderValue::from_static("</script.js>; rel=preload; as=script"),
+                    );
+                    assert!(early_hints.send(headers));
+                }
+
+                actix_rt::task::yield_now().await;
+
+                Ok::<_, Error>(Response::ok())
+            }
+        })
+    };
+
+    let dispatcher = |buf: &TestBuffer| {
+        let cfg = ServiceConfig::new(
+            KeepAlive::Disabled,
+            Duration::ZERO,
+            Duration::ZERO,
+            false,
+            None,
+        );
+
+        let services = HttpFlow::new(service.clone(), ExpectHandler, None);
+
+        Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+            buf.clone(),
+            services,
+            cfg,
+            None,
+            OnConnectData::default(),
+        )
+    };
+
+    let buf = TestBuffer::new("GET /page HTTP/1.1\r\n\r\n");
+    let h1 = dispatcher(&buf);
+    pin!(h1);
+
+    h1.await.unwrap();
+
+    let mut res = buf.take_write_buf().to_vec();
+    stabilize_date_header(&mut res);
+    assert_eq!(
+        str::from_utf8(&res).unwrap(),
+        "\
+            HTTP/1.1 103 Early Hints\r\n\
+            link: </style.css>; rel=preload; as=style\r\n\
+            link: </script.js>; rel=preload; as=script\r\n\
+            \r\n\
+            HTTP/1.1 200 OK\r\n\
+            content-length: 0\r\n\
+            connection: close\r\n\
+            date: Thu, 01 Jan 1970 12:34:56 UTC\r\n\
+            \r\n\
+            "
+    );
+
+    // no more hints can be sent once the final response is written
+    let early_hints = handles.borrow()[0].clone().unwrap();
+    assert!(!early_hints.send(HeaderMap::new()));
+
+    // HTTP/1.0 clients do not get early hints
+    let buf = TestBuffer::new("GET /page HTTP/1.0\r\n\r\n");
+    let h1 = dispatcher(&buf);
+    pin!(h1);
+
+    h1.await.unwrap();
+
+    assert!(handles.borrow()[1].is_none());
+    assert!(!str::from_utf8(&buf.take_write_buf())
+        .unwrap()
+        .contains("103 Early Hints"));
+}
+
 #[actix_rt::test]
 async fn upgrade_handling() {
     struct TestUpgrade;
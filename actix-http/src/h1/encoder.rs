@@ -328,6 +328,11 @@ impl<T: MessageType> MessageEncoder<T> {
         self.te.encode_eof(buf)
     }
 
+    /// Encode EOF followed by trailers.
+    pub fn encode_trailers(&mut self, trailers: &HeaderMap, buf: &mut BytesMut) -> io::Result<()> {
+        self.te.encode_trailers(trailers, buf)
+    }
+
     /// Encode message.
     pub fn encode(
         &mut self,
@@ -480,6 +485,29 @@ impl TransferEncoding {
             }
         }
     }
+
+    /// Encode eof with trailers. Trailers are only written when using chunked encoding.
+    #[inline]
+    pub fn encode_trailers(&mut self, trailers: &HeaderMap, buf: &mut BytesMut) -> io::Result<()> {
+        match self.kind {
+            TransferEncodingKind::Chunked(ref mut eof) if !*eof => {
+                *eof = true;
+                buf.extend_from_slice(b"0\r\n");
+
+                for (name, value) in trailers.iter() {
+                    buf.reserve(name.as_str().len() + value.len() + 4);
+                    buf.extend_from_slice(name.as_str().as_bytes());
+                    buf.extend_from_slice(b": ");
+                    buf.extend_from_slice(value.as_bytes());
+                    buf.extend_from_slice(b"\r\n");
+                }
+
+                buf.extend_from_slice(b"\r\n");
+                Ok(())
+            }
+            _ => self.encode_eof(buf),
+        }
+    }
 }
 
 /// # Safety
@@ -551,6 +579,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_chunked_te_trailers() {
+        let mut trailers = HeaderMap::new();
+        trailers.insert(
+            HeaderName::from_static("grpc-status"),
+            HeaderValue::from_static("0"),
+        );
+
+        let mut bytes = BytesMut::new();
+        let mut enc = TransferEncoding::chunked();
+        enc.encode(b"test", &mut bytes).unwrap();
+        enc.encode_trailers(&trailers, &mut bytes).unwrap();
+        assert_eq!(
+            bytes.split().freeze(),
+            Bytes::from_static(b"4\r\ntest\r\n0\r\ngrpc-status: 0\r\n\r\n")
+        );
+
+        // trailers can not be sent with other transfer encodings
+        let mut enc = TransferEncoding::length(4);
+        enc.encode(b"test", &mut bytes).unwrap();
+        enc.encode_trailers(&trailers, &mut bytes).unwrap();
+        assert_eq!(bytes.split().freeze(), Bytes::from_static(b"test"));
+    }
+
     #[actix_rt::test]
     async fn test_camel_case() {
         let mut bytes = BytesMut::with_capacity(2048);
@@ -11,7 +11,7 @@ use std::{
 use bytes::Bytes;
 use futures_core::Stream;
 
-use crate::error::PayloadError;
+use crate::{error::PayloadError, header::HeaderMap};
 
 /// max buffer size 32k
 pub(crate) const MAX_BUFFER_SIZE: usize = 32_768;
@@ -73,6 +73,13 @@ impl Payload {
     pub fn unread_data(&mut self, data: Bytes) {
         self.inner.borrow_mut().unread_data(data);
     }
+
+    /// Takes the trailer fields sent after a chunked payload.
+    ///
+    /// Trailers are only available once the payload stream has ended.
+    pub fn take_trailers(&mut self) -> Option<HeaderMap> {
+        self.inner.borrow_mut().trailers.take()
+    }
 }
 
 impl Stream for Payload {
@@ -89,11 +96,15 @@ impl Stream for Payload {
 /// Sender part of the payload stream
 pub struct PayloadSender {
     inner: Weak<RefCell<Inner>>,
+    continue_deferred: bool,
 }
 
 impl PayloadSender {
     fn new(inner: Weak<RefCell<Inner>>) -> Self {
-        Self { inner }
+        Self {
+            inner,
+            continue_deferred: false,
+        }
     }
 
     #[inline]
@@ -112,11 +123,67 @@ impl PayloadSender {
 
     #[inline]
     pub fn feed_data(&mut self, data: Bytes) {
+        // client sent the payload without waiting for `100 Continue`
+        self.continue_deferred = false;
+
         if let Some(shared) = self.inner.upgrade() {
             shared.borrow_mut().feed_data(data)
         }
     }
 
+    #[inline]
+    pub fn feed_trailers(&mut self, trailers: HeaderMap) {
+        if let Some(shared) = self.inner.upgrade() {
+            shared.borrow_mut().trailers = Some(trailers);
+        }
+    }
+
+    /// Defers the `100 Continue` response until the payload is first read.
+    pub(crate) fn defer_continue(&mut self, cx: &Context<'_>) {
+        if let Some(shared) = self.inner.upgrade() {
+            let mut inner = shared.borrow_mut();
+            inner.expect = ExpectState::Deferred;
+            inner.register_io(cx);
+            self.continue_deferred = true;
+        }
+    }
+
+    /// Returns true, once, after the payload is first read while `100 Continue` is deferred.
+    pub(crate) fn take_continue(&mut self) -> bool {
+        if !self.continue_deferred {
+            return false;
+        }
+
+        if let Some(shared) = self.inner.upgrade() {
+            let mut inner = shared.borrow_mut();
+
+            if inner.expect == ExpectState::Requested {
+                inner.expect = ExpectState::None;
+                self.continue_deferred = false;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Cancels a deferred `100 Continue` that has not been requested by reading the payload.
+    ///
+    /// Returns true if the client is still waiting for `100 Continue` before sending the payload.
+    pub(crate) fn cancel_continue(&mut self) -> bool {
+        if !self.continue_deferred {
+            return false;
+        }
+
+        self.continue_deferred = false;
+
+        if let Some(shared) = self.inner.upgrade() {
+            shared.borrow_mut().expect = ExpectState::None;
+        }
+
+        true
+    }
+
     #[allow(clippy::needless_pass_by_ref_mut)]
     #[inline]
     pub fn need_read(&self, cx: &mut Context<'_>) -> PayloadStatus {
@@ -135,6 +202,14 @@ impl PayloadSender {
     }
 }
 
+/// Progress of a `100 Continue` response that is deferred until the payload is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExpectState {
+    None,
+    Deferred,
+    Requested,
+}
+
 #[derive(Debug)]
 struct Inner {
     len: usize,
@@ -142,6 +217,8 @@ struct Inner {
     err: Option<PayloadError>,
     need_read: bool,
     items: VecDeque<Bytes>,
+    trailers: Option<HeaderMap>,
+    expect: ExpectState,
     task: Option<Waker>,
     io_task: Option<Waker>,
 }
@@ -153,6 +230,8 @@ impl Inner {
             len: 0,
             err: None,
             items: VecDeque::new(),
+            trailers: None,
+            expect: ExpectState::None,
             need_read: true,
             task: None,
             io_task: None,
@@ -209,6 +288,8 @@ impl Inner {
 
     #[inline]
     fn feed_data(&mut self, data: Bytes) {
+        self.expect = ExpectState::None;
+
         self.len += data.len();
         self.items.push_back(data);
         self.need_read = self.len < MAX_BUFFER_SIZE;
@@ -238,6 +319,10 @@ impl Inner {
         } else if self.eof {
             Poll::Ready(None)
         } else {
+            if self.expect == ExpectState::Deferred {
+                self.expect = ExpectState::Requested;
+            }
+
             self.need_read = true;
             self.register(cx);
             self.wake_io();
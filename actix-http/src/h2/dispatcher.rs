@@ -7,6 +7,7 @@ use std::{
     pin::{pin, Pin},
     rc::Rc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use actix_codec::{AsyncRead, AsyncWrite};
@@ -16,6 +17,7 @@ use actix_utils::future::poll_fn;
 use bytes::{Bytes, BytesMut};
 use futures_core::ready;
 use h2::{
+    ext::Protocol,
     server::{Connection, SendResponse},
     Ping, PingPong,
 };
@@ -42,6 +44,7 @@ pin_project! {
         config: ServiceConfig,
         peer_addr: Option<net::SocketAddr>,
         ping_pong: Option<H2PingPong>,
+        shutdown: Option<Pin<Box<dyn Future<Output = ()>>>>,
         _phantom: PhantomData<B>
     }
 }
@@ -58,7 +61,12 @@ where
         conn_data: OnConnectData,
         timer: Option<Pin<Box<Sleep>>>,
     ) -> Self {
-        let ping_pong = config.keep_alive().duration().map(|dur| H2PingPong {
+        let ping_interval = config
+            .h2_settings()
+            .ping_interval
+            .or_else(|| config.keep_alive().duration());
+
+        let ping_pong = ping_interval.map(|dur| H2PingPong {
             timer: timer
                 .map(|mut timer| {
                     // reuse timer slot if it was initialized for handshake
@@ -66,10 +74,13 @@ where
                     timer
                 })
                 .unwrap_or_else(|| Box::pin(sleep(dur))),
+            interval: dur,
             in_flight: false,
             ping_pong: conn.ping_pong().unwrap(),
         });
 
+        let shutdown = config.shutdown_signal().map(|signal| signal.notified());
+
         Self {
             flow,
             config,
@@ -77,6 +88,7 @@ where
             connection: conn,
             conn_data: conn_data.0.map(Rc::new),
             ping_pong,
+            shutdown,
             _phantom: PhantomData,
         }
     }
@@ -89,6 +101,9 @@ struct H2PingPong {
     /// True when a ping has been sent and is waiting for a reply.
     in_flight: bool,
 
+    /// Interval between pings; also the timeout for pong response.
+    interval: Duration,
+
     /// Timeout for pong response.
     timer: Pin<Box<Sleep>>,
 }
@@ -110,6 +125,14 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        if let Some(shutdown) = this.shutdown.as_mut() {
+            if shutdown.as_mut().poll(cx).is_ready() {
+                // send GOAWAY and keep polling until in-flight streams are finished
+                this.connection.graceful_shutdown();
+                this.shutdown = None;
+            }
+        }
+
         loop {
             match Pin::new(&mut this.connection).poll_accept(cx)? {
                 Poll::Ready(Some((req, tx))) => {
@@ -126,6 +149,15 @@ where
                     head.headers = parts.headers.into();
                     head.peer_addr = this.peer_addr;
 
+                    if let Some(protocol) = parts.extensions.get::<Protocol>() {
+                        // extended CONNECT for WebSockets is handled like an HTTP/1.1 upgrade
+                        if protocol.as_str() == "websocket" && head.method == Method::CONNECT {
+                            head.method = Method::GET;
+                        }
+
+                        head.set_protocol(protocol.as_str());
+                    }
+
                     req.conn_data = this.conn_data.clone();
 
                     let fut = this.flow.service.call(req);
@@ -170,7 +202,7 @@ where
                                 Poll::Ready(_) => {
                                     ping_pong.in_flight = false;
 
-                                    let dead_line = this.config.keep_alive_deadline().unwrap();
+                                    let dead_line = this.config.now() + ping_pong.interval;
                                     ping_pong.timer.as_mut().reset(dead_line.into());
                                 }
                                 Poll::Pending => {
@@ -185,7 +217,7 @@ where
 
                             ping_pong.ping_pong.send_ping(Ping::opaque())?;
 
-                            let dead_line = this.config.keep_alive_deadline().unwrap();
+                            let dead_line = this.config.now() + ping_pong.interval;
                             ping_pong.timer.as_mut().reset(dead_line.into());
 
                             ping_pong.in_flight = true;
@@ -265,10 +297,20 @@ where
         }
     }
 
-    // response body streaming finished. send end of stream and return.
-    stream
-        .send_data(Bytes::new(), true)
-        .map_err(DispatchError::SendData)?;
+    let trailers = poll_fn(|cx| body.as_mut().poll_trailers(cx))
+        .await
+        .map_err(|err| DispatchError::ResponseBody(err.into()))?;
+
+    // response body streaming finished. send trailers or end of stream and return.
+    match trailers {
+        Some(trailers) => stream
+            .send_trailers(trailers.into())
+            .map_err(DispatchError::SendData)?,
+
+        None => stream
+            .send_data(Bytes::new(), true)
+            .map_err(DispatchError::SendData)?,
+    }
 
     Ok(())
 }
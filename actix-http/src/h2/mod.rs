@@ -11,28 +11,45 @@ use actix_rt::time::{sleep_until, Sleep};
 use bytes::Bytes;
 use futures_core::{ready, Stream};
 use h2::{
-    server::{handshake, Connection, Handshake},
+    server::{Builder, Connection, Handshake},
     RecvStream,
 };
 
 use crate::{
     config::ServiceConfig,
     error::{DispatchError, PayloadError},
+    header::HeaderMap,
 };
 
 mod dispatcher;
 mod service;
+mod upgrade;
 
+pub(crate) use self::upgrade::{H2cIo, H2cProbe, H2cProbeOutput};
 pub use self::{dispatcher::Dispatcher, service::H2Service};
 
 /// HTTP/2 peer stream.
 pub struct Payload {
     stream: RecvStream,
+    trailers: Option<HeaderMap>,
+    eof: bool,
 }
 
 impl Payload {
     pub(crate) fn new(stream: RecvStream) -> Self {
-        Self { stream }
+        Self {
+            stream,
+            trailers: None,
+            eof: false,
+        }
+    }
+
+    /// Takes the trailer fields sent after the request body.
+    ///
+    /// Trailers are only available once the payload stream has ended. Their size is limited by
+    /// [`H2Settings::max_header_list_size`](crate::H2Settings::max_header_list_size).
+    pub fn take_trailers(&mut self) -> Option<HeaderMap> {
+        self.trailers.take()
     }
 }
 
@@ -42,6 +59,10 @@ impl Stream for Payload {
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let this = self.get_mut();
 
+        if this.eof {
+            return Poll::Ready(None);
+        }
+
         match ready!(Pin::new(&mut this.stream).poll_data(cx)) {
             Some(Ok(chunk)) => {
                 let len = chunk.len();
@@ -52,7 +73,16 @@ impl Stream for Payload {
                 }
             }
             Some(Err(err)) => Poll::Ready(Some(Err(err.into()))),
-            None => Poll::Ready(None),
+
+            // read trailers, if any, before ending the stream
+            None => match ready!(this.stream.poll_trailers(cx)) {
+                Ok(trailers) => {
+                    this.trailers = trailers.map(HeaderMap::from);
+                    this.eof = true;
+                    Poll::Ready(None)
+                }
+                Err(err) => Poll::Ready(Some(Err(err.into()))),
+            },
         }
     }
 }
@@ -61,8 +91,30 @@ pub(crate) fn handshake_with_timeout<T>(io: T, config: &ServiceConfig) -> Handsh
 where
     T: AsyncRead + AsyncWrite + Unpin,
 {
+    let settings = config.h2_settings();
+    let mut builder = Builder::new();
+
+    if let Some(max) = settings.max_concurrent_streams {
+        builder.max_concurrent_streams(max);
+    }
+    if let Some(size) = settings.initial_window_size {
+        builder.initial_window_size(size);
+    }
+    if let Some(size) = settings.initial_connection_window_size {
+        builder.initial_connection_window_size(size);
+    }
+    if let Some(size) = settings.max_frame_size {
+        builder.max_frame_size(size);
+    }
+    if let Some(size) = settings.max_header_list_size {
+        builder.max_header_list_size(size);
+    }
+    if settings.enable_connect_protocol {
+        builder.enable_connect_protocol();
+    }
+
     HandshakeWithTimeout {
-        handshake: handshake(io),
+        handshake: builder.handshake(io),
         timer: config
             .client_request_deadline()
             .map(|deadline| Box::pin(sleep_until(deadline.into()))),
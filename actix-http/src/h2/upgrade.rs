@@ -0,0 +1,628 @@
+//! HTTP/1.1 `Upgrade: h2c` handling (RFC 7540 §3.2).
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use actix_codec::{AsyncRead, AsyncWrite, ReadBuf};
+use actix_rt::time::{sleep_until, Sleep};
+use base64::{
+    alphabet,
+    engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    Engine as _,
+};
+use bytes::{Buf as _, BufMut as _, Bytes, BytesMut};
+use futures_core::ready;
+use tokio_util::codec::Decoder as _;
+
+use crate::{
+    config::ServiceConfig,
+    error::DispatchError,
+    h1::{Codec, Message},
+    header, Method, Request, Version,
+};
+
+/// Client connection preface that starts an HTTP/2 connection.
+const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+const FRAME_HEADER_SIZE: usize = 9;
+
+/// Default `SETTINGS_MAX_FRAME_SIZE`, which every HTTP/2 endpoint accepts.
+const MAX_FRAME_SIZE: usize = 16_384;
+
+const FRAME_TYPE_HEADERS: u8 = 0x1;
+const FRAME_TYPE_SETTINGS: u8 = 0x4;
+const FRAME_TYPE_CONTINUATION: u8 = 0x9;
+
+const FLAG_ACK: u8 = 0x1;
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+
+/// Base64url engine for `HTTP2-Settings` values, which are sent without padding by most clients.
+const SETTINGS_ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::URL_SAFE,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+const SWITCHING_PROTOCOLS: &[u8] =
+    b"HTTP/1.1 101 Switching Protocols\r\nconnection: upgrade\r\nupgrade: h2c\r\n\r\n";
+
+/// Result of probing the first request on a plaintext HTTP/1.1 connection.
+pub(crate) enum H2cProbeOutput<T> {
+    /// Connection continues as HTTP/1.1; the buffer holds everything read so far. The deadline for
+    /// the first request head, if any, was set when probing started.
+    Http1(T, BytesMut, Option<Instant>),
+
+    /// Connection was upgraded and continues as HTTP/2.
+    Http2(H2cIo<T>),
+}
+
+/// Reads the first request head of a connection and upgrades the connection to HTTP/2 if the
+/// request asks for it.
+pub(crate) struct H2cProbe<T> {
+    io: Option<T>,
+    read_buf: BytesMut,
+    codec: Codec,
+    max_head_size: usize,
+    deadline: Option<Instant>,
+    timer: Option<Pin<Box<Sleep>>>,
+
+    /// Length of the start of the read buffer that is known not to contain the end of the head.
+    scanned: usize,
+
+    /// Upgrade to perform, set once the `101` response is being written.
+    upgrade: Option<Upgrade>,
+    write_buf: Bytes,
+}
+
+impl<T> H2cProbe<T> {
+    pub(crate) fn new(io: T, config: ServiceConfig) -> Self {
+        let deadline = config.client_request_deadline();
+
+        Self {
+            io: Some(io),
+            read_buf: BytesMut::new(),
+            max_head_size: config.h1_limits().max_head_size,
+            codec: Codec::new(config),
+            deadline,
+            timer: deadline.map(|deadline| Box::pin(sleep_until(deadline.into()))),
+            scanned: 0,
+            upgrade: None,
+            write_buf: Bytes::new(),
+        }
+    }
+
+    fn fallback(&mut self) -> Poll<Result<H2cProbeOutput<T>, DispatchError>> {
+        let io = self.io.take().unwrap();
+        let read_buf = std::mem::take(&mut self.read_buf);
+        Poll::Ready(Ok(H2cProbeOutput::Http1(io, read_buf, self.deadline)))
+    }
+}
+
+impl<T> Future for H2cProbe<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    type Output = Result<H2cProbeOutput<T>, DispatchError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            if this.upgrade.is_some() {
+                let io = this.io.as_mut().unwrap();
+
+                while !this.write_buf.is_empty() {
+                    match ready!(Pin::new(&mut *io).poll_write(cx, &this.write_buf))? {
+                        0 => {
+                            return Poll::Ready(Err(DispatchError::Io(
+                                io::ErrorKind::WriteZero.into(),
+                            )))
+                        }
+                        n => this.write_buf.advance(n),
+                    }
+                }
+
+                ready!(Pin::new(&mut *io).poll_flush(cx))?;
+
+                let io = this.io.take().unwrap();
+                let read_buf = std::mem::take(&mut this.read_buf);
+                let upgrade = this.upgrade.take().unwrap();
+
+                return Poll::Ready(Ok(H2cProbeOutput::Http2(H2cIo::new(io, read_buf, upgrade))));
+            }
+
+            // the head is only decoded once it is complete, so each byte is scanned once
+            if let Some(head_len) = head_end(&this.read_buf, this.scanned) {
+                let mut head = BytesMut::from(&this.read_buf[..head_len]);
+
+                match this.codec.decode(&mut head) {
+                    Ok(Some(Message::Item(req))) if head.is_empty() => match upgrade(&req) {
+                        Some(upgrade) => {
+                            // everything after the request head belongs to the HTTP/2 connection
+                            this.read_buf.advance(head_len);
+
+                            this.upgrade = Some(upgrade);
+                            this.write_buf = Bytes::from_static(SWITCHING_PROTOCOLS);
+                            continue;
+                        }
+                        None => return this.fallback(),
+                    },
+
+                    // let the HTTP/1 dispatcher respond to anything else
+                    _ => return this.fallback(),
+                }
+            }
+
+            this.scanned = this.read_buf.len();
+
+            // let the HTTP/1 dispatcher respond to heads that are too large
+            if this.read_buf.len() >= this.max_head_size {
+                return this.fallback();
+            }
+
+            crate::h1::reserve_readbuf(&mut this.read_buf);
+
+            let io = Pin::new(this.io.as_mut().unwrap());
+
+            match tokio_util::io::poll_read_buf(io, cx, &mut this.read_buf) {
+                Poll::Ready(Ok(0)) => return this.fallback(),
+                Poll::Ready(Ok(_)) => {}
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(DispatchError::Io(err))),
+                Poll::Pending => {
+                    // slow request heads are answered by the HTTP/1 dispatcher
+                    if let Some(timer) = this.timer.as_mut() {
+                        ready!(timer.as_mut().poll(cx));
+                        return this.fallback();
+                    }
+
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+/// Returns the length of the request head at the start of `buf`, if it is complete.
+///
+/// The first `scanned` bytes are known not to contain the end of the head.
+fn head_end(buf: &[u8], scanned: usize) -> Option<usize> {
+    // the head ends with an empty line; lines may end with either CRLF or LF
+    let start = scanned.saturating_sub(2);
+
+    buf[start..].iter().enumerate().find_map(|(idx, &byte)| {
+        let idx = start + idx;
+
+        if byte != b'\n' || idx == 0 {
+            return None;
+        }
+
+        match buf[..idx] {
+            [.., b'\n'] => Some(idx + 1),
+            [.., b'\n', b'\r'] => Some(idx + 1),
+            _ => None,
+        }
+    })
+}
+
+/// HTTP/2 connection upgraded from an HTTP/1.1 request.
+struct Upgrade {
+    /// SETTINGS payload decoded from the request's `HTTP2-Settings` header.
+    settings: Bytes,
+
+    /// Frames that open stream 1 with the upgraded request.
+    frames: Bytes,
+}
+
+/// Returns the upgrade requested by the given request if it asks for an `h2c` upgrade.
+///
+/// Only requests without a body are upgraded; others are served over HTTP/1.1 as allowed by
+/// RFC 7540 §3.2.
+fn upgrade(req: &Request) -> Option<Upgrade> {
+    let head = req.head();
+
+    if head.version != Version::HTTP_11 || head.method == Method::CONNECT {
+        return None;
+    }
+
+    let has_token = |name: header::HeaderName, token: &str| {
+        head.headers.get_all(name).any(|val| {
+            val.to_str().is_ok_and(|val| {
+                val.split(',')
+                    .any(|item| item.trim().eq_ignore_ascii_case(token))
+            })
+        })
+    };
+
+    if !has_token(header::UPGRADE, "h2c") || !has_token(header::CONNECTION, "upgrade") {
+        return None;
+    }
+
+    // exactly one `HTTP2-Settings` header holding a base64url encoded SETTINGS payload
+    let mut settings = head.headers.get_all("http2-settings");
+    let settings = match (settings.next(), settings.next()) {
+        (Some(val), None) => val,
+        _ => return None,
+    };
+    let settings = SETTINGS_ENGINE.decode(settings.as_bytes()).ok()?;
+    if settings.len() % 6 != 0 || settings.len() > MAX_FRAME_SIZE / 2 {
+        return None;
+    }
+
+    if head.headers.contains_key(header::TRANSFER_ENCODING) {
+        return None;
+    }
+    if let Some(len) = head.headers.get(header::CONTENT_LENGTH) {
+        if len != "0" {
+            return None;
+        }
+    }
+
+    Some(Upgrade {
+        settings: Bytes::from(settings),
+        frames: encode_request(req),
+    })
+}
+
+/// Encodes the request head as a header block on stream 1, ending the stream.
+///
+/// Fields are encoded as HPACK literals without indexing so that the dynamic table of the peer's
+/// encoder, which the server continues to decode with, is not affected.
+fn encode_request(req: &Request) -> Bytes {
+    let head = req.head();
+
+    let mut block = BytesMut::new();
+
+    let path = head.uri.path_and_query().map_or("/", |path| path.as_str());
+
+    encode_field(&mut block, b":method", head.method.as_str().as_bytes());
+    encode_field(&mut block, b":scheme", b"http");
+    if let Some(host) = head.headers.get(header::HOST) {
+        encode_field(&mut block, b":authority", host.as_bytes());
+    }
+    encode_field(&mut block, b":path", path.as_bytes());
+
+    for (name, value) in head.headers.iter() {
+        // connection-specific fields are not allowed in HTTP/2 (RFC 9113 §8.2.2)
+        match name.as_str() {
+            "connection" | "upgrade" | "http2-settings" | "host" | "keep-alive"
+            | "proxy-connection" | "transfer-encoding" => continue,
+            "te" if value != "trailers" => continue,
+            _ => {}
+        }
+
+        encode_field(&mut block, name.as_str().as_bytes(), value.as_bytes());
+    }
+
+    let mut frames = BytesMut::with_capacity(block.len() + FRAME_HEADER_SIZE);
+    let mut frame_type = FRAME_TYPE_HEADERS;
+    let mut flags = FLAG_END_STREAM;
+
+    loop {
+        let chunk = block.split_to(block.len().min(MAX_FRAME_SIZE));
+
+        if block.is_empty() {
+            flags |= FLAG_END_HEADERS;
+        }
+
+        frames.put_uint(chunk.len() as u64, 3);
+        frames.put_u8(frame_type);
+        frames.put_u8(flags);
+        frames.put_u32(1);
+        frames.extend_from_slice(&chunk);
+
+        if block.is_empty() {
+            break;
+        }
+
+        frame_type = FRAME_TYPE_CONTINUATION;
+        flags = 0;
+    }
+
+    frames.freeze()
+}
+
+/// Encodes a literal header field without indexing with a literal name (RFC 7541 §6.2.2).
+fn encode_field(dst: &mut BytesMut, name: &[u8], value: &[u8]) {
+    dst.put_u8(0);
+    encode_string(dst, name);
+    encode_string(dst, value);
+}
+
+/// Encodes a string literal without Huffman coding (RFC 7541 §5.2).
+fn encode_string(dst: &mut BytesMut, val: &[u8]) {
+    // 7-bit prefix integer (RFC 7541 §5.1)
+    let mut len = val.len();
+
+    if len < 0x7f {
+        dst.put_u8(len as u8);
+    } else {
+        dst.put_u8(0x7f);
+        len -= 0x7f;
+
+        while len >= 0x80 {
+            dst.put_u8((len % 0x80) as u8 | 0x80);
+            len /= 0x80;
+        }
+
+        dst.put_u8(len as u8);
+    }
+
+    dst.extend_from_slice(val);
+}
+
+/// Returns the end of the peer's first SETTINGS frame if the buffer contains it.
+///
+/// Returns `Some(Err(()))` if the buffer does not start with a connection preface followed by a
+/// SETTINGS frame, in which case the HTTP/2 handshake will fail on its own.
+fn preface_end(buf: &[u8]) -> Option<Result<usize, ()>> {
+    let len = buf.len().min(PREFACE.len());
+
+    if buf[..len] != PREFACE[..len] {
+        return Some(Err(()));
+    }
+
+    if buf.len() < PREFACE.len() + FRAME_HEADER_SIZE {
+        return None;
+    }
+
+    let frame = &buf[PREFACE.len()..];
+    let frame_len = (frame[0] as usize) << 16 | (frame[1] as usize) << 8 | frame[2] as usize;
+
+    if frame[3] != FRAME_TYPE_SETTINGS || frame_len > MAX_FRAME_SIZE {
+        return Some(Err(()));
+    }
+
+    let end = PREFACE.len() + FRAME_HEADER_SIZE + frame_len;
+    (buf.len() >= end).then_some(Ok(end))
+}
+
+/// I/O wrapper for a connection upgraded from HTTP/1.1.
+///
+/// Applies the settings of the upgrade request by prepending them to the peer's first SETTINGS
+/// frame, which has the same effect as receiving them first (RFC 7540 §3.2.1) without the server
+/// acknowledging a SETTINGS frame the peer did not send. Injects the frames carrying the upgraded
+/// request after that frame, so that the request is served on stream 1 of the HTTP/2 connection.
+pub(crate) struct H2cIo<T> {
+    io: T,
+    read_buf: BytesMut,
+    upgrade: Option<Upgrade>,
+}
+
+impl<T> H2cIo<T> {
+    fn new(io: T, read_buf: BytesMut, upgrade: Upgrade) -> Self {
+        Self {
+            io,
+            read_buf,
+            upgrade: Some(upgrade),
+        }
+    }
+}
+
+/// Prepends `settings` to the SETTINGS frame that follows the connection preface at the start of
+/// `buf` and ends at `end`, and inserts `frames` after it.
+fn merge_settings(buf: &mut BytesMut, end: usize, upgrade: &Upgrade) -> io::Result<()> {
+    let rest = buf.split_off(end);
+    let payload = buf.split_off(PREFACE.len() + FRAME_HEADER_SIZE);
+    let header = buf.split_off(PREFACE.len());
+
+    // an acknowledgement, which is not a valid first frame, is left for the handshake to reject
+    let settings = if header[4] & FLAG_ACK == 0 {
+        &upgrade.settings[..]
+    } else {
+        &[]
+    };
+
+    let len = settings.len() + payload.len();
+
+    if len > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "upgrade settings do not fit into the first SETTINGS frame",
+        ));
+    }
+
+    buf.put_uint(len as u64, 3);
+    buf.extend_from_slice(&header[3..]);
+    buf.extend_from_slice(settings);
+    buf.extend_from_slice(&payload);
+    buf.extend_from_slice(&upgrade.frames);
+    buf.extend_from_slice(&rest);
+
+    Ok(())
+}
+
+impl<T: AsyncRead + Unpin> AsyncRead for H2cIo<T> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        while let Some(upgrade) = this.upgrade.as_ref() {
+            match preface_end(&this.read_buf) {
+                Some(Ok(end)) => {
+                    merge_settings(&mut this.read_buf, end, upgrade)?;
+                    this.upgrade = None;
+                }
+
+                Some(Err(())) => this.upgrade = None,
+
+                None => {
+                    if ready!(tokio_util::io::poll_read_buf(
+                        Pin::new(&mut this.io),
+                        cx,
+                        &mut this.read_buf
+                    ))? == 0
+                    {
+                        this.upgrade = None;
+                    }
+                }
+            }
+        }
+
+        if !this.read_buf.is_empty() {
+            let len = this.read_buf.len().min(buf.remaining());
+            buf.put_slice(&this.read_buf.split_to(len));
+            return Poll::Ready(Ok(()));
+        }
+
+        Pin::new(&mut this.io).poll_read(cx, buf)
+    }
+}
+
+impl<T: AsyncWrite + Unpin> AsyncWrite for H2cIo<T> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().io).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().io).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn string_literal_lengths() {
+        let mut buf = BytesMut::new();
+        encode_string(&mut buf, &[b'a'; 10]);
+        assert_eq!(buf[0], 10);
+
+        let mut buf = BytesMut::new();
+        encode_string(&mut buf, &[b'a'; 1337]);
+        // 1337 - 127 = 1210 = 0b1001_0111010
+        assert_eq!(&buf[..3], &[0x7f, 0b1011_1010, 0b0000_1001]);
+        assert_eq!(buf.len(), 3 + 1337);
+    }
+
+    #[test]
+    fn upgrade_request_detection() {
+        let req = TestRequest::default()
+            .insert_header((header::CONNECTION, "Upgrade, HTTP2-Settings"))
+            .insert_header((header::UPGRADE, "h2c"))
+            .insert_header(("http2-settings", "AAMAAABkAAQAAP__"))
+            .finish();
+        assert!(upgrade(&req).is_some());
+
+        let req = TestRequest::default()
+            .insert_header((header::CONNECTION, "Upgrade, HTTP2-Settings"))
+            .insert_header((header::UPGRADE, "websocket"))
+            .insert_header(("http2-settings", "AAMAAABkAAQAAP__"))
+            .finish();
+        assert!(upgrade(&req).is_none());
+
+        // bodies are not upgraded
+        let req = TestRequest::default()
+            .insert_header((header::CONNECTION, "Upgrade, HTTP2-Settings"))
+            .insert_header((header::UPGRADE, "h2c"))
+            .insert_header(("http2-settings", "AAMAAABkAAQAAP__"))
+            .insert_header((header::CONTENT_LENGTH, "4"))
+            .finish();
+        assert!(upgrade(&req).is_none());
+
+        // settings payload is not a multiple of 6 bytes
+        let req = TestRequest::default()
+            .insert_header((header::CONNECTION, "Upgrade, HTTP2-Settings"))
+            .insert_header((header::UPGRADE, "h2c"))
+            .insert_header(("http2-settings", "AAMA"))
+            .finish();
+        assert!(upgrade(&req).is_none());
+    }
+
+    #[test]
+    fn upgrade_settings() {
+        let req = TestRequest::default()
+            .insert_header((header::CONNECTION, "Upgrade, HTTP2-Settings"))
+            .insert_header((header::UPGRADE, "h2c"))
+            .insert_header(("http2-settings", "AAMAAABkAAQAAP__"))
+            .finish();
+        let upgrade = upgrade(&req).unwrap();
+        assert_eq!(
+            &upgrade.settings[..],
+            &[0, 3, 0, 0, 0, 100, 0, 4, 0, 0, 0xff, 0xff]
+        );
+
+        let mut buf = BytesMut::from(PREFACE);
+        buf.extend_from_slice(&[0, 0, 6, FRAME_TYPE_SETTINGS, 0, 0, 0, 0, 0]);
+        buf.extend_from_slice(&[0, 3, 0, 0, 0, 10]);
+        let end = buf.len();
+        buf.extend_from_slice(b"rest");
+
+        merge_settings(&mut buf, end, &upgrade).unwrap();
+
+        let mut expected = PREFACE.to_vec();
+        expected.extend_from_slice(&[0, 0, 18, FRAME_TYPE_SETTINGS, 0, 0, 0, 0, 0]);
+        // client's own settings follow the upgrade settings so that they take precedence
+        expected.extend_from_slice(&upgrade.settings);
+        expected.extend_from_slice(&[0, 3, 0, 0, 0, 10]);
+        expected.extend_from_slice(&upgrade.frames);
+        expected.extend_from_slice(b"rest");
+        assert_eq!(&buf[..], &expected[..]);
+
+        // settings that do not fit into one frame are rejected
+        let mut buf = BytesMut::from(PREFACE);
+        buf.put_uint(MAX_FRAME_SIZE as u64, 3);
+        buf.extend_from_slice(&[FRAME_TYPE_SETTINGS, 0, 0, 0, 0, 0]);
+        buf.resize(buf.len() + MAX_FRAME_SIZE, 0);
+        let end = buf.len();
+        assert!(merge_settings(&mut buf, end, &upgrade).is_err());
+    }
+
+    #[test]
+    fn head_end_detection() {
+        let buf = b"GET / HTTP/1.1\r\nHost: a\r\n\r\nbody";
+        assert_eq!(head_end(buf, 0), Some(buf.len() - 4));
+        assert_eq!(head_end(&buf[..buf.len() - 5], 0), None);
+
+        // end of head split across reads
+        assert_eq!(head_end(buf, buf.len() - 5), Some(buf.len() - 4));
+        assert_eq!(head_end(buf, buf.len() - 6), Some(buf.len() - 4));
+
+        let buf = b"GET / HTTP/1.1\nHost: a\n\n";
+        assert_eq!(head_end(buf, 0), Some(buf.len()));
+        assert_eq!(head_end(buf, buf.len() - 1), Some(buf.len()));
+    }
+
+    #[test]
+    fn preface_detection() {
+        let mut buf = PREFACE.to_vec();
+        assert!(preface_end(&buf).is_none());
+
+        buf.extend_from_slice(&[0, 0, 6, FRAME_TYPE_SETTINGS, 0, 0, 0, 0, 0]);
+        assert!(preface_end(&buf).is_none());
+
+        buf.extend_from_slice(&[0, 3, 0, 0, 0, 100]);
+        assert_eq!(preface_end(&buf), Some(Ok(buf.len())));
+
+        assert_eq!(preface_end(b"GET / HTTP/1.1\r\n"), Some(Err(())));
+    }
+}
@@ -0,0 +1,330 @@
+use std::{error::Error as StdError, future::Future as _, pin::pin, rc::Rc, task::Poll};
+
+use actix_rt::task::JoinHandle;
+use actix_service::Service;
+use actix_utils::future::poll_fn;
+use bytes::{Bytes, BytesMut};
+use h3::server::{Connection, RequestResolver, RequestStream};
+use http_1 as http;
+
+use crate::{
+    body::{BodySize, BoxBody, MessageBody},
+    config::ServiceConfig,
+    error::DispatchError,
+    header::{
+        HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING, UPGRADE,
+    },
+    service::HttpFlow,
+    Extensions, Method, OnConnectData, Payload, Request, Response, ResponseHead, StatusCode, Uri,
+    Version,
+};
+
+type H3Connection = Connection<h3_quinn::Connection, Bytes>;
+
+/// Application error code for closing a connection without error.
+const H3_NO_ERROR: u32 = 0x100;
+
+/// Completes the QUIC handshake of a connection attempt and sets up HTTP/3 on the connection.
+pub(crate) async fn handshake(
+    incoming: quinn::Incoming,
+) -> Result<(quinn::Connection, H3Connection), DispatchError> {
+    let conn = incoming
+        .await
+        .map_err(|err| DispatchError::Io(err.into()))?;
+    let h3_conn = H3Connection::new(h3_quinn::Connection::new(conn.clone())).await?;
+
+    Ok((conn, h3_conn))
+}
+
+/// Accepts and serves request streams until the connection is closed.
+pub(crate) async fn dispatch<S, B>(
+    conn: quinn::Connection,
+    mut h3_conn: H3Connection,
+    flow: Rc<HttpFlow<S, (), ()>>,
+    config: ServiceConfig,
+    conn_data: OnConnectData,
+) -> Result<(), DispatchError>
+where
+    S: Service<Request> + 'static,
+    S::Error: Into<Response<BoxBody>>,
+    S::Future: 'static,
+    S::Response: Into<Response<B>>,
+    B: MessageBody + 'static,
+{
+    let peer_addr = Some(conn.remote_address());
+    let conn_data = conn_data.0.map(Rc::new);
+    let mut shutdown = config.shutdown_signal().map(|signal| signal.notified());
+    let mut in_flight = Vec::new();
+
+    loop {
+        let accepted = {
+            let mut accept = pin!(h3_conn.accept());
+
+            poll_fn(|cx| {
+                if let Some(signal) = shutdown.as_mut() {
+                    if signal.as_mut().poll(cx).is_ready() {
+                        return Poll::Ready(None);
+                    }
+                }
+
+                accept.as_mut().poll(cx).map(Some)
+            })
+            .await
+        };
+
+        match accepted {
+            // send GOAWAY and close the connection once in-flight requests are finished
+            None => {
+                h3_conn.shutdown(0).await?;
+
+                for handle in in_flight {
+                    let _ = handle.await;
+                }
+
+                conn.close(H3_NO_ERROR.into(), b"");
+                return Ok(());
+            }
+
+            Some(Ok(Some(resolver))) => {
+                in_flight.retain(|handle: &JoinHandle<()>| !handle.is_finished());
+
+                in_flight.push(actix_rt::spawn(handle_request(
+                    resolver,
+                    flow.clone(),
+                    config.clone(),
+                    peer_addr,
+                    conn_data.clone(),
+                )));
+            }
+
+            Some(Ok(None)) => return Ok(()),
+            Some(Err(err)) if err.is_h3_no_error() => return Ok(()),
+            Some(Err(err)) => return Err(err.into()),
+        }
+    }
+}
+
+enum H3DispatchError {
+    ReceiveRequest(h3::error::StreamError),
+    SendResponse(h3::error::StreamError),
+    ResponseBody(Box<dyn StdError>),
+}
+
+async fn handle_request<S, B>(
+    resolver: RequestResolver<h3_quinn::Connection, Bytes>,
+    flow: Rc<HttpFlow<S, (), ()>>,
+    config: ServiceConfig,
+    peer_addr: Option<std::net::SocketAddr>,
+    conn_data: Option<Rc<Extensions>>,
+) where
+    S: Service<Request>,
+    S::Error: Into<Response<BoxBody>>,
+    S::Response: Into<Response<B>>,
+    B: MessageBody,
+{
+    let res = async {
+        let (req, stream) = resolver
+            .resolve_request()
+            .await
+            .map_err(H3DispatchError::ReceiveRequest)?;
+
+        let (mut tx, rx) = stream.split();
+
+        let (parts, ()) = req.into_parts();
+        let head_req = parts.method == http::Method::HEAD;
+
+        let payload = Box::pin(super::Payload::new(rx));
+        let mut req = Request::with_payload(Payload::from(payload as crate::BoxedPayloadStream));
+
+        let head = req.head_mut();
+        head.method = Method::from_bytes(parts.method.as_str().as_bytes()).unwrap();
+        head.uri = parts
+            .uri
+            .to_string()
+            .parse()
+            .unwrap_or_else(|_| Uri::default());
+        head.version = Version::HTTP_3;
+        head.peer_addr = peer_addr;
+
+        for (name, value) in &parts.headers {
+            let name = HeaderName::from_bytes(name.as_str().as_bytes());
+            let value = HeaderValue::from_bytes(value.as_bytes());
+
+            if let (Ok(name), Ok(value)) = (name, value) {
+                head.headers.append(name, value);
+            }
+        }
+
+        req.conn_data = conn_data;
+
+        match flow.service.call(req).await {
+            Ok(res) => handle_response(res.into(), &mut tx, config, head_req).await,
+            Err(err) => {
+                let res: Response<BoxBody> = err.into();
+                handle_response(res, &mut tx, config, head_req).await
+            }
+        }
+    }
+    .await;
+
+    // log error.
+    if let Err(err) = res {
+        match err {
+            H3DispatchError::ReceiveRequest(err) => {
+                tracing::trace!("Error receiving request: {err:?}");
+            }
+            H3DispatchError::SendResponse(err) => {
+                tracing::trace!("Error sending response: {err:?}");
+            }
+            H3DispatchError::ResponseBody(err) => {
+                tracing::error!("Response payload stream error: {err:?}");
+            }
+        }
+    }
+}
+
+async fn handle_response<B>(
+    res: Response<B>,
+    tx: &mut RequestStream<h3_quinn::SendStream<Bytes>, Bytes>,
+    config: ServiceConfig,
+    head_req: bool,
+) -> Result<(), H3DispatchError>
+where
+    B: MessageBody,
+{
+    let (res, body) = res.replace_body(());
+
+    // prepare response.
+    let mut size = body.size();
+    let res = prepare_response(config, res.head(), &mut size);
+    let eof_or_head = size.is_eof() || head_req;
+
+    // send response head and return on eof.
+    tx.send_response(res)
+        .await
+        .map_err(H3DispatchError::SendResponse)?;
+
+    if !eof_or_head {
+        let mut body = pin!(body);
+
+        // poll response body and send chunks to client
+        while let Some(res) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+            let chunk = res.map_err(|err| H3DispatchError::ResponseBody(err.into()))?;
+
+            if !chunk.is_empty() {
+                tx.send_data(chunk)
+                    .await
+                    .map_err(H3DispatchError::SendResponse)?;
+            }
+        }
+
+        let trailers = poll_fn(|cx| body.as_mut().poll_trailers(cx))
+            .await
+            .map_err(|err| H3DispatchError::ResponseBody(err.into()))?;
+
+        if let Some(trailers) = trailers {
+            tx.send_trailers(convert_trailers(&trailers))
+                .await
+                .map_err(H3DispatchError::SendResponse)?;
+        }
+    }
+
+    // response finished. send end of stream and return.
+    tx.finish().await.map_err(H3DispatchError::SendResponse)
+}
+
+fn prepare_response(
+    config: ServiceConfig,
+    head: &ResponseHead,
+    size: &mut BodySize,
+) -> http::Response<()> {
+    let mut has_date = false;
+    let mut skip_len = size != &BodySize::Stream;
+
+    let mut res = http::Response::new(());
+    *res.status_mut() = http::StatusCode::from_u16(head.status.as_u16()).unwrap();
+    *res.version_mut() = http::Version::HTTP_3;
+
+    // Content length
+    match head.status {
+        StatusCode::NO_CONTENT | StatusCode::CONTINUE | StatusCode::PROCESSING => {
+            *size = BodySize::None
+        }
+        StatusCode::SWITCHING_PROTOCOLS => {
+            skip_len = true;
+            *size = BodySize::Stream;
+        }
+        _ => {}
+    }
+
+    let headers = res.headers_mut();
+
+    match size {
+        BodySize::None | BodySize::Stream => {}
+
+        BodySize::Sized(len) => {
+            let mut buf = itoa::Buffer::new();
+
+            headers.insert(
+                http::header::CONTENT_LENGTH,
+                http::HeaderValue::from_str(buf.format(*len)).unwrap(),
+            );
+        }
+    };
+
+    // copy headers
+    for (key, value) in head.headers.iter() {
+        match key {
+            // omit connection-specific headers according to:
+            // https://datatracker.ietf.org/doc/html/rfc9114#section-4.2
+            &CONNECTION | &TRANSFER_ENCODING | &UPGRADE => continue,
+
+            &CONTENT_LENGTH if skip_len => continue,
+            &DATE => has_date = true,
+
+            hdr if hdr == HeaderName::from_static("keep-alive")
+                || hdr == HeaderName::from_static("proxy-connection") =>
+            {
+                continue
+            }
+
+            _ => {}
+        }
+
+        let name = http::HeaderName::from_bytes(key.as_str().as_bytes());
+        let value = http::HeaderValue::from_bytes(value.as_bytes());
+
+        if let (Ok(name), Ok(value)) = (name, value) {
+            headers.append(name, value);
+        }
+    }
+
+    // set date header
+    if !has_date {
+        let mut bytes = BytesMut::with_capacity(29);
+        config.write_date_header_value(&mut bytes);
+        headers.insert(
+            http::header::DATE,
+            // SAFETY: serialized date-times are known ASCII strings
+            unsafe { http::HeaderValue::from_maybe_shared_unchecked(bytes.freeze()) },
+        );
+    }
+
+    res
+}
+
+/// Converts trailers to the header map type used by the `h3` crate.
+fn convert_trailers(trailers: &crate::header::HeaderMap) -> http::HeaderMap {
+    let mut headers = http::HeaderMap::with_capacity(trailers.len());
+
+    for (key, value) in trailers.iter() {
+        let name = http::HeaderName::from_bytes(key.as_str().as_bytes());
+        let value = http::HeaderValue::from_bytes(value.as_bytes());
+
+        if let (Ok(name), Ok(value)) = (name, value) {
+            headers.append(name, value);
+        }
+    }
+
+    headers
+}
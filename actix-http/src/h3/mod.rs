@@ -0,0 +1,109 @@
+//! HTTP/3 protocol.
+//!
+//! HTTP/3 runs over QUIC instead of TCP. A QUIC endpoint is not a listener that the Actix server
+//! can accept connections from, so connection attempts are accepted from a [`quinn`] endpoint and
+//! passed to [`H3Service`] by the caller:
+//!
+//! ```no_run
+//! use actix_http::{h3, HttpService, Response};
+//! use actix_service::{Service as _, ServiceFactory as _};
+//! # fn rustls_config() -> h3::reexports::rustls::ServerConfig { unimplemented!() }
+//!
+//! # actix_rt::System::new().block_on(async {
+//! let config = h3::quic_server_config(rustls_config()).unwrap();
+//! let endpoint = h3::reexports::quinn::Endpoint::server(config, "127.0.0.1:8443".parse().unwrap())
+//!     .unwrap();
+//!
+//! let srv = HttpService::build()
+//!     .h3(|_| async { Ok::<_, actix_http::Error>(Response::ok()) })
+//!     .new_service(())
+//!     .await
+//!     .unwrap();
+//!
+//! while let Some(incoming) = endpoint.accept().await {
+//!     let conn = srv.call(incoming);
+//!     actix_rt::spawn(async move { conn.await });
+//! }
+//! # });
+//! ```
+
+use std::{
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::{Buf as _, Bytes};
+use futures_core::{ready, Stream};
+use h3::server::RequestStream;
+use quinn::crypto::rustls::QuicServerConfig;
+
+use crate::error::PayloadError;
+
+mod dispatcher;
+mod service;
+
+pub use self::service::{H3Service, H3ServiceHandler};
+
+/// Re-exports of the QUIC implementation used for HTTP/3.
+pub mod reexports {
+    pub use quinn::{self, rustls};
+}
+
+/// ALPN protocol ID of HTTP/3.
+const H3_ALPN: &[u8] = b"h3";
+
+/// Creates a QUIC server config for HTTP/3 from a Rustls v0.23 server config.
+///
+/// The "h3" ALPN protocol is added to any configured ones. Returns an error if the config does not
+/// support TLS v1.3, which QUIC requires.
+pub fn quic_server_config(
+    mut config: quinn::rustls::ServerConfig,
+) -> io::Result<quinn::ServerConfig> {
+    let mut protos = vec![H3_ALPN.to_vec()];
+    protos.extend_from_slice(&config.alpn_protocols);
+    config.alpn_protocols = protos;
+
+    let config = QuicServerConfig::try_from(Arc::new(config))
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+    Ok(quinn::ServerConfig::with_crypto(Arc::new(config)))
+}
+
+/// HTTP/3 peer stream.
+pub struct Payload {
+    stream: RequestStream<h3_quinn::RecvStream, Bytes>,
+}
+
+impl Payload {
+    pub(crate) fn new(stream: RequestStream<h3_quinn::RecvStream, Bytes>) -> Self {
+        Self { stream }
+    }
+}
+
+impl Stream for Payload {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        match ready!(this.stream.poll_recv_data(cx)) {
+            Ok(Some(mut chunk)) => {
+                let len = chunk.remaining();
+                Poll::Ready(Some(Ok(chunk.copy_to_bytes(len))))
+            }
+            Ok(None) => Poll::Ready(None),
+            Err(err) => Poll::Ready(Some(Err(err.into()))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+
+    assert_impl_all!(Payload: Unpin, Send);
+}
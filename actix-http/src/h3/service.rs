@@ -0,0 +1,174 @@
+use std::{
+    marker::PhantomData,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use actix_rt::time::timeout;
+use actix_service::{IntoServiceFactory, Service, ServiceFactory};
+use futures_core::future::LocalBoxFuture;
+use tracing::{error, trace};
+
+use super::dispatcher;
+use crate::{
+    body::{BoxBody, MessageBody},
+    config::ServiceConfig,
+    error::DispatchError,
+    service::HttpFlow,
+    ConnectCallback, OnConnectData, Request, Response,
+};
+
+/// `ServiceFactory` implementation for HTTP/3 transport.
+///
+/// Its services take incoming QUIC connection attempts and serve them until the connection is
+/// closed. See the [module docs](super) for an example.
+pub struct H3Service<S, B> {
+    srv: S,
+    cfg: ServiceConfig,
+    on_connect_ext: Option<Rc<ConnectCallback<quinn::Connection>>>,
+    _phantom: PhantomData<B>,
+}
+
+impl<S, B> H3Service<S, B>
+where
+    S: ServiceFactory<Request, Config = ()>,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::Response: Into<Response<B>> + 'static,
+    <S::Service as Service<Request>>::Future: 'static,
+
+    B: MessageBody + 'static,
+{
+    /// Create new `H3Service` instance with config.
+    pub(crate) fn with_config<F: IntoServiceFactory<S, Request>>(
+        cfg: ServiceConfig,
+        service: F,
+    ) -> Self {
+        H3Service {
+            cfg,
+            on_connect_ext: None,
+            srv: service.into_factory(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Set on connect callback.
+    pub(crate) fn on_connect_ext(
+        mut self,
+        f: Option<Rc<ConnectCallback<quinn::Connection>>>,
+    ) -> Self {
+        self.on_connect_ext = f;
+        self
+    }
+}
+
+impl<S, B> ServiceFactory<quinn::Incoming> for H3Service<S, B>
+where
+    S: ServiceFactory<Request, Config = ()>,
+    S::Future: 'static,
+    S::Service: 'static,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::Response: Into<Response<B>> + 'static,
+    <S::Service as Service<Request>>::Future: 'static,
+
+    B: MessageBody + 'static,
+{
+    type Response = ();
+    type Error = DispatchError;
+    type Config = ();
+    type Service = H3ServiceHandler<S::Service, B>;
+    type InitError = S::InitError;
+    type Future = LocalBoxFuture<'static, Result<Self::Service, Self::InitError>>;
+
+    fn new_service(&self, _: ()) -> Self::Future {
+        let service = self.srv.new_service(());
+        let cfg = self.cfg.clone();
+        let on_connect_ext = self.on_connect_ext.clone();
+
+        Box::pin(async move {
+            let service = service.await?;
+            Ok(H3ServiceHandler::new(cfg, on_connect_ext, service))
+        })
+    }
+}
+
+/// `Service` implementation for HTTP/3 transport.
+pub struct H3ServiceHandler<S, B>
+where
+    S: Service<Request>,
+{
+    flow: Rc<HttpFlow<S, (), ()>>,
+    cfg: ServiceConfig,
+    on_connect_ext: Option<Rc<ConnectCallback<quinn::Connection>>>,
+    _phantom: PhantomData<B>,
+}
+
+impl<S, B> H3ServiceHandler<S, B>
+where
+    S: Service<Request>,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::Future: 'static,
+    S::Response: Into<Response<B>> + 'static,
+    B: MessageBody + 'static,
+{
+    fn new(
+        cfg: ServiceConfig,
+        on_connect_ext: Option<Rc<ConnectCallback<quinn::Connection>>>,
+        service: S,
+    ) -> H3ServiceHandler<S, B> {
+        H3ServiceHandler {
+            flow: HttpFlow::new(service, (), None),
+            cfg,
+            on_connect_ext,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<S, B> Service<quinn::Incoming> for H3ServiceHandler<S, B>
+where
+    S: Service<Request> + 'static,
+    S::Error: Into<Response<BoxBody>> + 'static,
+    S::Future: 'static,
+    S::Response: Into<Response<B>> + 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ();
+    type Error = DispatchError;
+    type Future = LocalBoxFuture<'static, Result<(), DispatchError>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.flow.service.poll_ready(cx).map_err(|err| {
+            let err = err.into();
+            error!("Service readiness error: {:?}", err);
+            DispatchError::Service(err)
+        })
+    }
+
+    fn call(&self, incoming: quinn::Incoming) -> Self::Future {
+        let flow = self.flow.clone();
+        let config = self.cfg.clone();
+        let on_connect_ext = self.on_connect_ext.clone();
+
+        Box::pin(async move {
+            let handshake = dispatcher::handshake(incoming);
+
+            let res = match config.client_request_deadline() {
+                Some(deadline) => {
+                    timeout(deadline.saturating_duration_since(config.now()), handshake)
+                        .await
+                        .unwrap_or(Err(DispatchError::SlowRequestTimeout))
+                }
+                None => handshake.await,
+            };
+
+            let (conn, h3_conn) = res.map_err(|err| {
+                trace!("H3 handshake error: {}", err);
+                err
+            })?;
+
+            let conn_data = OnConnectData::from_io(&conn, on_connect_ext.as_deref());
+
+            dispatcher::dispatch(conn, h3_conn, flow, config, conn_data).await
+        })
+    }
+}
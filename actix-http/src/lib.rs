@@ -5,6 +5,7 @@
 //! | Feature             | Functionality                               |
 //! | ------------------- | ------------------------------------------- |
 //! | `http2`             | HTTP/2 support via [h2].                    |
+//! | `http3`             | HTTP/3 support via [h3] and [quinn].        |
 //! | `openssl`           | TLS support via [OpenSSL].                  |
 //! | `rustls`            | TLS support via [rustls].                   |
 //! | `compress-brotli`   | Payload compression support: Brotli.        |
@@ -13,6 +14,8 @@
 //! | `trust-dns`         | Use [trust-dns] as the client DNS resolver. |
 //!
 //! [h2]: https://crates.io/crates/h2
+//! [h3]: https://crates.io/crates/h3
+//! [quinn]: https://crates.io/crates/quinn
 //! [OpenSSL]: https://crates.io/crates/openssl
 //! [rustls]: https://crates.io/crates/rustls
 //! [trust-dns]: https://crates.io/crates/trust-dns
@@ -34,6 +37,7 @@ pub mod body;
 mod builder;
 mod config;
 mod date;
+mod early_hints;
 #[cfg(feature = "__compress")]
 pub mod encoding;
 pub mod error;
@@ -41,6 +45,8 @@ mod extensions;
 pub mod h1;
 #[cfg(feature = "http2")]
 pub mod h2;
+#[cfg(feature = "http3")]
+pub mod h3;
 pub mod header;
 mod helpers;
 mod http_message;
@@ -67,7 +73,8 @@ pub use self::payload::PayloadStream;
 pub use self::service::TlsAcceptorConfig;
 pub use self::{
     builder::HttpServiceBuilder,
-    config::ServiceConfig,
+    config::{H1Limits, H2Settings, ServiceConfig},
+    early_hints::EarlyHints,
     error::Error,
     extensions::Extensions,
     header::ContentEncoding,
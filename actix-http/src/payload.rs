@@ -8,7 +8,7 @@ use bytes::Bytes;
 use futures_core::Stream;
 use pin_project_lite::pin_project;
 
-use crate::error::PayloadError;
+use crate::{error::PayloadError, header::HeaderMap};
 
 /// A boxed payload stream.
 pub type BoxedPayloadStream = Pin<Box<dyn Stream<Item = Result<Bytes, PayloadError>>>>;
@@ -73,6 +73,21 @@ impl<S> Payload<S> {
     pub fn take(&mut self) -> Payload<S> {
         mem::replace(self, Payload::None)
     }
+
+    /// Takes the trailer fields sent after the request body.
+    ///
+    /// Trailers are only available once the payload stream has ended and are only received on
+    /// chunked HTTP/1.1 and on HTTP/2 payloads.
+    pub fn take_trailers(&mut self) -> Option<HeaderMap> {
+        match self {
+            Payload::H1 { payload } => payload.take_trailers(),
+
+            #[cfg(feature = "http2")]
+            Payload::H2 { payload } => payload.take_trailers(),
+
+            Payload::None | Payload::Stream { .. } => None,
+        }
+    }
 }
 
 impl<S> Stream for Payload<S>
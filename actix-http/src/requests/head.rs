@@ -1,5 +1,7 @@
 use std::{net, rc::Rc};
 
+use bytestring::ByteString;
+
 use crate::{
     header::{self, HeaderMap},
     message::{Flags, Head, MessagePool},
@@ -20,6 +22,7 @@ pub struct RequestHead {
     /// Will only be None when called in unit tests unless set manually.
     pub peer_addr: Option<net::SocketAddr>,
 
+    protocol: Option<ByteString>,
     flags: Flags,
 }
 
@@ -31,6 +34,7 @@ impl Default for RequestHead {
             version: Version::HTTP_11,
             headers: HeaderMap::with_capacity(16),
             peer_addr: None,
+            protocol: None,
             flags: Flags::empty(),
         }
     }
@@ -39,6 +43,7 @@ impl Default for RequestHead {
 impl Head for RequestHead {
     fn clear(&mut self) {
         self.flags = Flags::empty();
+        self.protocol = None;
         self.headers.clear();
     }
 
@@ -143,6 +148,22 @@ impl RequestHead {
     pub(crate) fn set_expect(&mut self) {
         self.flags.insert(Flags::EXPECT);
     }
+
+    /// Returns the protocol of an extended CONNECT request (RFC 8441), like `websocket`.
+    ///
+    /// HTTP/2 extended CONNECT requests for the `websocket` protocol are presented with the `GET`
+    /// method, like the HTTP/1.1 upgrade requests they replace, so that the same routes and
+    /// handshake functions handle both.
+    #[inline]
+    pub fn protocol(&self) -> Option<&str> {
+        self.protocol.as_deref()
+    }
+
+    /// Sets the protocol of an extended CONNECT request.
+    #[inline]
+    pub fn set_protocol(&mut self, protocol: impl Into<ByteString>) {
+        self.protocol = Some(protocol.into());
+    }
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -63,6 +63,7 @@ pub struct HttpService<T, S, B, X = h1::ExpectHandler, U = h1::UpgradeHandler> {
     expect: X,
     upgrade: Option<U>,
     on_connect_ext: Option<Rc<ConnectCallback<T>>>,
+    h2c_upgrade: bool,
     _phantom: PhantomData<B>,
 }
 
@@ -98,6 +99,7 @@ where
             expect: h1::ExpectHandler,
             upgrade: None,
             on_connect_ext: None,
+            h2c_upgrade: false,
             _phantom: PhantomData,
         }
     }
@@ -113,6 +115,7 @@ where
             expect: h1::ExpectHandler,
             upgrade: None,
             on_connect_ext: None,
+            h2c_upgrade: false,
             _phantom: PhantomData,
         }
     }
@@ -143,6 +146,7 @@ where
             srv: self.srv,
             upgrade: self.upgrade,
             on_connect_ext: self.on_connect_ext,
+            h2c_upgrade: self.h2c_upgrade,
             _phantom: PhantomData,
         }
     }
@@ -163,6 +167,7 @@ where
             srv: self.srv,
             expect: self.expect,
             on_connect_ext: self.on_connect_ext,
+            h2c_upgrade: self.h2c_upgrade,
             _phantom: PhantomData,
         }
     }
@@ -211,11 +216,18 @@ where
 
     /// Creates TCP stream service from HTTP service that automatically selects HTTP/1.x or HTTP/2
     /// on plaintext connections.
+    ///
+    /// HTTP/2 is selected when a connection starts with the HTTP/2 preface (prior knowledge) or,
+    /// unless disabled with [`H2Settings::h2c_upgrade`](crate::H2Settings::h2c_upgrade), when the
+    /// first HTTP/1.1 request asks to upgrade with `Upgrade: h2c`. Upgraded requests are served on
+    /// the first stream of the new HTTP/2 connection.
     #[cfg(feature = "http2")]
     pub fn tcp_auto_h2c(
-        self,
+        mut self,
     ) -> impl ServiceFactory<TcpStream, Config = (), Response = (), Error = DispatchError, InitError = ()>
     {
+        self.h2c_upgrade = self.cfg.h2_settings().h2c_upgrade;
+
         fn_service(move |io: TcpStream| async move {
             // subset of HTTP/2 preface defined by RFC 9113 §3.4
             // this subset was chosen to maximize likelihood that peeking only once will allow us to
@@ -687,6 +699,7 @@ where
         let upgrade = self.upgrade.as_ref().map(|s| s.new_service(()));
         let on_connect_ext = self.on_connect_ext.clone();
         let cfg = self.cfg.clone();
+        let h2c_upgrade = self.h2c_upgrade;
 
         Box::pin(async move {
             let expect = expect
@@ -707,13 +720,11 @@ where
                 .await
                 .map_err(|e| error!("Init http service error: {:?}", e))?;
 
-            Ok(HttpServiceHandler::new(
-                cfg,
-                service,
-                expect,
-                upgrade,
-                on_connect_ext,
-            ))
+            let mut handler =
+                HttpServiceHandler::new(cfg, service, expect, upgrade, on_connect_ext);
+            handler.h2c_upgrade = h2c_upgrade;
+
+            Ok(handler)
         })
     }
 }
@@ -728,6 +739,8 @@ where
     pub(super) flow: Rc<HttpFlow<S, X, U>>,
     pub(super) cfg: ServiceConfig,
     pub(super) on_connect_ext: Option<Rc<ConnectCallback<T>>>,
+    #[cfg_attr(not(feature = "http2"), allow(dead_code))]
+    h2c_upgrade: bool,
     _phantom: PhantomData<B>,
 }
 
@@ -751,6 +764,7 @@ where
             cfg,
             on_connect_ext,
             flow: HttpFlow::new(service, expect, upgrade),
+            h2c_upgrade: false,
             _phantom: PhantomData,
         }
     }
@@ -836,6 +850,14 @@ where
                 panic!("HTTP/2 support is disabled (enable with the `http2` feature flag)")
             }
 
+            #[cfg(feature = "http2")]
+            Protocol::Http1 if self.h2c_upgrade => HttpServiceHandlerResponse {
+                state: State::H2cProbe {
+                    probe: crate::h2::H2cProbe::new(io, self.cfg.clone()),
+                    data: Some((self.cfg.clone(), self.flow.clone(), conn_data, peer_addr)),
+                },
+            },
+
             Protocol::Http1 => HttpServiceHandlerResponse {
                 state: State::H1 {
                     dispatcher: h1::Dispatcher::new(
@@ -903,6 +925,8 @@ pin_project! {
 
         H2 { #[pin] dispatcher: crate::h2::Dispatcher<T, S, B, X, U> },
 
+        H2c { #[pin] dispatcher: crate::h2::Dispatcher<crate::h2::H2cIo<T>, S, B, X, U> },
+
         H2Handshake {
             handshake: Option<(
                 crate::h2::HandshakeWithTimeout<T>,
@@ -912,6 +936,26 @@ pin_project! {
                 Option<net::SocketAddr>,
             )>,
         },
+
+        H2cProbe {
+            probe: crate::h2::H2cProbe<T>,
+            data: Option<(
+                ServiceConfig,
+                Rc<HttpFlow<S, X, U>>,
+                OnConnectData,
+                Option<net::SocketAddr>,
+            )>,
+        },
+
+        H2cHandshake {
+            handshake: Option<(
+                crate::h2::HandshakeWithTimeout<crate::h2::H2cIo<T>>,
+                ServiceConfig,
+                Rc<HttpFlow<S, X, U>>,
+                OnConnectData,
+                Option<net::SocketAddr>,
+            )>,
+        },
     }
 }
 
@@ -968,6 +1012,9 @@ where
             #[cfg(feature = "http2")]
             StateProj::H2 { dispatcher } => dispatcher.poll(cx),
 
+            #[cfg(feature = "http2")]
+            StateProj::H2c { dispatcher } => dispatcher.poll(cx),
+
             #[cfg(feature = "http2")]
             StateProj::H2Handshake { handshake: data } => {
                 match ready!(Pin::new(&mut data.as_mut().unwrap().0).poll(cx)) {
@@ -987,6 +1034,62 @@ where
                     }
                 }
             }
+
+            #[cfg(feature = "http2")]
+            StateProj::H2cProbe { probe, data } => {
+                let output = ready!(Pin::new(probe).poll(cx))?;
+                let (config, flow, conn_data, peer_addr) = data.take().unwrap();
+
+                match output {
+                    crate::h2::H2cProbeOutput::Http1(io, read_buf, head_deadline) => {
+                        self.as_mut().project().state.set(State::H1 {
+                            dispatcher: h1::Dispatcher::with_read_buf(
+                                io,
+                                flow,
+                                config,
+                                peer_addr,
+                                conn_data,
+                                read_buf,
+                                head_deadline,
+                            ),
+                        });
+                    }
+
+                    crate::h2::H2cProbeOutput::Http2(io) => {
+                        self.as_mut().project().state.set(State::H2cHandshake {
+                            handshake: Some((
+                                crate::h2::handshake_with_timeout(io, &config),
+                                config,
+                                flow,
+                                conn_data,
+                                peer_addr,
+                            )),
+                        });
+                    }
+                }
+
+                self.poll(cx)
+            }
+
+            #[cfg(feature = "http2")]
+            StateProj::H2cHandshake { handshake: data } => {
+                match ready!(Pin::new(&mut data.as_mut().unwrap().0).poll(cx)) {
+                    Ok((conn, timer)) => {
+                        let (_, config, flow, conn_data, peer_addr) = data.take().unwrap();
+
+                        self.as_mut().project().state.set(State::H2c {
+                            dispatcher: crate::h2::Dispatcher::new(
+                                conn, flow, config, peer_addr, conn_data, timer,
+                            ),
+                        });
+                        self.poll(cx)
+                    }
+                    Err(err) => {
+                        tracing::trace!("H2 handshake error after h2c upgrade: {}", err);
+                        Poll::Ready(Err(err))
+                    }
+                }
+            }
         }
     }
 }
@@ -152,12 +152,19 @@ pub fn handshake(req: &RequestHead) -> Result<ResponseBuilder, HandshakeError> {
 }
 
 /// Verify WebSocket handshake request.
+///
+/// Both HTTP/1.1 upgrade requests and HTTP/2 extended CONNECT requests (RFC 8441) are accepted.
 pub fn verify_handshake(req: &RequestHead) -> Result<(), HandshakeError> {
     // WebSocket accepts only GET
     if req.method != Method::GET {
         return Err(HandshakeError::GetMethodRequired);
     }
 
+    if is_extended_connect(req) {
+        // extended CONNECT has no upgrade headers or key
+        return verify_version(req);
+    }
+
     // Check for "UPGRADE" to WebSocket header
     let has_hdr = if let Some(hdr) = req.headers().get(header::UPGRADE) {
         if let Ok(s) = hdr.to_str() {
@@ -177,6 +184,16 @@ pub fn verify_handshake(req: &RequestHead) -> Result<(), HandshakeError> {
         return Err(HandshakeError::NoConnectionUpgrade);
     }
 
+    verify_version(req)?;
+
+    // check client handshake for validity
+    if !req.headers().contains_key(header::SEC_WEBSOCKET_KEY) {
+        return Err(HandshakeError::BadWebsocketKey);
+    }
+    Ok(())
+}
+
+fn verify_version(req: &RequestHead) -> Result<(), HandshakeError> {
     // check supported version
     if !req.headers().contains_key(header::SEC_WEBSOCKET_VERSION) {
         return Err(HandshakeError::NoVersionHeader);
@@ -191,18 +208,23 @@ pub fn verify_handshake(req: &RequestHead) -> Result<(), HandshakeError> {
     if !supported_ver {
         return Err(HandshakeError::UnsupportedVersion);
     }
-
-    // check client handshake for validity
-    if !req.headers().contains_key(header::SEC_WEBSOCKET_KEY) {
-        return Err(HandshakeError::BadWebsocketKey);
-    }
     Ok(())
 }
 
+/// Returns true if the request is an extended CONNECT request for the WebSocket protocol.
+fn is_extended_connect(req: &RequestHead) -> bool {
+    req.protocol() == Some("websocket")
+}
+
 /// Create WebSocket handshake response.
 ///
-/// This function returns handshake `Response`, ready to send to peer.
+/// This function returns handshake `Response`, ready to send to peer. Extended CONNECT requests
+/// are accepted with a `200 OK` response instead of `101 Switching Protocols`.
 pub fn handshake_response(req: &RequestHead) -> ResponseBuilder {
+    if is_extended_connect(req) {
+        return Response::build(StatusCode::OK);
+    }
+
     let key = {
         let key = req.headers().get(header::SEC_WEBSOCKET_KEY).unwrap();
         proto::hash_key(key.as_ref())
@@ -333,6 +355,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_extended_connect_handshake() {
+        let mut req = TestRequest::default().finish();
+        req.head_mut().set_protocol("websocket");
+        assert_eq!(
+            HandshakeError::NoVersionHeader,
+            verify_handshake(req.head()).unwrap_err(),
+        );
+
+        let mut req = TestRequest::default()
+            .insert_header((
+                header::SEC_WEBSOCKET_VERSION,
+                header::HeaderValue::from_static("13"),
+            ))
+            .finish();
+        req.head_mut().set_protocol("websocket");
+        verify_handshake(req.head()).unwrap();
+
+        let res = handshake_response(req.head()).finish();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res.headers().contains_key(header::SEC_WEBSOCKET_ACCEPT));
+    }
+
     #[test]
     fn test_ws_error_http_response() {
         let resp: Response<BoxBody> = HandshakeError::GetMethodRequired.into();
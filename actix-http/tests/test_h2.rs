@@ -0,0 +1,405 @@
+use std::{io, time::Duration};
+
+use actix_codec::{Decoder as _, Encoder as _};
+use actix_http::{
+    body::{BodyStream, WithTrailers},
+    error::Error,
+    header::{HeaderMap, HeaderName, HeaderValue},
+    ws, H2Settings, HttpService, KeepAlive, Method, Request, Response,
+};
+use actix_rt::time::sleep;
+use actix_server::Server;
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt as _;
+
+#[actix_rt::test]
+async fn h2_settings() -> io::Result<()> {
+    let lst = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = lst.local_addr().unwrap();
+
+    let srv = Server::build()
+        .disable_signals()
+        .workers(1)
+        .listen("h2_settings", lst, || {
+            HttpService::build()
+                .h2_settings(
+                    H2Settings::new()
+                        .max_concurrent_streams(7)
+                        .initial_window_size(1024 * 1024),
+                )
+                .h2(|_| async { Ok::<_, Error>(Response::ok()) })
+                .tcp()
+        })?
+        .run();
+
+    let handle = srv.handle();
+    actix_rt::spawn(srv);
+
+    let stream = tokio::net::TcpStream::connect(addr).await?;
+    let (mut tx, mut conn) = h2::client::handshake(stream).await.unwrap();
+
+    let (res, _) = tx.send_request(::http::Request::new(()), true).unwrap();
+
+    let res = tokio::select! {
+        res = res => res.unwrap(),
+        _ = &mut conn => panic!("connection closed before response"),
+    };
+
+    assert_eq!(res.status().as_u16(), 200);
+    assert_eq!(conn.max_concurrent_send_streams(), 7);
+
+    handle.stop(false).await;
+
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn h2_response_trailers() -> io::Result<()> {
+    let lst = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = lst.local_addr().unwrap();
+
+    let srv = Server::build()
+        .disable_signals()
+        .workers(1)
+        .listen("h2_trailers", lst, || {
+            HttpService::build()
+                .h2(|_| async {
+                    let mut trailers = HeaderMap::new();
+                    trailers.insert(
+                        HeaderName::from_static("grpc-status"),
+                        HeaderValue::from_static("0"),
+                    );
+
+                    Ok::<_, Error>(Response::ok().set_body(WithTrailers::new("hello", trailers)))
+                })
+                .tcp()
+        })?
+        .run();
+
+    let handle = srv.handle();
+    actix_rt::spawn(srv);
+
+    let stream = tokio::net::TcpStream::connect(addr).await?;
+    let (mut client, conn) = h2::client::handshake(stream).await.unwrap();
+    actix_rt::spawn(conn);
+
+    let (res, _) = client.send_request(::http::Request::new(()), true).unwrap();
+    let mut body = res.await.unwrap().into_body();
+
+    assert_eq!(body.data().await.unwrap().unwrap(), "hello");
+    assert!(body.data().await.is_none());
+
+    let trailers = body.trailers().await.unwrap().unwrap();
+    assert_eq!(trailers.get("grpc-status").unwrap(), "0");
+
+    handle.stop(false).await;
+
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn h2_request_trailers() -> io::Result<()> {
+    let lst = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = lst.local_addr().unwrap();
+
+    let srv = Server::build()
+        .disable_signals()
+        .workers(1)
+        .listen("h2_request_trailers", lst, || {
+            HttpService::build()
+                .h2(|mut req: Request| async move {
+                    let mut pl = req.take_payload();
+                    let mut body = BytesMut::new();
+
+                    while let Some(chunk) = pl.next().await {
+                        body.extend_from_slice(&chunk?);
+                    }
+
+                    let checksum = pl.take_trailers().unwrap().remove("x-checksum").next();
+
+                    Ok::<_, Error>(Response::ok().set_body(format!(
+                        "{}:{}",
+                        String::from_utf8_lossy(&body),
+                        checksum.unwrap().to_str().unwrap(),
+                    )))
+                })
+                .tcp()
+        })?
+        .run();
+
+    let handle = srv.handle();
+    actix_rt::spawn(srv);
+
+    let stream = tokio::net::TcpStream::connect(addr).await?;
+    let (mut client, conn) = h2::client::handshake(stream).await.unwrap();
+    actix_rt::spawn(conn);
+
+    let req = ::http::Request::post("/").body(()).unwrap();
+    let (res, mut send) = client.send_request(req, false).unwrap();
+
+    let mut trailers = ::http::HeaderMap::new();
+    trailers.insert("x-checksum", ::http::HeaderValue::from_static("abc"));
+
+    send.send_data(Bytes::from_static(b"hello"), false).unwrap();
+    send.send_trailers(trailers).unwrap();
+
+    let mut body = res.await.unwrap().into_body();
+    assert_eq!(body.data().await.unwrap().unwrap(), "hello:abc");
+
+    handle.stop(false).await;
+
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn h2_goaway_on_graceful_shutdown() -> io::Result<()> {
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(());
+
+    let lst = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = lst.local_addr().unwrap();
+
+    let join = std::thread::spawn(move || {
+        actix_rt::System::new().block_on(async move {
+            let srv = Server::build()
+                .disable_signals()
+                .workers(1)
+                .listen("h2_goaway", lst, move || {
+                    let shutdown_rx = shutdown_rx.clone();
+
+                    HttpService::build()
+                        // no pings, so only GOAWAY can close an idle connection
+                        .keep_alive(KeepAlive::Disabled)
+                        .graceful_shutdown_signal(move || {
+                            let mut shutdown_rx = shutdown_rx.clone();
+                            async move {
+                                let _ = shutdown_rx.changed().await;
+                            }
+                        })
+                        .h2(|_| async {
+                            sleep(Duration::from_millis(500)).await;
+                            Ok::<_, Error>(Response::ok())
+                        })
+                        .tcp()
+                })?
+                .run();
+
+            tx.send(srv.handle()).unwrap();
+
+            srv.await
+        })
+    });
+
+    let handle = rx.recv().unwrap();
+
+    let stream = tokio::net::TcpStream::connect(addr).await?;
+    let (mut client, conn) = h2::client::handshake(stream).await.unwrap();
+    let conn = actix_rt::spawn(conn);
+
+    let (res, _) = client.send_request(::http::Request::new(()), true).unwrap();
+
+    // give the request time to reach the service before stopping the server
+    sleep(Duration::from_millis(100)).await;
+
+    let now = std::time::Instant::now();
+    shutdown_tx.send(()).unwrap();
+    let stop = actix_rt::spawn(handle.stop(true));
+
+    // in-flight request is still served
+    let res = res.await.unwrap();
+    assert_eq!(res.status().as_u16(), 200);
+
+    // GOAWAY closes the connection cleanly once the in-flight stream is finished
+    conn.await.unwrap().unwrap();
+    assert!(client.send_request(::http::Request::new(()), true).is_err());
+
+    stop.await.unwrap();
+    join.join().unwrap()?;
+
+    // server shutdown is not waiting for graceful shutdown timeout
+    assert!(now.elapsed() < Duration::from_secs(30));
+
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn h2_extended_connect_websocket() -> io::Result<()> {
+    let lst = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = lst.local_addr().unwrap();
+
+    let srv = Server::build()
+        .disable_signals()
+        .workers(1)
+        .listen("h2_ws", lst, || {
+            HttpService::build()
+                .h2(|mut req: Request| async move {
+                    if req.head().protocol().is_none() {
+                        return Ok(Response::ok().map_into_boxed_body());
+                    }
+
+                    assert_eq!(req.method(), Method::GET);
+                    assert_eq!(req.head().protocol(), Some("websocket"));
+
+                    let mut res = ws::handshake(req.head())?;
+                    let mut payload = req.take_payload();
+
+                    // echo the first text frame
+                    let body = async_stream::stream! {
+                        let mut codec = ws::Codec::new();
+                        let mut buf = BytesMut::new();
+
+                        while let Some(chunk) = payload.next().await {
+                            buf.extend_from_slice(&chunk?);
+
+                            if let Some(ws::Frame::Text(text)) = codec.decode(&mut buf)? {
+                                let text = String::from_utf8(text.to_vec()).unwrap();
+                                let mut out = BytesMut::new();
+                                codec.encode(ws::Message::Text(text.into()), &mut out)?;
+                                yield Ok::<_, Error>(out.freeze());
+                                break;
+                            }
+                        }
+                    };
+
+                    Ok::<_, Error>(res.body(BodyStream::new(body)).map_into_boxed_body())
+                })
+                .tcp()
+        })?
+        .run();
+
+    let handle = srv.handle();
+    actix_rt::spawn(srv);
+
+    let stream = tokio::net::TcpStream::connect(addr).await?;
+    let (client, conn) = h2::client::handshake(stream).await.unwrap();
+    actix_rt::spawn(conn);
+
+    // settings are received by the time a first response arrives
+    let mut client = client.ready().await.unwrap();
+    let (res, _) = client.send_request(::http::Request::new(()), true).unwrap();
+    res.await.unwrap();
+    assert!(client.is_extended_connect_protocol_enabled());
+
+    let mut req = ::http::Request::builder()
+        .method(Method::CONNECT)
+        .uri(format!("http://{addr}/ws"))
+        .header("sec-websocket-version", "13")
+        .body(())
+        .unwrap();
+    req.extensions_mut()
+        .insert(h2::ext::Protocol::from_static("websocket"));
+
+    let (res, mut send) = client.send_request(req, false).unwrap();
+    let res = res.await.unwrap();
+    assert_eq!(res.status().as_u16(), 200);
+    assert!(!res.headers().contains_key("sec-websocket-accept"));
+
+    let mut codec = ws::Codec::new().client_mode();
+    let mut buf = BytesMut::new();
+    codec
+        .encode(ws::Message::Text("hello".into()), &mut buf)
+        .unwrap();
+    send.send_data(buf.freeze(), false).unwrap();
+
+    let mut body = res.into_body();
+    let mut buf = BytesMut::new();
+    let frame = loop {
+        buf.extend_from_slice(&body.data().await.unwrap().unwrap());
+
+        if let Some(frame) = codec.decode(&mut buf).unwrap() {
+            break frame;
+        }
+    };
+    assert_eq!(frame, ws::Frame::Text(Bytes::from_static(b"hello")));
+
+    handle.stop(false).await;
+
+    Ok(())
+}
+
+#[actix_rt::test]
+async fn h2c_upgrade() -> io::Result<()> {
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    let lst = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let addr = lst.local_addr().unwrap();
+
+    let srv = Server::build()
+        .disable_signals()
+        .workers(1)
+        .listen("h2c_upgrade", lst, || {
+            HttpService::build()
+                .finish(|req: Request| async move {
+                    let body = format!("{:?} {}", req.version(), req.path());
+                    Ok::<_, Error>(Response::ok().set_body(body))
+                })
+                .tcp_auto_h2c()
+        })?
+        .run();
+
+    let handle = srv.handle();
+    actix_rt::spawn(srv);
+
+    // requests with a body are served over HTTP/1.1
+    let mut stream = tokio::net::TcpStream::connect(addr).await?;
+    stream
+        .write_all(
+            b"POST /body HTTP/1.1\r\nconnection: upgrade, http2-settings\r\nupgrade: h2c\r\n\
+            http2-settings: AAMAAABkAAQAAP__\r\ncontent-length: 4\r\n\r\ntest",
+        )
+        .await?;
+
+    let mut buf = vec![0; 1024];
+    let n = stream.read(&mut buf).await?;
+    let res = String::from_utf8_lossy(&buf[..n]);
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(res.ends_with("HTTP/1.1 /body"));
+
+    let mut stream = tokio::net::TcpStream::connect(addr).await?;
+    stream
+        .write_all(
+            b"GET /upgraded HTTP/1.1\r\nhost: localhost\r\nconnection: Upgrade, HTTP2-Settings\r\n\
+            upgrade: h2c\r\nhttp2-settings: AAMAAABkAAQAAP__\r\n\r\n",
+        )
+        .await?;
+
+    let switching =
+        b"HTTP/1.1 101 Switching Protocols\r\nconnection: upgrade\r\nupgrade: h2c\r\n\r\n";
+    let mut buf = vec![0; switching.len()];
+    stream.read_exact(&mut buf).await?;
+    assert_eq!(buf, switching);
+
+    // connection preface followed by an empty SETTINGS frame
+    stream
+        .write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")
+        .await?;
+    stream.write_all(&[0, 0, 0, 4, 0, 0, 0, 0, 0]).await?;
+
+    // collect the response body sent on stream 1
+    let mut body = Vec::new();
+    loop {
+        let mut head = [0; 9];
+        stream.read_exact(&mut head).await?;
+
+        let len = (head[0] as usize) << 16 | (head[1] as usize) << 8 | head[2] as usize;
+        let stream_id = u32::from_be_bytes([head[5], head[6], head[7], head[8]]);
+
+        let mut payload = vec![0; len];
+        stream.read_exact(&mut payload).await?;
+
+        // DATA frame
+        if head[3] == 0 && stream_id == 1 {
+            body.extend_from_slice(&payload);
+
+            // END_STREAM flag
+            if head[4] & 0x1 != 0 {
+                break;
+            }
+        }
+    }
+    assert_eq!(body, b"HTTP/2.0 /upgraded");
+
+    handle.stop(false).await;
+
+    Ok(())
+}
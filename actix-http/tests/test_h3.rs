@@ -0,0 +1,127 @@
+#![cfg(feature = "http3")]
+
+use std::{io, net::SocketAddr, sync::Arc};
+
+use actix_http::{
+    h3::{
+        self,
+        reexports::{quinn, rustls},
+    },
+    Error, HttpService, Request, Response, StatusCode, Version,
+};
+use actix_service::{Service as _, ServiceFactory as _};
+use actix_utils::future::poll_fn;
+use bytes::{Buf as _, Bytes, BytesMut};
+use futures_util::StreamExt as _;
+use quinn::crypto::rustls::QuicClientConfig;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+
+fn provider() -> Arc<rustls::crypto::CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn tls_configs() -> (rustls::ServerConfig, rustls::ClientConfig) {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+    let cert_der = CertificateDer::from(cert.serialize_der().unwrap());
+    let key_der = PrivatePkcs8KeyDer::from(cert.serialize_private_key_der());
+
+    let server = rustls::ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der.clone()], PrivateKeyDer::Pkcs8(key_der))
+        .unwrap();
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(cert_der).unwrap();
+
+    let mut client = rustls::ClientConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    client.alpn_protocols = vec![b"h3".to_vec()];
+
+    (server, client)
+}
+
+/// Starts an HTTP/3 server on a loopback QUIC endpoint and returns its address.
+fn start_server(server_config: rustls::ServerConfig) -> io::Result<SocketAddr> {
+    let config = h3::quic_server_config(server_config)?;
+    let endpoint = quinn::Endpoint::server(config, "127.0.0.1:0".parse().unwrap())?;
+    let addr = endpoint.local_addr()?;
+
+    actix_rt::spawn(async move {
+        let srv = HttpService::build()
+            .h3(|mut req: Request| async move {
+                assert_eq!(req.version(), Version::HTTP_3);
+                assert!(req.peer_addr().is_some());
+
+                let mut body = BytesMut::new();
+                while let Some(chunk) = req.payload().next().await {
+                    body.extend_from_slice(&chunk?);
+                }
+
+                let res = Response::build(StatusCode::OK)
+                    .insert_header(("x-method", req.method().as_str()))
+                    .body(body.freeze());
+
+                Ok::<_, Error>(res)
+            })
+            .new_service(())
+            .await
+            .unwrap();
+
+        while let Some(incoming) = endpoint.accept().await {
+            let conn = srv.call(incoming);
+            actix_rt::spawn(async move { conn.await.unwrap() });
+        }
+    });
+
+    Ok(addr)
+}
+
+#[actix_rt::test]
+async fn h3_echo() -> io::Result<()> {
+    let (server_config, client_config) = tls_configs();
+    let addr = start_server(server_config)?;
+
+    let client_config = QuicClientConfig::try_from(client_config).unwrap();
+    let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap())?;
+    endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(client_config)));
+
+    let conn = endpoint.connect(addr, "localhost").unwrap().await?;
+    let (mut driver, mut client) = ::h3::client::new(h3_quinn::Connection::new(conn))
+        .await
+        .unwrap();
+    actix_rt::spawn(async move { poll_fn(|cx| driver.poll_close(cx)).await });
+
+    for body in ["hello", "world"] {
+        let req = http_1::Request::post(format!("https://localhost:{}/", addr.port()))
+            .body(())
+            .unwrap();
+
+        let mut stream = client.send_request(req).await.unwrap();
+        stream.send_data(Bytes::from(body)).await.unwrap();
+        stream.finish().await.unwrap();
+
+        let res = stream.recv_response().await.unwrap();
+        assert_eq!(res.status(), http_1::StatusCode::OK);
+        assert_eq!(res.headers()["x-method"], "POST");
+        assert_eq!(
+            res.headers()["content-length"],
+            body.len().to_string().as_str()
+        );
+        assert!(res.headers().contains_key("date"));
+
+        let mut buf = BytesMut::new();
+        while let Some(mut chunk) = stream.recv_data().await.unwrap() {
+            buf.extend_from_slice(&chunk.copy_to_bytes(chunk.remaining()));
+        }
+        assert_eq!(buf, body.as_bytes());
+    }
+
+    endpoint.close(0u32.into(), b"");
+
+    Ok(())
+}
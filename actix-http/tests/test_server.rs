@@ -7,7 +7,7 @@ use std::{
 
 use actix_http::{
     body::{self, BodyStream, BoxBody, SizedStream},
-    header, Error, HttpService, KeepAlive, Request, Response, StatusCode, Version,
+    header, Error, H1Limits, HttpService, KeepAlive, Request, Response, StatusCode, Version,
 };
 use actix_http_test::test_server;
 use actix_rt::{net::TcpStream, time::sleep};
@@ -246,6 +246,143 @@ async fn http1_malformed_request() {
     srv.stop().await;
 }
 
+#[actix_rt::test]
+async fn http1_head_limits() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1_limits(
+                H1Limits::new()
+                    .max_headers(4)
+                    .max_request_line_size(64)
+                    .max_header_value_size(32),
+            )
+            .h1(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp()
+    })
+    .await;
+
+    let send = |req: String| {
+        let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+        let _ = stream.write_all(req.as_bytes());
+        let mut data = String::new();
+        let _ = stream.read_to_string(&mut data);
+        data
+    };
+
+    let data = send("GET /test HTTP/1.1\r\nconnection: close\r\n\r\n".to_owned());
+    assert!(data.starts_with("HTTP/1.1 200 OK"));
+
+    let data = send(format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(64)));
+    assert!(data.starts_with("HTTP/1.1 414 URI Too Long"));
+
+    let data = send("GET / HTTP/1.1\r\na: 1\r\nb: 2\r\nc: 3\r\nd: 4\r\ne: 5\r\n\r\n".to_owned());
+    assert!(data.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+    let data = send(format!(
+        "GET / HTTP/1.1\r\nx-long: {}\r\n\r\n",
+        "a".repeat(33)
+    ));
+    assert!(data.starts_with("HTTP/1.1 431 Request Header Fields Too Large"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn h1_response_trailers() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1(|_| {
+                let mut trailers = header::HeaderMap::new();
+                trailers.insert(
+                    header::HeaderName::from_static("x-checksum"),
+                    header::HeaderValue::from_static("abc"),
+                );
+
+                ok::<_, Infallible>(
+                    Response::ok().set_body(body::WithTrailers::new("hello", trailers)),
+                )
+            })
+            .tcp()
+    })
+    .await;
+
+    let send = |req: &str| {
+        let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+        let _ = stream.write_all(req.as_bytes());
+        let mut data = String::new();
+        let _ = stream.read_to_string(&mut data);
+        data
+    };
+
+    let data = send("GET / HTTP/1.1\r\nte: trailers\r\nconnection: close\r\n\r\n");
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.ends_with("\r\n\r\n5\r\nhello\r\n0\r\nx-checksum: abc\r\n\r\n"));
+
+    // trailers are only sent to clients that accept them
+    let data = send("GET / HTTP/1.1\r\nconnection: close\r\n\r\n");
+    assert!(data.ends_with("\r\n\r\n5\r\nhello\r\n0\r\n\r\n"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn h1_request_trailers() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1_limits(H1Limits::new().max_trailer_size(64))
+            .h1(|mut req: Request| async move {
+                let mut pl = req.take_payload();
+                let mut body = Vec::new();
+
+                while let Some(chunk) = pl.next().await {
+                    body.extend_from_slice(&chunk?);
+                }
+
+                let checksum = pl
+                    .take_trailers()
+                    .and_then(|trailers| trailers.get("x-checksum").cloned());
+
+                Ok::<_, Error>(Response::ok().set_body(format!(
+                    "{}:{}",
+                    String::from_utf8_lossy(&body),
+                    checksum.as_ref().map_or("none", |val| val.to_str().unwrap()),
+                )))
+            })
+            .tcp()
+    })
+    .await;
+
+    let send = |req: &str| {
+        let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+        let _ = stream.write_all(req.as_bytes());
+        let mut data = String::new();
+        let _ = stream.read_to_string(&mut data);
+        data
+    };
+
+    let data = send(
+        "POST / HTTP/1.1\r\nconnection: close\r\ntransfer-encoding: chunked\r\n\r\n\
+         5\r\nhello\r\n0\r\nx-checksum: abc\r\n\r\n",
+    );
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.ends_with("\r\n\r\nhello:abc"));
+
+    let data = send(
+        "POST / HTTP/1.1\r\nconnection: close\r\ntransfer-encoding: chunked\r\n\r\n\
+         5\r\nhello\r\n0\r\n\r\n",
+    );
+    assert!(data.ends_with("\r\n\r\nhello:none"));
+
+    // trailer sections that exceed the limit fail the payload
+    let data = send(
+        "POST / HTTP/1.1\r\nconnection: close\r\ntransfer-encoding: chunked\r\n\r\n\
+         5\r\nhello\r\n0\r\nx-checksum: 0123456789012345678901234567890123456789012345678901234567890123456789\r\n\r\n",
+    );
+    assert!(!data.starts_with("HTTP/1.1 200 OK\r\n"));
+
+    srv.stop().await;
+}
+
 #[actix_rt::test]
 async fn http1_keepalive() {
     let mut srv = test_server(|| {
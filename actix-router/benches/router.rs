@@ -179,6 +179,21 @@ fn compare_routers(c: &mut Criterion) {
         });
     });
 
+    let mut actix_radix = actix_router::Router::<bool>::build();
+    actix_radix.radix_tree(true);
+    for route in register!(brackets) {
+        actix_radix.path(route, true);
+    }
+    let actix_radix = actix_radix.finish();
+    group.bench_function("actix-radix", |b| {
+        b.iter(|| {
+            for route in call() {
+                let mut path = actix_router::Path::new(route);
+                black_box(actix_radix.recognize(&mut path).unwrap());
+            }
+        });
+    });
+
     let regex_set = regex::RegexSet::new(register!(regex)).unwrap();
     group.bench_function("regex", |b| {
         b.iter(|| {
@@ -191,5 +206,48 @@ fn compare_routers(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, compare_routers);
+fn large_route_table(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Large Route Table");
+
+    let routes = (0..200)
+        .flat_map(|i| {
+            [
+                format!("/service{}", i),
+                format!("/service{}/items", i),
+                format!("/service{}/items/{{id}}", i),
+                format!("/service{}/items/{{id}}/history", i),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let paths = [
+        "/service0/items",
+        "/service100/items/42",
+        "/service199/items/42/history",
+        "/service250/items",
+    ];
+
+    for radix_tree in [false, true] {
+        let mut router = actix_router::Router::<bool>::build();
+        router.radix_tree(radix_tree);
+        for route in &routes {
+            router.path(route.as_str(), true);
+        }
+        let router = router.finish();
+
+        let name = if radix_tree { "actix-radix" } else { "actix" };
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for route in paths {
+                    let mut path = actix_router::Path::new(route);
+                    black_box(router.recognize(&mut path));
+                }
+            });
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, compare_routers, large_route_table);
 criterion_main!(benches);
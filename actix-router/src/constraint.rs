@@ -0,0 +1,257 @@
+//! Named constraints for dynamic segment values.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use crate::regex_set::Regex;
+
+type Validator = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A named constraint on the values of dynamic segments.
+///
+/// Constraints are used in patterns in place of a custom regex, like `{id<u64>}`. A segment value
+/// conforms to a constraint if it matches the constraint's regex and passes its validator, if any.
+/// Paths containing non-conforming values do not match the resource.
+///
+/// The following constraints are built in:
+///
+/// | Name | Conforming values |
+/// |---|---|
+/// | `u8`, `u16`, `u32`, `u64`, `u128`, `usize` | decimal integers in the range of the type |
+/// | `i8`, `i16`, `i32`, `i64`, `i128`, `isize` | decimal integers in the range of the type |
+/// | `uuid` | hyphenated UUIDs, like `67e55044-10b1-426f-9247-bb680e5fe0c8` |
+/// | `slug` | lowercase alphanumeric words separated by single hyphens, like `my-post-2` |
+/// | `date` | valid calendar dates in `YYYY-MM-DD` format |
+///
+/// Custom constraints can be added using [`ResourceDef::register_constraint`].
+///
+/// # Examples
+/// ```
+/// use actix_router::{Constraint, ResourceDef};
+///
+/// let resource = ResourceDef::new("/user/{id<u8>}");
+/// assert!(resource.is_match("/user/255"));
+/// assert!(!resource.is_match("/user/256"));
+/// assert!(!resource.is_match("/user/james"));
+///
+/// ResourceDef::register_constraint(
+///     "even",
+///     Constraint::new("[0-9]+").validator(|val| val.ends_with(['0', '2', '4', '6', '8'])),
+/// );
+///
+/// let resource = ResourceDef::new("/page/{num<even>}");
+/// assert!(resource.is_match("/page/42"));
+/// assert!(!resource.is_match("/page/7"));
+/// ```
+///
+/// [`ResourceDef::register_constraint`]: crate::ResourceDef::register_constraint
+#[derive(Clone)]
+pub struct Constraint {
+    regex: String,
+    validator: Option<Validator>,
+}
+
+impl Constraint {
+    /// Constructs a constraint whose conforming values match `regex`.
+    ///
+    /// The regex must not match the `/` character unless the segment should be able to capture
+    /// more than one path segment.
+    pub fn new(regex: impl Into<String>) -> Self {
+        Self {
+            regex: regex.into(),
+            validator: None,
+        }
+    }
+
+    /// Adds a validator that values matching the constraint's regex must also pass.
+    pub fn validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Returns the regex that conforming values match.
+    pub fn regex(&self) -> &str {
+        &self.regex
+    }
+}
+
+impl fmt::Debug for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Constraint")
+            .field("regex", &self.regex)
+            .field("validator", &self.validator.is_some())
+            .finish()
+    }
+}
+
+fn registry() -> &'static RwLock<HashMap<String, Constraint>> {
+    static REGISTRY: OnceLock<RwLock<HashMap<String, Constraint>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(builtin()))
+}
+
+/// Adds a named constraint to the global registry.
+pub(crate) fn register(name: String, constraint: Constraint) {
+    registry()
+        .write()
+        .unwrap_or_else(|err| err.into_inner())
+        .insert(name, constraint);
+}
+
+/// Looks up a named constraint in the global registry.
+pub(crate) fn lookup(name: &str) -> Option<Constraint> {
+    registry()
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .get(name)
+        .cloned()
+}
+
+fn builtin() -> HashMap<String, Constraint> {
+    fn parses<T: std::str::FromStr>(val: &str) -> bool {
+        val.parse::<T>().is_ok()
+    }
+
+    const UNSIGNED: &str = "[0-9]+";
+    const SIGNED: &str = "-?[0-9]+";
+
+    let constraints = [
+        ("u8", Constraint::new(UNSIGNED).validator(parses::<u8>)),
+        ("u16", Constraint::new(UNSIGNED).validator(parses::<u16>)),
+        ("u32", Constraint::new(UNSIGNED).validator(parses::<u32>)),
+        ("u64", Constraint::new(UNSIGNED).validator(parses::<u64>)),
+        ("u128", Constraint::new(UNSIGNED).validator(parses::<u128>)),
+        (
+            "usize",
+            Constraint::new(UNSIGNED).validator(parses::<usize>),
+        ),
+        ("i8", Constraint::new(SIGNED).validator(parses::<i8>)),
+        ("i16", Constraint::new(SIGNED).validator(parses::<i16>)),
+        ("i32", Constraint::new(SIGNED).validator(parses::<i32>)),
+        ("i64", Constraint::new(SIGNED).validator(parses::<i64>)),
+        ("i128", Constraint::new(SIGNED).validator(parses::<i128>)),
+        ("isize", Constraint::new(SIGNED).validator(parses::<isize>)),
+        (
+            "uuid",
+            Constraint::new(
+                "[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+            ),
+        ),
+        ("slug", Constraint::new("[a-z0-9]+(?:-[a-z0-9]+)*")),
+        (
+            "date",
+            Constraint::new("[0-9]{4}-[0-9]{2}-[0-9]{2}").validator(is_valid_date),
+        ),
+    ];
+
+    constraints
+        .into_iter()
+        .map(|(name, constraint)| (name.to_owned(), constraint))
+        .collect()
+}
+
+/// Returns true if `val`, in `YYYY-MM-DD` format, is a valid calendar date.
+fn is_valid_date(val: &str) -> bool {
+    let mut parts = val.splitn(3, '-').map(str::parse::<u32>);
+
+    let (year, month, day) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) => (year, month, day),
+        _ => return false,
+    };
+
+    let is_leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days).contains(&day)
+}
+
+/// A constraint applied to a dynamic segment of a parsed pattern.
+#[derive(Clone)]
+pub(crate) struct SegmentConstraint {
+    /// Name of the dynamic segment.
+    pub(crate) name: String,
+
+    /// Regex matching whole conforming values.
+    regex: Regex,
+
+    validator: Option<Validator>,
+}
+
+impl SegmentConstraint {
+    pub(crate) fn new(name: String, constraint: &Constraint) -> Self {
+        let regex = format!("(?s-m)^(?:{})$", constraint.regex);
+
+        Self {
+            name,
+            regex: Regex::new(&regex).unwrap(),
+            validator: constraint.validator.clone(),
+        }
+    }
+
+    /// Returns true if a value captured using the constraint's regex passes its validator.
+    pub(crate) fn validate(&self, val: &str) -> bool {
+        match &self.validator {
+            Some(validator) => validator(val),
+            None => true,
+        }
+    }
+
+    /// Returns true if an arbitrary value conforms to the constraint.
+    pub(crate) fn check(&self, val: &str) -> bool {
+        self.regex.is_match(val) && self.validate(val)
+    }
+}
+
+impl fmt::Debug for SegmentConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentConstraint")
+            .field("name", &self.name)
+            .field("regex", &self.regex.as_str())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_constraints() {
+        let check = |name: &str, val: &str| {
+            let constraint = lookup(name).unwrap();
+            SegmentConstraint::new("val".to_owned(), &constraint).check(val)
+        };
+
+        assert!(check("u8", "255"));
+        assert!(!check("u8", "256"));
+        assert!(!check("u8", "-1"));
+        assert!(check("i64", "-9223372036854775808"));
+        assert!(!check("i64", "9223372036854775808"));
+        assert!(!check("u64", "1.5"));
+
+        assert!(check("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8"));
+        assert!(!check("uuid", "67e55044-10b1-426f-9247"));
+
+        assert!(check("slug", "my-post-2"));
+        assert!(!check("slug", "my--post"));
+        assert!(!check("slug", "My-Post"));
+        assert!(!check("slug", "-post"));
+
+        assert!(check("date", "2024-02-29"));
+        assert!(!check("date", "2023-02-29"));
+        assert!(!check("date", "2023-13-01"));
+        assert!(!check("date", "2023-1-01"));
+    }
+}
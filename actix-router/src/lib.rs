@@ -6,10 +6,12 @@
 #![doc(html_favicon_url = "https://actix.rs/favicon.ico")]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+mod constraint;
 mod de;
 mod path;
 mod pattern;
 mod quoter;
+mod radix_tree;
 mod regex_set;
 mod resource;
 mod resource_path;
@@ -21,6 +23,7 @@ mod url;
 #[cfg(feature = "http")]
 pub use self::url::Url;
 pub use self::{
+    constraint::Constraint,
     de::PathDeserializer,
     path::Path,
     pattern::{IntoPatterns, Patterns},
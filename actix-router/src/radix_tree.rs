@@ -0,0 +1,171 @@
+//! Radix tree index of route candidates keyed by the literal prefix of their patterns.
+
+/// Radix tree mapping literal path prefixes to the indices of routes whose patterns start with
+/// them.
+///
+/// Looking up a path yields the routes whose literal prefix is a prefix of the path, which is a
+/// superset of the routes that can match it. Candidates still need to be matched in order against
+/// their full resource definitions.
+#[derive(Debug, Default)]
+pub(crate) struct RadixTree {
+    root: Node,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    /// Bytes of the key leading to this node from its parent.
+    prefix: Vec<u8>,
+
+    /// Indices of routes whose key ends at this node, in insertion order.
+    routes: Vec<usize>,
+
+    /// Child nodes; their prefixes are non-empty and start with distinct bytes.
+    children: Vec<Node>,
+}
+
+impl RadixTree {
+    /// Adds a route index for the given literal prefix.
+    pub(crate) fn insert(&mut self, key: &str, idx: usize) {
+        self.root.insert(key.as_bytes(), idx);
+    }
+
+    /// Collects indices of routes whose literal prefix is a prefix of `path`, in ascending order.
+    pub(crate) fn candidates(&self, path: &str, out: &mut Vec<usize>) {
+        let mut path = path.as_bytes();
+        let mut node = &self.root;
+
+        loop {
+            out.extend_from_slice(&node.routes);
+
+            let child = path
+                .first()
+                .and_then(|byte| node.children.iter().find(|child| child.prefix[0] == *byte));
+
+            match child {
+                Some(child) if path.starts_with(&child.prefix) => {
+                    path = &path[child.prefix.len()..];
+                    node = child;
+                }
+                _ => break,
+            }
+        }
+
+        out.sort_unstable();
+    }
+}
+
+impl Node {
+    fn insert(&mut self, key: &[u8], idx: usize) {
+        if key.is_empty() {
+            self.routes.push(idx);
+            return;
+        }
+
+        let child = match self
+            .children
+            .iter_mut()
+            .position(|child| child.prefix[0] == key[0])
+        {
+            Some(pos) => &mut self.children[pos],
+            None => {
+                self.children.push(Node {
+                    prefix: key.to_vec(),
+                    routes: vec![idx],
+                    children: Vec::new(),
+                });
+                return;
+            }
+        };
+
+        let common = child
+            .prefix
+            .iter()
+            .zip(key)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        if common < child.prefix.len() {
+            // split child so that its prefix is the common part of both keys
+            let suffix = child.prefix.split_off(common);
+
+            let split = Node {
+                prefix: suffix,
+                routes: std::mem::take(&mut child.routes),
+                children: std::mem::take(&mut child.children),
+            };
+
+            child.children.push(split);
+        }
+
+        child.insert(&key[common..], idx);
+    }
+}
+
+/// Returns the part of a path pattern that every matching path starts with.
+pub(crate) fn literal_prefix(pattern: &str) -> &str {
+    let end = pattern.find(['{', '*']).unwrap_or(pattern.len());
+    &pattern[..end]
+}
+
+/// Returns the longest common prefix of two strings, respecting char boundaries.
+pub(crate) fn common_prefix<'a>(a: &'a str, b: &str) -> &'a str {
+    let len = a
+        .char_indices()
+        .zip(b.chars())
+        .find(|((_, a), b)| a != b)
+        .map_or_else(|| a.len().min(b.len()), |((idx, _), _)| idx);
+
+    &a[..len]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(tree: &RadixTree, path: &str) -> Vec<usize> {
+        let mut out = Vec::new();
+        tree.candidates(path, &mut out);
+        out
+    }
+
+    #[test]
+    fn lookup() {
+        let mut tree = RadixTree::default();
+        tree.insert("/users/", 0);
+        tree.insert("/user", 1);
+        tree.insert("", 2);
+        tree.insert("/users/", 3);
+        tree.insert("/u", 4);
+        tree.insert("/posts", 5);
+
+        assert_eq!(candidates(&tree, "/users/1"), [0, 1, 2, 3, 4]);
+        assert_eq!(candidates(&tree, "/user"), [1, 2, 4]);
+        assert_eq!(candidates(&tree, "/us"), [2, 4]);
+        assert_eq!(candidates(&tree, "/posts/1"), [2, 5]);
+        assert_eq!(candidates(&tree, "/p"), [2]);
+        assert_eq!(candidates(&tree, ""), [2]);
+    }
+
+    #[test]
+    fn multi_byte_keys() {
+        let mut tree = RadixTree::default();
+        tree.insert("/é", 0);
+        tree.insert("/è", 1);
+
+        assert_eq!(candidates(&tree, "/é/x"), [0]);
+        assert_eq!(candidates(&tree, "/è"), [1]);
+        assert!(candidates(&tree, "/e").is_empty());
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(literal_prefix("/user/{id}/posts"), "/user/");
+        assert_eq!(literal_prefix("/static"), "/static");
+        assert_eq!(literal_prefix("/files*"), "/files");
+        assert_eq!(literal_prefix("{tail}*"), "");
+
+        assert_eq!(common_prefix("/users/", "/user/"), "/user");
+        assert_eq!(common_prefix("/é", "/è"), "/");
+        assert_eq!(common_prefix("/a", "/a/b"), "/a");
+    }
+}
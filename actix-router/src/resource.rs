@@ -2,15 +2,15 @@ use std::{
     borrow::{Borrow, Cow},
     collections::HashMap,
     hash::{BuildHasher, Hash, Hasher},
-    mem,
 };
 
 use tracing::error;
 
 use crate::{
+    constraint::{self, Constraint, SegmentConstraint},
     path::PathItem,
-    regex_set::{escape, Regex, RegexSet},
-    IntoPatterns, Patterns, Resource, ResourcePath,
+    regex_set::{escape, Captures, Regex, RegexSet},
+    IntoPatterns, Patterns, Resource,
 };
 
 const MAX_DYNAMIC_SEGMENTS: usize = 16;
@@ -155,6 +155,64 @@ const REGEX_FLAGS: &str = "(?s-m)";
 /// assert!(!resource.is_match("/user/abc"));
 /// ```
 ///
+/// # Segment Constraints
+/// Instead of a regex, the name of a [`Constraint`] can be given in angle brackets after the
+/// segment name. For example, `/user/{id<u64>}` only matches paths where the user ID is an integer
+/// that fits in a `u64`, so that other IDs are not matched rather than failing to be extracted
+/// later on. Segments can not have both a constraint and a custom regex, and using a constraint
+/// that is not registered panics.
+///
+/// Built-in constraints are described in the [`Constraint`] docs. Custom constraints can be added
+/// using [`register_constraint`](Self::register_constraint).
+///
+/// When generating resource paths, values of constrained segments must conform to the constraint.
+///
+/// ## Examples
+/// ```
+/// # use actix_router::ResourceDef;
+/// let resource = ResourceDef::new("/posts/{date<date>}/{slug<slug>}");
+/// assert!(resource.is_match("/posts/2024-02-29/hello-world"));
+/// assert!(!resource.is_match("/posts/2023-02-29/hello-world"));
+/// assert!(!resource.is_match("/posts/2024-02-29/Hello_World"));
+///
+/// let mut s = String::new();
+/// assert!(!resource.resource_path_from_iter(&mut s, &["yesterday", "hello-world"]));
+/// ```
+///
+/// # Optional Segments and Defaults
+/// A dynamic segment can be made optional by adding `?` to its name, like `{lang?}`, or by giving
+/// it a default value, like `{page=index}`. Paths that omit the segment still match the resource;
+/// the default, if any, is then used as the segment's value, otherwise it is left out of the
+/// captured values entirely.
+///
+/// Adding a leading slash to the name of an optional segment, like `{/lang?}`, makes the slash
+/// before the segment part of it, so that it is omitted along with the value.
+/// When combined with a custom regex or constraint, these markers go before them, like
+/// `{/tab?:[a-z]+}` or `{/tab?<slug>}`.
+///
+/// When generating resource paths from a map, optional segments that have no value in the map are
+/// omitted. Optional segments cannot be used in tail segments.
+///
+/// ## Examples
+/// ```
+/// # use std::collections::HashMap;
+/// # use actix_router::{Path, ResourceDef};
+/// let resource = ResourceDef::new("/files{/lang?}/{page=index}");
+/// assert!(resource.is_match("/files/"));
+/// assert!(resource.is_match("/files/en/"));
+/// assert!(resource.is_match("/files/en/about"));
+///
+/// let mut path = Path::new("/files/");
+/// assert!(resource.capture_match_info(&mut path));
+/// assert!(path.get("lang").is_none());
+/// assert_eq!(path.get("page").unwrap(), "index");
+///
+/// let mut s = String::new();
+/// let map = HashMap::from([("page", "about")]);
+/// assert!(resource.resource_path_from_map(&mut s, &map));
+/// assert_eq!(s, "/files/about");
+/// ```
+///
 /// # Tail Segments
 /// As a shortcut to defining a custom regex for matching _all_ remaining characters (not just those
 /// up until a `/` character), there is a special pattern to match (and capture) the remaining
@@ -234,6 +292,18 @@ enum PatternSegment {
 
     /// Name of dynamic segment.
     Var(String),
+
+    /// Dynamic segment that can be omitted.
+    Optional {
+        /// Name of dynamic segment.
+        name: String,
+
+        /// Value used when the segment is omitted.
+        default: Option<String>,
+
+        /// Whether the segment is preceded by a slash that is omitted along with it.
+        slash: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -242,11 +312,74 @@ enum PatternType {
     /// Single constant/literal segment.
     Static(String),
 
-    /// Single regular expression and list of dynamic segment names.
-    Dynamic(Regex, Vec<&'static str>),
+    /// Single regular expression.
+    Dynamic(DynamicPattern),
+
+    /// Regular expression set and list of component expressions.
+    DynamicSet(RegexSet, Vec<DynamicPattern>),
+}
+
+#[derive(Debug, Clone)]
+struct DynamicPattern {
+    /// Regular expression, with the matched part of the path in capture group #1.
+    re: Regex,
+
+    /// Names of dynamic segments.
+    names: Vec<&'static str>,
+
+    /// Constraints of dynamic segments using named constraints.
+    constraints: Vec<SegmentConstraint>,
+
+    /// Names of optional dynamic segments along with their default values.
+    optional: Vec<(String, Option<String>)>,
+}
+
+impl DynamicPattern {
+    /// Returns captures if `path` matches the pattern and passes its segment constraints.
+    fn captures<'p>(&self, path: &'p str) -> Option<Captures<'p>> {
+        let captures = self.re.captures(path)?;
+
+        let conforms =
+            self.constraints
+                .iter()
+                .all(|constraint| match captures.name(&constraint.name) {
+                    Some(m) => constraint.validate(m.as_str()),
+                    None => true,
+                });
+
+        conforms.then_some(captures)
+    }
+
+    /// Collects captured segment values, or defaults of omitted optional segments, into
+    /// `segments`.
+    ///
+    /// Returns `false` if a required segment was not captured.
+    fn collect_segments(
+        &self,
+        captures: &Captures<'_>,
+        segments: &mut [Option<PathItem>; MAX_DYNAMIC_SEGMENTS],
+    ) -> bool {
+        for (no, name) in self.names.iter().enumerate() {
+            if let Some(m) = captures.name(name) {
+                segments[no] = Some(PathItem::Segment(m.start() as u16, m.end() as u16));
+                continue;
+            }
+
+            match self.optional.iter().find(|(opt_name, _)| opt_name == name) {
+                Some((_, default)) => {
+                    segments[no] = default
+                        .as_ref()
+                        .map(|default| PathItem::Static(Cow::Owned(default.clone())));
+                }
+                None => {
+                    error!("Dynamic path match but not all segments found: {}", name);
+                    return false;
+                }
+            }
+        }
 
-    /// Regular expression set and list of component expressions plus dynamic segment names.
-    DynamicSet(RegexSet, Vec<(Regex, Vec<&'static str>)>),
+        true
+    }
 }
 
 impl ResourceDef {
@@ -328,6 +461,26 @@ impl ResourceDef {
         ResourceDef::prefix(insert_slash(path).into_owned())
     }
 
+    /// Registers a named segment constraint for use in patterns, like `{id<name>}`.
+    ///
+    /// Constraints are global and only apply to resource definitions constructed after they are
+    /// registered. Registering a constraint with the name of an existing one, including built-in
+    /// ones, replaces it.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_router::{Constraint, ResourceDef};
+    ///
+    /// ResourceDef::register_constraint("lang", Constraint::new("en|fr|de"));
+    ///
+    /// let resource = ResourceDef::new("/{lang<lang>}/about");
+    /// assert!(resource.is_match("/fr/about"));
+    /// assert!(!resource.is_match("/es/about"));
+    /// ```
+    pub fn register_constraint(name: impl Into<String>, constraint: Constraint) {
+        constraint::register(name.into(), constraint);
+    }
+
     /// Returns a numeric resource ID.
     ///
     /// If not explicitly set using [`set_id`][Self::set_id], this will return `0`.
@@ -559,8 +712,15 @@ impl ResourceDef {
 
         match &self.pat_type {
             PatternType::Static(pattern) => self.static_match(pattern, path).is_some(),
-            PatternType::Dynamic(re, _) => re.is_match(path),
-            PatternType::DynamicSet(re, _) => re.is_match(path),
+            PatternType::Dynamic(pattern) if pattern.constraints.is_empty() => {
+                pattern.re.is_match(path)
+            }
+            PatternType::DynamicSet(re, params)
+                if params.iter().all(|pattern| pattern.constraints.is_empty()) =>
+            {
+                re.is_match(path)
+            }
+            _ => self.find_match(path).is_some(),
         }
     }
 
@@ -603,12 +763,11 @@ impl ResourceDef {
         match &self.pat_type {
             PatternType::Static(pattern) => self.static_match(pattern, path),
 
-            PatternType::Dynamic(re, _) => Some(re.captures(path)?[1].len()),
+            PatternType::Dynamic(pattern) => Some(pattern.captures(path)?[1].len()),
 
             PatternType::DynamicSet(re, params) => {
-                let idx = re.first_match_idx(path)?;
-                let (ref pattern, _) = params[idx];
-                Some(pattern.captures(path)?[1].len())
+                let (captures, _) = set_captures(re, params, path)?;
+                Some(captures[1].len())
             }
         }
     }
@@ -679,7 +838,7 @@ impl ResourceDef {
         R: Resource,
         F: FnOnce(&R) -> bool,
     {
-        let mut segments = <[PathItem; MAX_DYNAMIC_SEGMENTS]>::default();
+        let mut segments = <[Option<PathItem>; MAX_DYNAMIC_SEGMENTS]>::default();
         let path = resource.resource_path();
         let path_str = path.unprocessed();
 
@@ -689,46 +848,30 @@ impl ResourceDef {
                 None => return false,
             },
 
-            PatternType::Dynamic(re, names) => {
-                let captures = match re.captures(path.unprocessed()) {
+            PatternType::Dynamic(pattern) => {
+                let captures = match pattern.captures(path.unprocessed()) {
                     Some(captures) => captures,
                     _ => return false,
                 };
 
-                for (no, name) in names.iter().enumerate() {
-                    if let Some(m) = captures.name(name) {
-                        segments[no] = PathItem::Segment(m.start() as u16, m.end() as u16);
-                    } else {
-                        error!("Dynamic path match but not all segments found: {}", name);
-                        return false;
-                    }
+                if !pattern.collect_segments(&captures, &mut segments) {
+                    return false;
                 }
 
-                (captures[1].len(), Some(names))
+                (captures[1].len(), Some(&pattern.names))
             }
 
             PatternType::DynamicSet(re, params) => {
-                let path = path.unprocessed();
-                let (pattern, names) = match re.first_match_idx(path) {
-                    Some(idx) => &params[idx],
+                let (captures, pattern) = match set_captures(re, params, path.unprocessed()) {
+                    Some(res) => res,
                     _ => return false,
                 };
 
-                let captures = match pattern.captures(path.path()) {
-                    Some(captures) => captures,
-                    _ => return false,
-                };
-
-                for (no, name) in names.iter().enumerate() {
-                    if let Some(m) = captures.name(name) {
-                        segments[no] = PathItem::Segment(m.start() as u16, m.end() as u16);
-                    } else {
-                        error!("Dynamic path match but not all segments found: {}", name);
-                        return false;
-                    }
+                if !pattern.collect_segments(&captures, &mut segments) {
+                    return false;
                 }
 
-                (captures[1].len(), Some(names))
+                (captures[1].len(), Some(&pattern.names))
             }
         };
 
@@ -741,7 +884,9 @@ impl ResourceDef {
 
         if let Some(vars) = matched_vars {
             for i in 0..vars.len() {
-                path.add(vars[i], mem::take(&mut segments[i]));
+                if let Some(segment) = segments[i].take() {
+                    path.add(vars[i], segment);
+                }
             }
         }
 
@@ -756,13 +901,39 @@ impl ResourceDef {
         F: FnMut(&str) -> Option<I>,
         I: AsRef<str>,
     {
+        let constraints = match &self.pat_type {
+            PatternType::Static(_) => &[][..],
+            PatternType::Dynamic(pattern) => &pattern.constraints,
+            PatternType::DynamicSet(_, params) => params
+                .first()
+                .map_or(&[][..], |pattern| &pattern.constraints),
+        };
+
+        let conforms = |name: &str, val: &str| {
+            constraints
+                .iter()
+                .filter(|constraint| constraint.name == name)
+                .all(|constraint| constraint.check(val))
+        };
+
         for segment in &self.segments {
             match segment {
                 PatternSegment::Const(val) => path.push_str(val),
                 PatternSegment::Var(name) => match vars(name) {
-                    Some(val) => path.push_str(val.as_ref()),
+                    Some(val) if conforms(name, val.as_ref()) => path.push_str(val.as_ref()),
                     _ => return false,
                 },
+                PatternSegment::Optional { name, slash, .. } => match vars(name) {
+                    Some(val) if conforms(name, val.as_ref()) => {
+                        if *slash {
+                            path.push('/');
+                        }
+
+                        path.push_str(val.as_ref());
+                    }
+                    Some(_) => return false,
+                    None => {}
+                },
             }
         }
 
@@ -864,9 +1035,9 @@ impl ResourceDef {
 
                 for pattern in patterns {
                     match ResourceDef::parse(pattern, is_prefix, true) {
-                        (PatternType::Dynamic(re, names), segs) => {
-                            re_set.push(re.as_str().to_owned());
-                            pattern_data.push((re, names));
+                        (PatternType::Dynamic(pattern), segs) => {
+                            re_set.push(pattern.re.as_str().to_owned());
+                            pattern_data.push(pattern);
                             segments.get_or_insert(segs);
                         }
                         _ => unreachable!(),
@@ -896,14 +1067,23 @@ impl ResourceDef {
     /// Parses a dynamic segment definition from a pattern.
     ///
     /// The returned tuple includes:
-    /// - the segment descriptor, either `Var` or `Tail`
+    /// - the segment descriptor, either `Var` or `Optional`
     /// - the segment's regex to check values against
     /// - the remaining, unprocessed string slice
     /// - whether the parsed parameter represents a tail pattern
+    /// - the segment's named constraint, if any
     ///
     /// # Panics
     /// Panics if given patterns does not contain a dynamic segment.
-    fn parse_param(pattern: &str) -> (PatternSegment, String, &str, bool) {
+    fn parse_param(
+        pattern: &str,
+    ) -> (
+        PatternSegment,
+        String,
+        &str,
+        bool,
+        Option<SegmentConstraint>,
+    ) {
         const DEFAULT_PATTERN: &str = "[^/]+";
         const DEFAULT_PATTERN_TAIL: &str = ".*";
 
@@ -952,10 +1132,83 @@ impl ResourceDef {
             ),
         };
 
-        let segment = PatternSegment::Var(name.to_string());
-        let regex = format!(r"(?P<{}>{})", &name, &pattern);
+        // named constraints: `{name<constraint>}`, used in place of a custom regex
+        let (name, constraint) = match name
+            .strip_suffix('>')
+            .and_then(|name| name.rsplit_once('<'))
+        {
+            Some((name, constraint_name)) => {
+                assert!(
+                    !tail,
+                    "segment constraints are not supported for tail match"
+                );
+                assert!(
+                    pattern == DEFAULT_PATTERN,
+                    "segment {} can not have both a constraint and a custom regex",
+                    name
+                );
+
+                let constraint = constraint::lookup(constraint_name).unwrap_or_else(|| {
+                    panic!(
+                        r#"segment constraint "{}" is not registered"#,
+                        constraint_name
+                    )
+                });
+
+                (name, Some(constraint))
+            }
+            None => (name, None),
+        };
+
+        let pattern = constraint.as_ref().map_or(pattern, Constraint::regex);
+
+        // optional segments: `{name?}`, `{name=default}`, optionally with a leading slash
+        let (slash, name) = match name.strip_prefix('/') {
+            Some(name) => (true, name),
+            None => (false, name),
+        };
+
+        let (name, optional) = if let Some(name) = name.strip_suffix('?') {
+            (name, Some(None))
+        } else if let Some((name, default)) = name.split_once('=') {
+            (name, Some(Some(default.to_owned())))
+        } else {
+            assert!(
+                !slash,
+                "only optional segments can include a leading slash; use {{/{}?}}",
+                name
+            );
+            (name, None)
+        };
+
+        assert!(
+            !(tail && optional.is_some()),
+            "optional segments are not supported for tail match"
+        );
+
+        let (segment, regex) = match optional {
+            Some(default) => (
+                PatternSegment::Optional {
+                    name: name.to_owned(),
+                    default,
+                    slash,
+                },
+                format!(
+                    r"(?:{}(?P<{}>{}))?",
+                    if slash { "/" } else { "" },
+                    &name,
+                    &pattern
+                ),
+            ),
+            None => (
+                PatternSegment::Var(name.to_owned()),
+                format!(r"(?P<{}>{})", &name, &pattern),
+            ),
+        };
+
+        let constraint = constraint.map(|c| SegmentConstraint::new(name.to_owned(), &c));
 
-        (segment, regex, unprocessed, tail)
+        (segment, regex, unprocessed, tail, constraint)
     }
 
     /// Parse `pattern` using `is_prefix` and `force_dynamic` flags.
@@ -986,6 +1239,7 @@ impl ResourceDef {
         let mut re = format!("{}^", REGEX_FLAGS);
         let mut dyn_segment_count = 0;
         let mut has_tail_segment = false;
+        let mut constraints = Vec::new();
 
         while let Some(idx) = unprocessed.find('{') {
             let (prefix, rem) = unprocessed.split_at(idx);
@@ -993,12 +1247,14 @@ impl ResourceDef {
             segments.push(PatternSegment::Const(prefix.to_owned()));
             re.push_str(&escape(prefix));
 
-            let (param_pattern, re_part, rem, tail) = Self::parse_param(rem);
+            let (param_pattern, re_part, rem, tail, constraint) = Self::parse_param(rem);
 
             if tail {
                 has_tail_segment = true;
             }
 
+            constraints.extend(constraint);
+
             segments.push(param_pattern);
             re.push_str(&re_part);
 
@@ -1074,10 +1330,42 @@ impl ResourceDef {
             .filter_map(|name| name.map(|name| Box::leak(Box::new(name.to_owned())).as_str()))
             .collect();
 
-        (PatternType::Dynamic(re, names), segments)
+        let optional = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                PatternSegment::Optional { name, default, .. } => {
+                    Some((name.clone(), default.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        (
+            PatternType::Dynamic(DynamicPattern {
+                re,
+                names,
+                constraints,
+                optional,
+            }),
+            segments,
+        )
     }
 }
 
+/// Returns captures from the first pattern in a set that matches `path` and passes its segment
+/// constraints, along with the pattern.
+fn set_captures<'a, 'p>(
+    re: &RegexSet,
+    params: &'a [DynamicPattern],
+    path: &'p str,
+) -> Option<(Captures<'p>, &'a DynamicPattern)> {
+    let idx = re.first_match_idx(path)?;
+
+    params[idx..]
+        .iter()
+        .find_map(|pattern| Some((pattern.captures(path)?, pattern)))
+}
+
 impl Eq for ResourceDef {}
 
 impl PartialEq for ResourceDef {
@@ -1318,6 +1606,172 @@ mod tests {
         assert_eq!(path.unprocessed(), "/res");
     }
 
+    #[test]
+    fn segment_constraints() {
+        let re = ResourceDef::new("/user/{id<u8>}/{name}");
+        assert!(re.is_match("/user/255/james"));
+        assert!(!re.is_match("/user/256/james"));
+        assert!(!re.is_match("/user/abc/james"));
+        assert_eq!(re.find_match("/user/256/james"), None);
+
+        let mut path = Path::new("/user/42/james");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("id").unwrap(), "42");
+
+        let mut path = Path::new("/user/420/james");
+        assert!(!re.capture_match_info(&mut path));
+        assert_eq!(path.unprocessed(), "/user/420/james");
+
+        // later patterns are tried when values fail an earlier pattern's constraints
+        let re = ResourceDef::new(["/item/{id<u8>}", "/item/{id<i64>}"]);
+        assert!(re.is_match("/item/-1000"));
+        let mut path = Path::new("/item/1000");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("id").unwrap(), "1000");
+        assert!(!re.is_match("/item/abc"));
+
+        let re = ResourceDef::prefix("/post/{id<uuid>}");
+        let mut path = Path::new("/post/67e55044-10b1-426f-9247-bb680e5fe0c8/comments");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.unprocessed(), "/comments");
+        assert!(!re.is_match("/post/67e55044/comments"));
+
+        // constraint names after a colon are still treated as regexes
+        let re = ResourceDef::new("/v{version:[12]}");
+        assert!(re.is_match("/v2"));
+        assert!(!re.is_match("/v3"));
+        let re = ResourceDef::new("/{d:date}");
+        assert!(re.is_match("/date"));
+        assert!(!re.is_match("/2024-02-29"));
+
+        ResourceDef::register_constraint(
+            "resource_test_lang",
+            Constraint::new("[a-z]{2}").validator(|val| val != "xx"),
+        );
+        let re = ResourceDef::new("/{lang<resource_test_lang>}/about");
+        assert!(re.is_match("/en/about"));
+        assert!(!re.is_match("/xx/about"));
+        assert!(!re.is_match("/eng/about"));
+    }
+
+    #[test]
+    fn constrained_resource_path() {
+        let re = ResourceDef::new("/user/{id<u64>}/{name}");
+
+        let mut s = String::new();
+        assert!(re.resource_path_from_iter(&mut s, &["42", "james"]));
+        assert_eq!(s, "/user/42/james");
+
+        let mut s = String::new();
+        assert!(!re.resource_path_from_iter(&mut s, &["james", "42"]));
+
+        let mut map = HashMap::new();
+        map.insert("id", "18446744073709551616");
+        map.insert("name", "james");
+        let mut s = String::new();
+        assert!(!re.resource_path_from_map(&mut s, &map));
+    }
+
+    #[test]
+    fn optional_segments() {
+        let re = ResourceDef::new("/files{/lang?}/{page=index}");
+        assert!(re.is_match("/files/"));
+        assert!(re.is_match("/files/en/"));
+        assert!(re.is_match("/files/en/about"));
+        assert!(!re.is_match("/files"));
+        assert!(!re.is_match("/files/en/about/more"));
+
+        let mut path = Path::new("/files/");
+        assert!(re.capture_match_info(&mut path));
+        assert!(path.get("lang").is_none());
+        assert_eq!(path.get("page").unwrap(), "index");
+        assert_eq!(path.iter().count(), 1);
+
+        let mut path = Path::new("/files/en/about");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("lang").unwrap(), "en");
+        assert_eq!(path.get("page").unwrap(), "about");
+
+        let re = ResourceDef::new("/user/{id<u64>}/{tab=overview}");
+        assert!(re.is_match("/user/42/"));
+        assert!(!re.is_match("/user/profile/"));
+
+        let mut path = Path::new("/user/42/");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("id").unwrap(), "42");
+        assert_eq!(path.get("tab").unwrap(), "overview");
+
+        let re = ResourceDef::prefix("/{lang=en}");
+        let mut path = Path::new("/de/docs");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("lang").unwrap(), "de");
+        assert_eq!(path.unprocessed(), "/docs");
+
+        let mut path = Path::new("/");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("lang").unwrap(), "en");
+
+        let re = ResourceDef::new(["/posts{/year?}", "/archive{/year?}"]);
+        let mut path = Path::new("/archive");
+        assert!(re.capture_match_info(&mut path));
+        assert!(path.get("year").is_none());
+        let mut path = Path::new("/archive/2024");
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("year").unwrap(), "2024");
+    }
+
+    #[test]
+    fn optional_segments_resource_path() {
+        let re = ResourceDef::new("/files{/lang?}/{page=index}");
+
+        let mut s = String::new();
+        assert!(re.resource_path_from_map(&mut s, &HashMap::<&str, &str>::new()));
+        assert_eq!(s, "/files/");
+
+        let mut s = String::new();
+        let map = HashMap::from([("lang", "en")]);
+        assert!(re.resource_path_from_map(&mut s, &map));
+        assert_eq!(s, "/files/en/");
+
+        let mut s = String::new();
+        let map = HashMap::from([("lang", "en"), ("page", "about")]);
+        assert!(re.resource_path_from_map(&mut s, &map));
+        assert_eq!(s, "/files/en/about");
+
+        let mut s = String::new();
+        assert!(re.resource_path_from_iter(&mut s, &["en", "about"]));
+        assert_eq!(s, "/files/en/about");
+
+        let re = ResourceDef::new("/user/{id<u64>}{/tab?<slug>}");
+        let mut s = String::new();
+        let map = HashMap::from([("id", "42"), ("tab", "Not A Slug")]);
+        assert!(!re.resource_path_from_map(&mut s, &map));
+    }
+
+    #[test]
+    #[should_panic]
+    fn unknown_segment_constraint() {
+        ResourceDef::new("/user/{id<not_registered>}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn segment_constraint_with_regex() {
+        ResourceDef::new("/user/{id<u64>:[0-9]+}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn optional_segment_slash_without_optional() {
+        ResourceDef::new("/files{/lang}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn optional_tail_segment() {
+        ResourceDef::new("/files/{tail?}*");
+    }
+
     #[test]
     fn parse_tail() {
         let re = ResourceDef::new("/user/-{id}*");
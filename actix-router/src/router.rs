@@ -1,4 +1,7 @@
-use crate::{IntoPatterns, Resource, ResourceDef};
+use crate::{
+    radix_tree::{self, RadixTree},
+    IntoPatterns, Resource, ResourceDef,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct ResourceId(pub u16);
@@ -11,14 +14,22 @@ pub struct ResourceId(pub u16);
 /// 1. Some _context_ data, of the generic type `U`, which is only provided to the check function in
 ///    [`recognize_fn`](Self::recognize_fn). This parameter defaults to `()` and can be omitted if
 ///    not required.
+///
+/// By default, routes are tested one by one. For large route lists, a radix tree index of the
+/// routes' literal prefixes can be enabled using [`RouterBuilder::radix_tree`]; it only narrows
+/// down which routes are tested and does not change which route is matched.
 pub struct Router<T, U = ()> {
     routes: Vec<(ResourceDef, T, U)>,
+    tree: Option<RadixTree>,
 }
 
 impl<T, U> Router<T, U> {
     /// Constructs new `RouterBuilder` with empty route list.
     pub fn build() -> RouterBuilder<T, U> {
-        RouterBuilder { routes: Vec::new() }
+        RouterBuilder {
+            routes: Vec::new(),
+            radix_tree: false,
+        }
     }
 
     /// Finds the value in the router that matches a given [routing resource](Resource).
@@ -46,18 +57,14 @@ impl<T, U> Router<T, U> {
     /// the `check` closure is executed, passing the resource and each route's context data. If the
     /// closure returns true then the match result is stored into `resource` and a reference to
     /// the matched _value_ is returned.
-    pub fn recognize_fn<R, F>(&self, resource: &mut R, mut check: F) -> Option<(&T, ResourceId)>
+    pub fn recognize_fn<R, F>(&self, resource: &mut R, check: F) -> Option<(&T, ResourceId)>
     where
         R: Resource,
         F: FnMut(&R, &U) -> bool,
     {
-        for (rdef, val, ctx) in self.routes.iter() {
-            if rdef.capture_match_info_fn(resource, |res| check(res, ctx)) {
-                return Some((val, ResourceId(rdef.id())));
-            }
-        }
-
-        None
+        let idx = self.find(resource, check)?;
+        let (rdef, val, _) = &self.routes[idx];
+        Some((val, ResourceId(rdef.id())))
     }
 
     /// Same as [`recognize_fn`](Self::recognize_fn) but returns a mutable reference to the matched
@@ -65,28 +72,61 @@ impl<T, U> Router<T, U> {
     pub fn recognize_mut_fn<R, F>(
         &mut self,
         resource: &mut R,
-        mut check: F,
+        check: F,
     ) -> Option<(&mut T, ResourceId)>
     where
         R: Resource,
         F: FnMut(&R, &U) -> bool,
     {
-        for (rdef, val, ctx) in self.routes.iter_mut() {
-            if rdef.capture_match_info_fn(resource, |res| check(res, ctx)) {
-                return Some((val, ResourceId(rdef.id())));
-            }
-        }
+        let idx = self.find(resource, check)?;
+        let (rdef, val, _) = &mut self.routes[idx];
+        Some((val, ResourceId(rdef.id())))
+    }
 
-        None
+    /// Returns the index of the first route that matches `resource` and passes `check`.
+    fn find<R, F>(&self, resource: &mut R, mut check: F) -> Option<usize>
+    where
+        R: Resource,
+        F: FnMut(&R, &U) -> bool,
+    {
+        let candidates = self.tree.as_ref().map(|tree| {
+            let mut candidates = Vec::new();
+            tree.candidates(resource.resource_path().unprocessed(), &mut candidates);
+            candidates
+        });
+
+        let mut try_match = |idx: usize| {
+            let (rdef, _, ctx) = &self.routes[idx];
+            rdef.capture_match_info_fn(resource, |res| check(res, ctx))
+        };
+
+        match candidates {
+            Some(candidates) => candidates.into_iter().find(|&idx| try_match(idx)),
+            None => (0..self.routes.len()).find(|&idx| try_match(idx)),
+        }
     }
 }
 
 /// Builder for an ordered [routing](Router) list.
 pub struct RouterBuilder<T, U = ()> {
     routes: Vec<(ResourceDef, T, U)>,
+    radix_tree: bool,
 }
 
 impl<T, U> RouterBuilder<T, U> {
+    /// Sets whether the router indexes routes using a radix tree of their literal prefixes.
+    ///
+    /// Lookups then only test the routes whose patterns' literal prefix (the part before the first
+    /// dynamic segment) is a prefix of the path, which is faster for large route lists. Routes are
+    /// still tested in the order they were added, so the matched route is the same as without the
+    /// index.
+    ///
+    /// Disabled by default.
+    pub fn radix_tree(&mut self, enabled: bool) -> &mut Self {
+        self.radix_tree = enabled;
+        self
+    }
+
     /// Adds a new route to the end of the routing list.
     ///
     /// Returns mutable references to elements of the new route.
@@ -106,8 +146,25 @@ impl<T, U> RouterBuilder<T, U> {
 
     /// Finish configuration and create router instance.
     pub fn finish(self) -> Router<T, U> {
+        let tree = self.radix_tree.then(|| {
+            let mut tree = RadixTree::default();
+
+            for (idx, (rdef, _, _)) in self.routes.iter().enumerate() {
+                let prefix = rdef
+                    .pattern_iter()
+                    .map(radix_tree::literal_prefix)
+                    .reduce(radix_tree::common_prefix)
+                    .unwrap_or("");
+
+                tree.insert(prefix, idx);
+            }
+
+            tree
+        });
+
         Router {
             routes: self.routes,
+            tree,
         }
     }
 }
@@ -142,6 +199,7 @@ mod tests {
     use crate::{
         path::Path,
         router::{ResourceId, Router},
+        ResourceDef,
     };
 
     #[allow(clippy::cognitive_complexity)]
@@ -279,4 +337,82 @@ mod tests {
         assert_eq!(*h, 11);
         assert_eq!(&path["val"], "ttt");
     }
+
+    #[test]
+    fn test_radix_tree() {
+        let patterns = [
+            ResourceDef::new("/name"),
+            ResourceDef::new("/name/{val}"),
+            ResourceDef::new("/{test}/index.html"),
+            ResourceDef::prefix("/name/"),
+            ResourceDef::prefix("/files"),
+            ResourceDef::new("/v{val}/{val2}/index.html"),
+            ResourceDef::new("/v/{tail}*"),
+            ResourceDef::new(["/user/{id}", "/users/{id}"]),
+            ResourceDef::new("/users/me"),
+            ResourceDef::new("/é/{val}"),
+            ResourceDef::root_prefix(""),
+        ];
+
+        let paths = [
+            "/name",
+            "/name/",
+            "/name/value",
+            "/name/index.html",
+            "/names",
+            "/files",
+            "/files/a/b",
+            "/filesystem",
+            "/v2/ttt/index.html",
+            "/v/a/b",
+            "/user/1",
+            "/users/1",
+            "/users/me",
+            "/é/a",
+            "/è/a",
+            "/unknown",
+            "",
+        ];
+
+        let mut linear = Router::<usize, usize>::build();
+        let mut radix = Router::<usize, usize>::build();
+        radix.radix_tree(true);
+
+        for (idx, rdef) in patterns.iter().enumerate() {
+            linear.push(rdef.clone(), idx, idx);
+            radix.push(rdef.clone(), idx, idx);
+        }
+
+        let linear = linear.finish();
+        let radix = radix.finish();
+
+        for path in paths {
+            let mut linear_path = Path::new(path);
+            let mut radix_path = Path::new(path);
+
+            assert_eq!(
+                linear.recognize(&mut linear_path).map(|(val, _)| *val),
+                radix.recognize(&mut radix_path).map(|(val, _)| *val),
+                "different match for {:?}",
+                path
+            );
+            assert_eq!(linear_path.unprocessed(), radix_path.unprocessed());
+        }
+
+        // candidates failing the check function are skipped in order
+        let mut path = Path::new("/name/value");
+        let (val, _) = radix.recognize_fn(&mut path, |_, &val| val != 1).unwrap();
+        assert_eq!(*val, 10);
+
+        let mut path = Path::new("/name/");
+        let (val, _) = radix.recognize_fn(&mut path, |_, &val| val != 3).unwrap();
+        assert_eq!(*val, 10);
+
+        // paths are matched from their unprocessed part
+        let mut path = Path::new("/test/name/ttt");
+        path.skip(5);
+        let (val, _) = radix.recognize(&mut path).unwrap();
+        assert_eq!(*val, 1);
+        assert_eq!(&path["val"], "ttt");
+    }
 }
@@ -155,6 +155,7 @@ where
             let factory = factory.clone();
             let srv_cfg = cfg.clone();
             let timeout = cfg.client_request_timeout;
+            let defer_continue = cfg.defer_continue;
 
             let builder = Server::build()
                 .workers(cfg.workers)
@@ -173,6 +174,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .tcp()
                     }),
@@ -186,6 +188,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .tcp()
                     }),
@@ -199,6 +202,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .tcp()
                     }),
@@ -215,6 +219,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .openssl(acceptor.clone())
                     }),
@@ -228,6 +233,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .openssl(acceptor.clone())
                     }),
@@ -241,6 +247,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .openssl(acceptor.clone())
                     }),
@@ -257,6 +264,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .rustls(config.clone())
                     }),
@@ -270,6 +278,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .rustls(config.clone())
                     }),
@@ -283,6 +292,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .rustls(config.clone())
                     }),
@@ -299,6 +309,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .rustls_021(config.clone())
                     }),
@@ -312,6 +323,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .rustls_021(config.clone())
                     }),
@@ -325,6 +337,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .rustls_021(config.clone())
                     }),
@@ -341,6 +354,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .rustls_0_22(config.clone())
                     }),
@@ -354,6 +368,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .rustls_0_22(config.clone())
                     }),
@@ -367,6 +382,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .defer_continue(defer_continue)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .rustls_0_22(config.clone())
                     }),
@@ -459,6 +475,7 @@ pub struct TestServerConfig {
     tp: HttpVer,
     stream: StreamType,
     client_request_timeout: Duration,
+    defer_continue: bool,
     port: u16,
     workers: usize,
 }
@@ -476,6 +493,7 @@ impl TestServerConfig {
             tp: HttpVer::Both,
             stream: StreamType::Tcp,
             client_request_timeout: Duration::from_secs(5),
+            defer_continue: false,
             port: 0,
             workers: 1,
         }
@@ -543,6 +561,14 @@ impl TestServerConfig {
         self
     }
 
+    /// Sets whether `100 Continue` responses are deferred until the request payload is first read.
+    ///
+    /// See [`HttpServer::defer_continue()`](actix_web::HttpServer::defer_continue).
+    pub fn defer_continue(mut self, enabled: bool) -> Self {
+        self.defer_continue = enabled;
+        self
+    }
+
     /// Sets test server port.
     ///
     /// By default, a random free port is determined by the OS.
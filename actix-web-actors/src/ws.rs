@@ -379,23 +379,28 @@ pub fn handshake_with_protocols(
         return Err(HandshakeError::GetMethodRequired);
     }
 
-    // check for "UPGRADE" to WebSocket header
-    let has_hdr = if let Some(hdr) = req.headers().get(&header::UPGRADE) {
-        if let Ok(s) = hdr.to_str() {
-            s.to_ascii_lowercase().contains("websocket")
+    // HTTP/2 extended CONNECT requests (RFC 8441) have no upgrade headers or key
+    let extended_connect = req.head().protocol() == Some("websocket");
+
+    if !extended_connect {
+        // check for "UPGRADE" to WebSocket header
+        let has_hdr = if let Some(hdr) = req.headers().get(&header::UPGRADE) {
+            if let Ok(s) = hdr.to_str() {
+                s.to_ascii_lowercase().contains("websocket")
+            } else {
+                false
+            }
         } else {
             false
+        };
+        if !has_hdr {
+            return Err(HandshakeError::NoWebsocketUpgrade);
         }
-    } else {
-        false
-    };
-    if !has_hdr {
-        return Err(HandshakeError::NoWebsocketUpgrade);
-    }
 
-    // Upgrade connection
-    if !req.head().upgrade() {
-        return Err(HandshakeError::NoConnectionUpgrade);
+        // Upgrade connection
+        if !req.head().upgrade() {
+            return Err(HandshakeError::NoConnectionUpgrade);
+        }
     }
 
     // check supported version
@@ -413,15 +418,6 @@ pub fn handshake_with_protocols(
         return Err(HandshakeError::UnsupportedVersion);
     }
 
-    // check client handshake for validity
-    if !req.headers().contains_key(&header::SEC_WEBSOCKET_KEY) {
-        return Err(HandshakeError::BadWebsocketKey);
-    }
-    let key = {
-        let key = req.headers().get(&header::SEC_WEBSOCKET_KEY).unwrap();
-        hash_key(key.as_ref())
-    };
-
     // check requested protocols
     let protocol = req
         .headers()
@@ -434,14 +430,27 @@ pub fn handshake_with_protocols(
                 .find(|req_p| protocols.iter().any(|p| p == req_p))
         });
 
-    let mut response = HttpResponse::build(StatusCode::SWITCHING_PROTOCOLS)
-        .upgrade("websocket")
-        .insert_header((
-            header::SEC_WEBSOCKET_ACCEPT,
-            // key is known to be header value safe ascii
-            HeaderValue::from_bytes(&key).unwrap(),
-        ))
-        .take();
+    let mut response = if extended_connect {
+        HttpResponse::build(StatusCode::OK)
+    } else {
+        // check client handshake for validity
+        if !req.headers().contains_key(&header::SEC_WEBSOCKET_KEY) {
+            return Err(HandshakeError::BadWebsocketKey);
+        }
+        let key = {
+            let key = req.headers().get(&header::SEC_WEBSOCKET_KEY).unwrap();
+            hash_key(key.as_ref())
+        };
+
+        HttpResponse::build(StatusCode::SWITCHING_PROTOCOLS)
+            .upgrade("websocket")
+            .insert_header((
+                header::SEC_WEBSOCKET_ACCEPT,
+                // key is known to be header value safe ascii
+                HeaderValue::from_bytes(&key).unwrap(),
+            ))
+            .take()
+    };
 
     if let Some(protocol) = protocol {
         response.insert_header((header::SEC_WEBSOCKET_PROTOCOL, protocol));
@@ -0,0 +1,221 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_quote, spanned::Spanned, Data, DeriveInput, Fields, GenericArgument, Type};
+
+/// Maximum arity of the tuple `FromRequest` implementations in `actix-web`.
+const MAX_TUPLE_LEN: usize = 16;
+
+/// Extractor types which are known to consume the request payload.
+const PAYLOAD_EXTRACTORS: &[&str] = &[
+    "Bytes",
+    "Form",
+    "Json",
+    "Multipart",
+    "MultipartForm",
+    "Payload",
+    "String",
+];
+
+/// Wrapper extractors which consume the payload if their inner extractor does.
+const WRAPPER_EXTRACTORS: &[&str] = &["Either", "Option", "Result", "Valid"];
+
+struct ParsedField<'a> {
+    /// Field identifier for named fields.
+    ident: Option<&'a syn::Ident>,
+
+    /// Binding used when destructuring the extracted tuple.
+    binding: syn::Ident,
+
+    ty: &'a Type,
+}
+
+pub(crate) fn derive(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "FromRequest can only be derived for structs",
+            ))
+        }
+    };
+
+    if fields.is_empty() {
+        return Err(syn::Error::new(
+            fields.span(),
+            "FromRequest can only be derived for structs with at least one field",
+        ));
+    }
+
+    let mut parsed = Vec::with_capacity(fields.len());
+    let mut payload_field = None::<(usize, &syn::Field)>;
+
+    for (idx, field) in fields.iter().enumerate() {
+        if consumes_payload(field)? {
+            if let Some((prev_idx, prev)) = payload_field {
+                let prev = match &prev.ident {
+                    Some(ident) => format!("`{ident}`"),
+                    None => format!("field {prev_idx}"),
+                };
+
+                return Err(syn::Error::new(
+                    field.ty.span(),
+                    format!(
+                        "at most one field can consume the request payload; \
+                        this field and {prev} both do"
+                    ),
+                ));
+            }
+
+            payload_field = Some((idx, field));
+        }
+
+        parsed.push(ParsedField {
+            ident: field.ident.as_ref(),
+            binding: format_ident!("__field{}", idx),
+            ty: &field.ty,
+        });
+    }
+
+    let name = &input.ident;
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in &parsed {
+        let ty = field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::actix_web::FromRequest + 'static));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let (tuple_ty, tuple_pat) = tuple_parts(&parsed);
+
+    let construct = match fields {
+        Fields::Named(_) => {
+            let fields = parsed.iter().map(|field| {
+                let ident = field.ident;
+                let binding = &field.binding;
+                quote! { #ident: #binding }
+            });
+
+            quote! { Self { #(#fields),* } }
+        }
+
+        _ => {
+            let bindings = parsed.iter().map(|field| &field.binding);
+            quote! { Self(#(#bindings),*) }
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::actix_web::FromRequest for #name #ty_generics #where_clause {
+            type Error = ::actix_web::Error;
+            type Future = ::actix_web::dev::FromRequestMapFuture<
+                <#tuple_ty as ::actix_web::FromRequest>::Future,
+                #tuple_ty,
+                Self,
+            >;
+
+            fn from_request(
+                req: &::actix_web::HttpRequest,
+                payload: &mut ::actix_web::dev::Payload,
+            ) -> Self::Future {
+                ::actix_web::dev::FromRequestMapFuture::new(
+                    <#tuple_ty as ::actix_web::FromRequest>::from_request(req, payload),
+                    |#tuple_pat| #construct,
+                )
+            }
+        }
+    })
+}
+
+/// Builds the tuple type that is extracted and the pattern used to destructure it.
+///
+/// Structs with more fields than the largest tuple implementation are split into nested tuples.
+fn tuple_parts(fields: &[ParsedField<'_>]) -> (TokenStream2, TokenStream2) {
+    if fields.len() <= MAX_TUPLE_LEN {
+        let tys = fields.iter().map(|field| field.ty);
+        let bindings = fields.iter().map(|field| &field.binding);
+
+        return (quote! { (#(#tys,)*) }, quote! { (#(#bindings,)*) });
+    }
+
+    let (tys, pats): (Vec<_>, Vec<_>) = fields.chunks(MAX_TUPLE_LEN).map(tuple_parts).unzip();
+
+    (quote! { (#(#tys,)*) }, quote! { (#(#pats,)*) })
+}
+
+/// Returns true if field is marked with `#[from_request(payload)]` or its type is a known
+/// payload extractor.
+fn consumes_payload(field: &syn::Field) -> syn::Result<bool> {
+    let mut marked = false;
+
+    for attr in &field.attrs {
+        if !attr.path().is_ident("from_request") {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("payload") {
+                marked = true;
+                Ok(())
+            } else {
+                Err(meta.error(format!(
+                    "unknown from_request attribute `{}`, expected `payload`",
+                    meta.path.to_token_stream()
+                )))
+            }
+        })?;
+    }
+
+    Ok(marked || is_payload_extractor(&field.ty))
+}
+
+fn is_payload_extractor(ty: &Type) -> bool {
+    let segment = match ty {
+        Type::Path(ty) => match ty.path.segments.last() {
+            Some(segment) => segment,
+            None => return false,
+        },
+        Type::Group(ty) => return is_payload_extractor(&ty.elem),
+        Type::Paren(ty) => return is_payload_extractor(&ty.elem),
+        _ => return false,
+    };
+
+    let name = segment.ident.to_string();
+
+    if PAYLOAD_EXTRACTORS.contains(&name.as_str()) {
+        return true;
+    }
+
+    if !WRAPPER_EXTRACTORS.contains(&name.as_str()) {
+        return false;
+    }
+
+    let args = match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => &args.args,
+        _ => return false,
+    };
+
+    // the error type of `Result<T, E>` is not an extractor
+    let args = args
+        .iter()
+        .take(if name == "Result" { 1 } else { usize::MAX });
+
+    args.filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    })
+    .any(is_payload_extractor)
+}
@@ -61,6 +61,44 @@
 //! }
 //! ```
 //!
+//! # Scoped Handlers
+//! Handlers defined in an inline module can be grouped under a common path prefix. See
+//! [macro@scope] macro docs.
+//!
+//! ```
+//! # use actix_web::App;
+//! # use actix_web_codegen::scope;
+//! #[scope("/api")]
+//! mod api {
+//!     # use actix_web::HttpResponse;
+//!     # use actix_web_codegen::get;
+//!     #[get("/test")]
+//!     async fn test() -> HttpResponse {
+//!         HttpResponse::Ok().finish()
+//!     }
+//! }
+//!
+//! let app = App::new().configure(api::config);
+//! ```
+//!
+//! # Composite Extractors
+//! Structs made up of extractor fields can derive `FromRequest`. See [macro@FromRequest] macro
+//! docs.
+//!
+//! ```
+//! # use actix_web::web;
+//! # use actix_web_codegen::FromRequest;
+//! #[derive(FromRequest)]
+//! struct Ctx {
+//!     id: web::Path<u32>,
+//!     body: web::Json<String>,
+//! }
+//! ```
+//!
+//! # Error and Response Types
+//! Error types can derive `ResponseError` and enums of responders can derive `Responder`. See
+//! [macro@ResponseError] and [macro@Responder] macro docs.
+//!
 //! [actix-web attributes docs]: https://docs.rs/actix-web/latest/actix_web/#attributes
 //! [GET]: macro@get
 //! [POST]: macro@post
@@ -82,7 +120,11 @@
 use proc_macro::TokenStream;
 use quote::quote;
 
+mod from_request;
+mod responder;
+mod response_error;
 mod route;
+mod scope;
 
 /// Creates resource handler, allowing multiple HTTP method guards.
 ///
@@ -151,6 +193,59 @@ pub fn routes(_: TokenStream, input: TokenStream) -> TokenStream {
     route::with_methods(input)
 }
 
+/// Groups the route handlers in an inline module under a common scope.
+///
+/// Generates a `pub fn config(cfg: &mut ServiceConfig)` function in the module which registers a
+/// [`Scope`](https://docs.rs/actix-web/4/actix_web/struct.Scope.html) containing every function
+/// in the module annotated with a routing macro (e.g., [`get`](macro@get) or
+/// [`routes`](macro@routes)). Route paths are relative to the scope path.
+///
+/// Nested modules that are also annotated with `#[scope]` are registered inside this scope, so
+/// their paths are relative to this scope's path and only the outermost `config` function needs
+/// to be registered. Handlers in other nested modules are not included.
+///
+/// # Syntax
+/// ```plain
+/// #[scope("path"[, attributes])]
+/// ```
+///
+/// # Attributes
+/// - `"path"`: Raw literal string with the path prefix of the scope.
+/// - `guard = "function_name"`: Registers function as guard using `actix_web::guard::fn_guard`.
+/// - `wrap = "Middleware"`: Registers a scope middleware.
+///
+/// # Notes
+/// Guard functions and middleware expressions are resolved inside the annotated module, so any
+/// items they refer to need to be in scope there.
+///
+/// # Examples
+/// ```
+/// # use actix_web::App;
+/// # use actix_web_codegen::scope;
+/// #[scope("/api/v1", wrap = "middleware::Logger::default()")]
+/// mod api {
+///     use actix_web::{middleware, web, HttpResponse};
+///     use actix_web_codegen::{get, post};
+///
+///     #[get("/users")]
+///     async fn list_users() -> HttpResponse {
+///         HttpResponse::Ok().finish()
+///     }
+///
+///     #[post("/users")]
+///     async fn create_user(body: web::Json<String>) -> HttpResponse {
+///         HttpResponse::Created().finish()
+///     }
+/// }
+///
+/// // registers `GET /api/v1/users` and `POST /api/v1/users`
+/// let app = App::new().configure(api::config);
+/// ```
+#[proc_macro_attribute]
+pub fn scope(args: TokenStream, input: TokenStream) -> TokenStream {
+    scope::with_scope(args, input)
+}
+
 macro_rules! method_macro {
     ($variant:ident, $method:ident) => {
         #[doc = concat!("Creates route handler with `actix_web::guard::", stringify!($variant), "`.")]
@@ -240,3 +335,124 @@ pub fn test(_: TokenStream, item: TokenStream) -> TokenStream {
     output.extend(item);
     output
 }
+
+/// Derives `FromRequest` for a struct whose fields are all extractors.
+///
+/// All fields are extracted concurrently, in the same way as the tuple `FromRequest`
+/// implementations, and the first error encountered is returned. Both structs with named fields
+/// and tuple structs are supported.
+///
+/// # Payload Consumption
+/// The request payload can only be consumed once, so at most one field may be a payload
+/// extractor. This is checked at compile time for the built-in payload extractors (`Json`, `Form`,
+/// `Bytes`, `String`, `Payload`, and `MultipartForm`), including when wrapped in `Option`,
+/// `Result`, `Either`, or `Valid`. Custom extractors that read the payload can be marked with the
+/// `#[from_request(payload)]` field attribute so they are included in this check.
+///
+/// # Examples
+/// ```
+/// # use actix_web::{post, web, HttpRequest};
+/// # use actix_web_codegen::FromRequest;
+/// #[derive(FromRequest)]
+/// struct CreatePost {
+///     req: HttpRequest,
+///     user_id: web::Path<u64>,
+///     query: web::Query<Vec<(String, String)>>,
+///     body: web::Json<String>,
+/// }
+///
+/// #[post("/users/{user_id}/posts")]
+/// async fn create_post(ctx: CreatePost) -> String {
+///     format!("user {} posted {:?} to {}", ctx.user_id, ctx.body, ctx.req.path())
+/// }
+/// ```
+///
+/// Multiple payload extractors are rejected:
+/// ```compile_fail
+/// # use actix_web::web;
+/// # use actix_web_codegen::FromRequest;
+/// #[derive(FromRequest)]
+/// struct Invalid {
+///     json: web::Json<String>,
+///     bytes: web::Bytes,
+/// }
+/// ```
+#[proc_macro_derive(FromRequest, attributes(from_request))]
+pub fn from_request(input: TokenStream) -> TokenStream {
+    from_request::derive(input)
+}
+
+/// Derives `ResponseError` for an enum or struct.
+///
+/// The type must also implement `Debug` and `Display`, e.g. using `derive_more` or `thiserror`.
+///
+/// # Attributes
+/// Each of these attributes can be placed on the type, to apply to all variants, or on individual
+/// enum variants. Variant attributes take precedence over type attributes.
+/// - `#[status(404)]`: Sets the response status code. Defaults to 500 Internal Server Error.
+/// - `#[body(text)]`: Responds with the error's `Display` output as `text/plain`. This is the
+///   default.
+/// - `#[body(json)]`: Responds with the error serialized as JSON. Requires the type to implement
+///   `serde::Serialize`.
+/// - `#[header("name", "value")]`: Adds a response header. Can be repeated.
+///
+/// # Examples
+/// ```
+/// # use actix_web_codegen::ResponseError;
+/// #[derive(Debug, ResponseError)]
+/// #[header("cache-control", "no-store")]
+/// enum AccountError {
+///     #[status(404)]
+///     NotFound,
+///
+///     #[status(429)]
+///     #[header("retry-after", "60")]
+///     RateLimited,
+///
+///     Database(std::io::Error),
+/// }
+///
+/// impl std::fmt::Display for AccountError {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         match self {
+///             Self::NotFound => f.write_str("account not found"),
+///             Self::RateLimited => f.write_str("too many requests"),
+///             Self::Database(err) => write!(f, "database error: {err}"),
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro_derive(ResponseError, attributes(status, body, header))]
+pub fn response_error(input: TokenStream) -> TokenStream {
+    response_error::derive(input)
+}
+
+/// Derives `Responder` for an enum whose variants each wrap a responder.
+///
+/// Each variant must be a tuple variant with a single field that implements `Responder`. This
+/// allows handlers with several different kinds of response to avoid nesting `Either` types.
+///
+/// # Examples
+/// ```
+/// # use actix_web::{get, web, HttpResponse};
+/// # use actix_web_codegen::Responder;
+/// #[derive(Responder)]
+/// enum UserResponse {
+///     Found(web::Json<Vec<String>>),
+///     NotFound(HttpResponse),
+///     Redirect(web::Redirect),
+/// }
+///
+/// #[get("/users/{id}")]
+/// async fn user(id: web::Path<u32>) -> UserResponse {
+///     match id.into_inner() {
+///         0 => UserResponse::Redirect(web::Redirect::to("/users/1")),
+///         1 => UserResponse::Found(web::Json(vec!["alice".to_owned()])),
+///         _ => UserResponse::NotFound(HttpResponse::NotFound().finish()),
+///     }
+/// }
+/// ```
+#[proc_macro_derive(Responder)]
+pub fn responder(input: TokenStream) -> TokenStream {
+    responder::derive(input)
+}
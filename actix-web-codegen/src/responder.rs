@@ -0,0 +1,74 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned, Data, DeriveInput, Fields};
+
+pub(crate) fn derive(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "Responder can only be derived for enums",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    let mut arms = Vec::with_capacity(data.variants.len());
+
+    for variant in &data.variants {
+        let ident = &variant.ident;
+
+        let field =
+            match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => &fields.unnamed[0],
+                _ => return Err(syn::Error::new(
+                    variant.span(),
+                    "Responder can only be derived for enums whose variants each wrap a single \
+                    responder, e.g. `Ok(Json<T>)`",
+                )),
+            };
+
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::actix_web::Responder));
+        where_clause
+            .predicates
+            .push(parse_quote!(<#ty as ::actix_web::Responder>::Body: 'static));
+
+        arms.push(quote! {
+            Self::#ident(inner) => ::actix_web::Responder::respond_to(inner, req)
+                .map_into_boxed_body(),
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::actix_web::Responder for #name #ty_generics #where_clause {
+            type Body = ::actix_web::body::BoxBody;
+
+            fn respond_to(
+                self,
+                req: &::actix_web::HttpRequest,
+            ) -> ::actix_web::HttpResponse<Self::Body> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,191 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{punctuated::Punctuated, Attribute, Data, DeriveInput, LitInt, LitStr, Token};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+    Text,
+    Json,
+}
+
+/// Response options set using attributes on the type or on a variant.
+#[derive(Clone, Default)]
+struct ResponseOpts {
+    status: Option<u16>,
+    body: Option<BodyKind>,
+    headers: Vec<(LitStr, LitStr)>,
+}
+
+impl ResponseOpts {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut opts = Self::default();
+
+        for attr in attrs {
+            if attr.path().is_ident("status") {
+                if opts.status.is_some() {
+                    return Err(syn::Error::new_spanned(attr, "duplicate status attribute"));
+                }
+
+                let lit = attr.parse_args::<LitInt>()?;
+                let status = lit.base10_parse::<u16>()?;
+
+                if !(100..1000).contains(&status) {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "status code must be in the range 100 to 999",
+                    ));
+                }
+
+                opts.status = Some(status);
+            } else if attr.path().is_ident("body") {
+                if opts.body.is_some() {
+                    return Err(syn::Error::new_spanned(attr, "duplicate body attribute"));
+                }
+
+                let kind = attr.parse_args::<syn::Ident>()?;
+
+                opts.body = Some(match kind.to_string().as_str() {
+                    "text" => BodyKind::Text,
+                    "json" => BodyKind::Json,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            kind,
+                            "unknown body format, expected `text` or `json`",
+                        ))
+                    }
+                });
+            } else if attr.path().is_ident("header") {
+                let args =
+                    attr.parse_args_with(Punctuated::<LitStr, Token![,]>::parse_terminated)?;
+
+                if args.len() != 2 {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        r#"invalid header attribute, expected #[header("<name>", "<value>")]"#,
+                    ));
+                }
+
+                let mut args = args.into_iter();
+                opts.headers
+                    .push((args.next().unwrap(), args.next().unwrap()));
+            }
+        }
+
+        Ok(opts)
+    }
+
+    /// Fills in options not set on a variant from the type's options.
+    fn or(mut self, defaults: &Self) -> Self {
+        self.status = self.status.or(defaults.status);
+        self.body = self.body.or(defaults.body);
+
+        let mut headers = defaults.headers.clone();
+        headers.append(&mut self.headers);
+        self.headers = headers;
+
+        self
+    }
+
+    fn status_tokens(&self) -> TokenStream2 {
+        match self.status {
+            Some(status) => quote! {
+                ::actix_web::http::StatusCode::from_u16(#status).unwrap()
+            },
+            None => quote! { ::actix_web::http::StatusCode::INTERNAL_SERVER_ERROR },
+        }
+    }
+
+    fn response_tokens(&self) -> TokenStream2 {
+        let headers = self.headers.iter().map(|(name, value)| {
+            quote! { res.insert_header((#name, #value)); }
+        });
+
+        let body = match self.body.unwrap_or(BodyKind::Text) {
+            BodyKind::Text => quote! {
+                res.insert_header(::actix_web::http::header::ContentType::plaintext());
+                #(#headers)*
+                res.body(::std::string::ToString::to_string(self))
+            },
+            BodyKind::Json => quote! {
+                #(#headers)*
+                res.json(self)
+            },
+        };
+
+        quote! {{
+            let mut res = ::actix_web::HttpResponse::build(
+                ::actix_web::ResponseError::status_code(self),
+            );
+            #body
+        }}
+    }
+}
+
+pub(crate) fn derive(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+
+    match expand(input) {
+        Ok(output) => output.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let defaults = ResponseOpts::parse(&input.attrs)?;
+
+    let (status_code, error_response) = match &input.data {
+        Data::Struct(_) => (defaults.status_tokens(), defaults.response_tokens()),
+
+        Data::Enum(data) => {
+            let mut status_arms = Vec::with_capacity(data.variants.len());
+            let mut response_arms = Vec::with_capacity(data.variants.len());
+
+            for variant in &data.variants {
+                let ident = &variant.ident;
+                let opts = ResponseOpts::parse(&variant.attrs)?.or(&defaults);
+
+                let status = opts.status_tokens();
+                let response = opts.response_tokens();
+
+                status_arms.push(quote! { Self::#ident { .. } => #status, });
+                response_arms.push(quote! { Self::#ident { .. } => #response, });
+            }
+
+            (
+                quote! {
+                    match *self {
+                        #(#status_arms)*
+                    }
+                },
+                quote! {
+                    match *self {
+                        #(#response_arms)*
+                    }
+                },
+            )
+        }
+
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "ResponseError cannot be derived for unions",
+            ))
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::actix_web::ResponseError for #name #ty_generics #where_clause {
+            fn status_code(&self) -> ::actix_web::http::StatusCode {
+                #status_code
+            }
+
+            fn error_response(&self) -> ::actix_web::HttpResponse<::actix_web::body::BoxBody> {
+                #error_response
+            }
+        }
+    })
+}
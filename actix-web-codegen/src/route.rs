@@ -411,6 +411,12 @@ impl ToTokens for Route {
             doc_attributes,
         } = self;
 
+        // only attach OpenAPI documentation when enabled, since it relies on `actix-web` internals
+        #[cfg(feature = "openapi")]
+        let openapi = openapi_tokens(ast, doc_attributes);
+        #[cfg(not(feature = "openapi"))]
+        let openapi = quote! { __resource };
+
         let registrations: TokenStream2 = args
             .iter()
             .map(|args| {
@@ -450,6 +456,7 @@ impl ToTokens for Route {
                         #(.guard(::actix_web::guard::fn_guard(#guards)))*
                         #(.wrap(#wrappers))*
                         .to(#name);
+                    let __resource = #openapi;
                     ::actix_web::dev::HttpServiceFactory::register(__resource, __config);
                 }
             })
@@ -472,6 +479,67 @@ impl ToTokens for Route {
     }
 }
 
+/// Generates the expression that attaches OpenAPI documentation to `__resource`.
+///
+/// The summary and description are taken from the doc comment. Types of arguments and the return
+/// type are passed on to be described, unless they are `impl Trait` types.
+#[cfg(feature = "openapi")]
+fn openapi_tokens(ast: &syn::ItemFn, doc_attributes: &[syn::Attribute]) -> TokenStream2 {
+    let docs = doc_attributes
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(lit),
+                        ..
+                    }),
+                ..
+            }) => Some(lit.value()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut lines = docs
+        .lines()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .skip_while(|line| line.trim().is_empty());
+
+    let summary = lines.next().unwrap_or_default().trim();
+    let description = lines.collect::<Vec<_>>().join("\n");
+    let description = description.trim();
+
+    let inputs = ast.sig.inputs.iter().filter_map(|arg| match arg {
+        syn::FnArg::Typed(pat) if !contains_impl_trait(pat.ty.to_token_stream()) => Some(&pat.ty),
+        _ => None,
+    });
+
+    let output = match &ast.sig.output {
+        syn::ReturnType::Type(_, ty) if !contains_impl_trait(ty.to_token_stream()) => Some(ty),
+        _ => None,
+    };
+
+    quote! {
+        ::actix_web::__openapi_resource!(
+            __resource,
+            #summary,
+            #description,
+            [#(#inputs),*],
+            [#output]
+        )
+    }
+}
+
+#[cfg(feature = "openapi")]
+fn contains_impl_trait(tokens: TokenStream2) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        proc_macro2::TokenTree::Ident(ident) => ident == "impl",
+        proc_macro2::TokenTree::Group(group) => contains_impl_trait(group.stream()),
+        _ => false,
+    })
+}
+
 pub(crate) fn with_method(
     method: Option<MethodType>,
     args: TokenStream,
@@ -549,7 +617,7 @@ pub(crate) fn with_methods(input: TokenStream) -> TokenStream {
 /// recover and show more precise errors within the macro body.
 ///
 /// See <https://github.com/rust-analyzer/rust-analyzer/issues/10468> for more info.
-fn input_and_compile_error(mut item: TokenStream, err: syn::Error) -> TokenStream {
+pub(crate) fn input_and_compile_error(mut item: TokenStream, err: syn::Error) -> TokenStream {
     let compile_err = TokenStream::from(err.to_compile_error());
     item.extend(compile_err);
     item
@@ -0,0 +1,207 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, ToTokens};
+use syn::{punctuated::Punctuated, Token};
+
+use crate::route::input_and_compile_error;
+
+/// Attribute macros that generate a service factory named after the annotated function.
+const ROUTE_MACROS: &[&str] = &[
+    "route", "routes", "get", "post", "put", "delete", "head", "connect", "options", "trace",
+    "patch",
+];
+
+pub struct ScopeArgs {
+    path: syn::LitStr,
+    options: Punctuated<syn::MetaNameValue, Token![,]>,
+}
+
+impl syn::parse::Parse for ScopeArgs {
+    fn parse(input: syn::parse::ParseStream<'_>) -> syn::Result<Self> {
+        // path prefix: "/api"
+        let path = input.parse::<syn::LitStr>().map_err(|mut err| {
+            err.combine(syn::Error::new(
+                err.span(),
+                r#"invalid scope definition, expected #[scope("<path>")]"#,
+            ));
+
+            err
+        })?;
+
+        // if there's no comma, assume that no options are provided
+        if !input.peek(Token![,]) {
+            return Ok(Self {
+                path,
+                options: Punctuated::new(),
+            });
+        }
+
+        // advance past comma separator
+        input.parse::<Token![,]>()?;
+
+        // zero or more options: guard = "foo"
+        let options = input.parse_terminated(syn::MetaNameValue::parse, Token![,])?;
+
+        Ok(Self { path, options })
+    }
+}
+
+struct Scope {
+    path: syn::LitStr,
+    guards: Vec<syn::Path>,
+    wrappers: Vec<syn::Expr>,
+
+    /// AST of the module being annotated.
+    ast: syn::ItemMod,
+
+    /// Names of route handlers found in the module.
+    handlers: Vec<syn::Ident>,
+
+    /// Names of nested modules annotated with `#[scope]`.
+    nested: Vec<syn::Ident>,
+}
+
+impl Scope {
+    fn new(args: ScopeArgs, ast: syn::ItemMod) -> syn::Result<Self> {
+        let mut guards = Vec::new();
+        let mut wrappers = Vec::new();
+
+        for nv in args.options {
+            let lit = match nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit),
+                    ..
+                }) => lit,
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "Scope attributes expect literal strings",
+                    ))
+                }
+            };
+
+            if nv.path.is_ident("guard") {
+                guards.push(lit.parse()?);
+            } else if nv.path.is_ident("wrap") {
+                wrappers.push(lit.parse()?);
+            } else {
+                return Err(syn::Error::new_spanned(
+                    nv.path,
+                    "Unknown attribute key is specified; allowed: guard and wrap",
+                ));
+            }
+        }
+
+        let items = match &ast.content {
+            Some((_, items)) => items,
+            None => {
+                return Err(syn::Error::new_spanned(
+                    ast,
+                    "The #[scope(..)] macro can only be used on inline modules",
+                ))
+            }
+        };
+
+        let handlers = items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Fn(func) if func.attrs.iter().any(is_route_macro) => {
+                    Some(func.sig.ident.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
+        // nested scopes are expanded after this one, so their attributes are still present
+        let nested = items
+            .iter()
+            .filter_map(|item| match item {
+                syn::Item::Mod(module) if module.attrs.iter().any(is_scope_macro) => {
+                    Some(module.ident.clone())
+                }
+                _ => None,
+            })
+            .collect();
+
+        Ok(Self {
+            path: args.path,
+            guards,
+            wrappers,
+            ast,
+            handlers,
+            nested,
+        })
+    }
+}
+
+impl ToTokens for Scope {
+    fn to_tokens(&self, output: &mut TokenStream2) {
+        let Self {
+            path,
+            guards,
+            wrappers,
+            ast,
+            handlers,
+            nested,
+        } = self;
+
+        let doc = format!(
+            "Registers the route handlers in this module under the `{}` scope.",
+            path.value()
+        );
+
+        let config = quote! {
+            #[doc = #doc]
+            pub fn config(cfg: &mut ::actix_web::web::ServiceConfig) {
+                cfg.service(
+                    ::actix_web::web::scope(#path)
+                        #(.guard(::actix_web::guard::fn_guard(#guards)))*
+                        #(.wrap(#wrappers))*
+                        #(.service(#handlers))*
+                        #(.configure(#nested::config))*
+                );
+            }
+        };
+
+        let mut ast = ast.clone();
+        if let Some((_, items)) = &mut ast.content {
+            items.push(syn::Item::Verbatim(config));
+        }
+
+        ast.to_tokens(output);
+    }
+}
+
+fn is_route_macro(attr: &syn::Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|seg| ROUTE_MACROS.contains(&seg.ident.to_string().as_str()))
+}
+
+fn is_scope_macro(attr: &syn::Attribute) -> bool {
+    attr.path()
+        .segments
+        .last()
+        .is_some_and(|seg| seg.ident == "scope")
+}
+
+pub(crate) fn with_scope(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match syn::parse(args) {
+        Ok(args) => args,
+        // on parse error, make IDEs happy; see fn docs
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    let ast = match syn::parse::<syn::ItemMod>(input.clone()) {
+        Ok(ast) => ast,
+        // on parse error, make IDEs happy; see fn docs
+        Err(err) => return input_and_compile_error(input, err),
+    };
+
+    match Scope::new(args, ast) {
+        Ok(scope) => scope.into_token_stream().into(),
+        // on macro related error, make IDEs happy; see fn docs
+        Err(err) => input_and_compile_error(input, err),
+    }
+}
@@ -11,7 +11,8 @@ use actix_web::{
     web, App, Error, HttpRequest, HttpResponse, Responder,
 };
 use actix_web_codegen::{
-    connect, delete, get, head, options, patch, post, put, route, routes, trace,
+    connect, delete, get, head, options, patch, post, put, route, routes, scope, trace,
+    FromRequest, ResponseError,
 };
 use futures_core::future::LocalBoxFuture;
 
@@ -247,6 +248,53 @@ async fn test_params() {
     assert_eq!(response.status(), http::StatusCode::NO_CONTENT);
 }
 
+#[get("/fallback")]
+async fn fallback_get() -> impl Responder {
+    "hello"
+}
+
+#[post("/fallback")]
+async fn fallback_post() -> impl Responder {
+    HttpResponse::Created()
+}
+
+#[actix_rt::test]
+async fn test_method_fallback() {
+    let srv = actix_test::start(|| {
+        App::new()
+            .method_not_allowed(true)
+            .auto_head(true)
+            .auto_options(true)
+            .service(fallback_get)
+            .service(fallback_post)
+    });
+
+    let mut response = srv.head("/fallback").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response
+            .headers()
+            .get(http::header::CONTENT_LENGTH)
+            .unwrap(),
+        "5"
+    );
+    assert!(response.body().await.unwrap().is_empty());
+
+    let response = srv.options("/fallback").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        response.headers().get(http::header::ALLOW).unwrap(),
+        "GET, POST, HEAD, OPTIONS"
+    );
+
+    let response = srv.put("/fallback").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(
+        response.headers().get(http::header::ALLOW).unwrap(),
+        "GET, POST, HEAD, OPTIONS"
+    );
+}
+
 #[actix_rt::test]
 async fn test_body() {
     let srv = actix_test::start(|| {
@@ -384,3 +432,249 @@ async fn test_wrap() {
     let body = String::from_utf8(body.to_vec()).unwrap();
     assert!(body.contains("wrong number of parameters"));
 }
+
+#[derive(FromRequest)]
+struct CreateItem {
+    req: HttpRequest,
+    id: web::Path<u32>,
+    query: web::Query<Vec<(String, String)>>,
+    data: web::Data<String>,
+    body: web::Json<Vec<u32>>,
+}
+
+#[derive(FromRequest)]
+struct Ids(web::Path<(u32, u32)>, Option<web::Json<u32>>);
+
+#[post("/items/{id}")]
+async fn create_item(item: CreateItem) -> impl Responder {
+    format!(
+        "{} {} {} {} {:?}",
+        item.req.path(),
+        item.id,
+        item.query.len(),
+        item.data.as_str(),
+        *item.body
+    )
+}
+
+#[get("/ids/{a}/{b}")]
+async fn ids(Ids(path, body): Ids) -> impl Responder {
+    format!(
+        "{} {} {:?}",
+        path.0,
+        path.1,
+        body.map(|body| body.into_inner())
+    )
+}
+
+#[actix_rt::test]
+async fn test_derive_from_request() {
+    let srv = actix_test::start(|| {
+        App::new()
+            .app_data(web::Data::new("data".to_owned()))
+            .service(create_item)
+            .service(ids)
+    });
+
+    let request = srv.request(http::Method::POST, srv.url("/items/3?a=1&b=2"));
+    let mut response = request.send_json(&[1, 2]).await.unwrap();
+    assert!(response.status().is_success());
+    let body = response.body().await.unwrap();
+    assert_eq!(body, "/items/3 3 2 data [1, 2]");
+
+    let request = srv.request(http::Method::POST, srv.url("/items/abc"));
+    let response = request.send_json(&[1, 2]).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let request = srv.request(http::Method::GET, srv.url("/ids/1/2"));
+    let mut response = request.send().await.unwrap();
+    assert!(response.status().is_success());
+    let body = response.body().await.unwrap();
+    assert_eq!(body, "1 2 None");
+}
+
+#[derive(Debug, ResponseError)]
+#[header("cache-control", "no-store")]
+enum ItemError {
+    #[status(404)]
+    NotFound,
+
+    #[status(429)]
+    #[header("retry-after", "60")]
+    RateLimited,
+
+    Internal,
+}
+
+impl std::fmt::Display for ItemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("item not found"),
+            Self::RateLimited => f.write_str("slow down"),
+            Self::Internal => f.write_str("internal error"),
+        }
+    }
+}
+
+#[derive(Debug, ResponseError)]
+#[status(400)]
+#[body(json)]
+struct BadInput {
+    field: &'static str,
+}
+
+impl std::fmt::Display for BadInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bad input: {}", self.field)
+    }
+}
+
+impl serde::Serialize for BadInput {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.field)
+    }
+}
+
+#[derive(Responder)]
+enum ItemResponse {
+    Found(web::Json<Vec<u32>>),
+    Created(HttpResponse),
+    Text(&'static str),
+}
+
+#[get("/items/{id}")]
+async fn get_item(id: web::Path<u32>) -> Result<ItemResponse, ItemError> {
+    match id.into_inner() {
+        0 => Ok(ItemResponse::Found(web::Json(vec![1, 2]))),
+        1 => Ok(ItemResponse::Created(HttpResponse::Created().finish())),
+        2 => Ok(ItemResponse::Text("text")),
+        3 => Err(ItemError::NotFound),
+        4 => Err(ItemError::RateLimited),
+        _ => Err(ItemError::Internal),
+    }
+}
+
+#[get("/bad-input")]
+async fn bad_input() -> Result<HttpResponse, BadInput> {
+    Err(BadInput { field: "name" })
+}
+
+#[actix_rt::test]
+async fn test_derive_responses() {
+    let srv = actix_test::start(|| App::new().service(get_item).service(bad_input));
+
+    let mut response = srv.get("/items/0").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body().await.unwrap(), "[1,2]");
+
+    let response = srv.get("/items/1").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let mut response = srv.get("/items/2").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body().await.unwrap(), "text");
+
+    let mut response = srv.get("/items/3").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/plain; charset=utf-8"
+    );
+    assert_eq!(response.body().await.unwrap(), "item not found");
+
+    let response = srv.get("/items/4").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+    assert_eq!(response.headers().get("retry-after").unwrap(), "60");
+
+    let response = srv.get("/items/5").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let mut response = srv.get("/bad-input").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/json"
+    );
+    assert_eq!(response.body().await.unwrap(), r#""name""#);
+}
+
+#[scope(
+    "/scoped",
+    wrap = "super::ChangeStatusCode",
+    guard = "super::scope_guard"
+)]
+mod scoped {
+    use actix_web::{web, HttpResponse, Responder};
+    use actix_web_codegen::{get, post, routes};
+
+    #[get("/test")]
+    async fn test() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[post("/items/{id}")]
+    async fn item(id: web::Path<u32>) -> impl Responder {
+        id.to_string()
+    }
+
+    #[routes]
+    #[get("/multi")]
+    #[put("/multi")]
+    async fn multi() -> impl Responder {
+        HttpResponse::Ok()
+    }
+
+    #[allow(dead_code)]
+    fn not_a_handler() {}
+
+    #[super::scope("/nested")]
+    mod nested {
+        use actix_web::{HttpResponse, Responder};
+        use actix_web_codegen::get;
+
+        #[get("/test")]
+        async fn test() -> impl Responder {
+            HttpResponse::Accepted()
+        }
+    }
+}
+
+fn scope_guard(ctx: &actix_web::guard::GuardContext<'_>) -> bool {
+    !ctx.head().headers().contains_key("x-deny")
+}
+
+#[actix_rt::test]
+async fn test_scope() {
+    let srv = actix_test::start(|| App::new().configure(scoped::config));
+
+    let response = srv.get("/scoped/test").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("custom-header"));
+
+    let mut response = srv.post("/scoped/items/7").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.body().await.unwrap(), "7");
+
+    let response = srv.put("/scoped/multi").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = srv.get("/scoped/nested/test").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    assert!(response.headers().contains_key("custom-header"));
+
+    let response = srv.get("/nested/test").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = srv.get("/test").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = srv
+        .get("/scoped/test")
+        .insert_header(("x-deny", "1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -21,4 +21,13 @@ fn compile_macros() {
     t.pass("tests/trybuild/docstring-ok.rs");
 
     t.pass("tests/trybuild/test-runtime.rs");
+
+    t.pass("tests/trybuild/scope-ok.rs");
+    t.compile_fail("tests/trybuild/scope-fail.rs");
+
+    t.pass("tests/trybuild/from-request-ok.rs");
+    t.compile_fail("tests/trybuild/from-request-payload-fail.rs");
+
+    t.pass("tests/trybuild/response-derive-ok.rs");
+    t.compile_fail("tests/trybuild/response-derive-fail.rs");
 }
@@ -0,0 +1,49 @@
+use actix_web::{web, HttpRequest};
+use actix_web_codegen::FromRequest;
+
+#[derive(FromRequest)]
+struct Named {
+    req: HttpRequest,
+    path: web::Path<String>,
+    query: Option<web::Query<Vec<(String, String)>>>,
+    body: web::Either<web::Json<String>, web::Form<Vec<(String, String)>>>,
+}
+
+#[derive(FromRequest)]
+struct Unnamed(web::Path<String>, web::Bytes);
+
+#[derive(FromRequest)]
+struct Generic<T: 'static> {
+    data: web::Data<T>,
+    body: String,
+}
+
+#[derive(FromRequest)]
+struct Large {
+    a: HttpRequest,
+    b: HttpRequest,
+    c: HttpRequest,
+    d: HttpRequest,
+    e: HttpRequest,
+    f: HttpRequest,
+    g: HttpRequest,
+    h: HttpRequest,
+    i: HttpRequest,
+    j: HttpRequest,
+    k: HttpRequest,
+    l: HttpRequest,
+    m: HttpRequest,
+    n: HttpRequest,
+    o: HttpRequest,
+    p: HttpRequest,
+    q: HttpRequest,
+    r: HttpRequest,
+}
+
+async fn handler(_: Named, _: Unnamed, _: Generic<u32>, _: Large) -> &'static str {
+    "ok"
+}
+
+fn main() {
+    let _ = web::to(handler);
+}
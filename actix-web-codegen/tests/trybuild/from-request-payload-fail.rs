@@ -0,0 +1,30 @@
+use actix_web::{dev, web, FromRequest, HttpRequest};
+use actix_utils::future::Ready;
+
+#[derive(FromRequest)]
+struct TwoPayloads {
+    json: web::Json<String>,
+    bytes: Option<web::Bytes>,
+}
+
+struct Custom;
+
+impl FromRequest for Custom {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(_: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        actix_utils::future::ok(Custom)
+    }
+}
+
+#[derive(FromRequest)]
+struct MarkedPayload(web::Form<String>, #[from_request(payload)] Custom);
+
+#[derive(FromRequest)]
+struct UnknownAttr {
+    #[from_request(body)]
+    custom: Custom,
+}
+
+fn main() {}
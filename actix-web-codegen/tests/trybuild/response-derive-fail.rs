@@ -0,0 +1,27 @@
+use actix_web::HttpResponse;
+use actix_web_codegen::{Responder, ResponseError};
+
+#[derive(Debug, ResponseError)]
+enum BadStatus {
+    #[status(1000)]
+    A,
+}
+
+#[derive(Debug, ResponseError)]
+#[body(xml)]
+struct BadBody;
+
+#[derive(Debug, ResponseError)]
+#[header("x-only-name")]
+struct BadHeader;
+
+#[derive(Responder)]
+enum BadVariant {
+    Ok(HttpResponse),
+    Empty,
+}
+
+#[derive(Responder)]
+struct NotEnum(HttpResponse);
+
+fn main() {}
@@ -0,0 +1,46 @@
+use actix_web::{web, HttpResponse};
+use actix_web_codegen::{Responder, ResponseError};
+
+#[derive(Debug, ResponseError)]
+#[status(400)]
+#[header("x-error", "true")]
+enum Error {
+    #[status(404)]
+    NotFound,
+
+    #[header("retry-after", "10")]
+    Tuple(u32),
+
+    #[status(409)]
+    #[body(text)]
+    Struct { id: u32 },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Debug, ResponseError)]
+struct Unit;
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unit")
+    }
+}
+
+#[derive(Responder)]
+enum Response<T: serde::Serialize> {
+    Json(web::Json<T>),
+    Http(HttpResponse),
+}
+
+async fn handler() -> Result<Response<u32>, Error> {
+    Ok(Response::Json(web::Json(1)))
+}
+
+fn main() {
+    let _ = web::to(handler);
+}
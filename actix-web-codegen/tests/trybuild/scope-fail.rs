@@ -0,0 +1,9 @@
+use actix_web_codegen::scope;
+
+#[scope("/api", name = "api")]
+mod unknown_key {}
+
+#[scope]
+mod missing_path {}
+
+fn main() {}
@@ -0,0 +1,41 @@
+use actix_web::App;
+use actix_web_codegen::scope;
+
+#[scope("/api")]
+mod api {
+    use actix_web::{guard::GuardContext, middleware, HttpResponse};
+    use actix_web_codegen::{get, route};
+
+    fn guard(_: &GuardContext<'_>) -> bool {
+        true
+    }
+
+    #[get("/one")]
+    async fn one() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[route("/two", method = "GET", method = "POST", wrap = "middleware::Compress::default()")]
+    async fn two() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[super::scope("/nested", guard = "super::guard")]
+    pub mod nested {
+        use actix_web::HttpResponse;
+
+        #[actix_web_codegen::get("/three")]
+        async fn three() -> HttpResponse {
+            HttpResponse::Ok().finish()
+        }
+    }
+}
+
+#[scope("")]
+mod empty {}
+
+fn main() {
+    let _ = App::new()
+        .configure(api::config)
+        .configure(empty::config);
+}
@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt, future::Future, rc::Rc};
+use std::{any, cell::RefCell, fmt, future::Future, rc::Rc};
 
 use actix_http::{body::MessageBody, Extensions, Request};
 use actix_service::{
@@ -13,6 +13,7 @@ use crate::{
     data::{Data, DataFactory, FnDataFactory},
     dev::ResourceDef,
     error::Error,
+    method_fallback::{MethodFallback, NotAllowed},
     resource::Resource,
     route::Route,
     service::{
@@ -30,6 +31,9 @@ pub struct App<T> {
     data_factories: Vec<FnDataFactory>,
     external: Vec<ResourceDef>,
     extensions: Extensions,
+    middleware: Vec<&'static str>,
+    method_fallback: MethodFallback,
+    radix_router: bool,
 }
 
 impl App<AppEntry> {
@@ -46,6 +50,9 @@ impl App<AppEntry> {
             factory_ref,
             external: Vec::new(),
             extensions: Extensions::new(),
+            middleware: Vec::new(),
+            method_fallback: MethodFallback::default(),
+            radix_router: false,
         }
     }
 }
@@ -279,6 +286,104 @@ where
         self
     }
 
+    /// Sets whether requests matching a resource's path but none of its routes' methods get an
+    /// empty `405 Method Not Allowed` response.
+    ///
+    /// The response lists the methods handled by the resource in its `Allow` header.
+    ///
+    /// By default, only requests matching a resource without method guards of its own get a `405`
+    /// response. Requests matching the paths of resources that are all guarded by other methods,
+    /// like those registered using [`route()`](Self::route) or the routing macros, are passed to
+    /// the app's default service. Enabling this also responds to these requests with `405`, while
+    /// disabling it passes all such requests to the default service instead. Resources with their
+    /// own default service are not affected.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// // `POST /` responds with `405 Method Not Allowed` and `Allow: GET`
+    /// let app = App::new()
+    ///     .method_not_allowed(true)
+    ///     .route("/", web::get().to(HttpResponse::Ok));
+    ///
+    /// // `POST /` responds with `404 Not Found`
+    /// let app = App::new()
+    ///     .method_not_allowed(false)
+    ///     .service(web::resource("/").get(HttpResponse::Ok));
+    /// ```
+    pub fn method_not_allowed(mut self, enabled: bool) -> Self {
+        self.method_fallback.not_allowed = if enabled {
+            NotAllowed::Enabled
+        } else {
+            NotAllowed::Disabled
+        };
+        self
+    }
+
+    /// Sets whether `OPTIONS` requests to resources without an `OPTIONS` route get an empty
+    /// `204 No Content` response listing the resource's methods in its `Allow` header.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// // `OPTIONS /` responds with `Allow: GET, OPTIONS`
+    /// let app = App::new()
+    ///     .auto_options(true)
+    ///     .route("/", web::get().to(HttpResponse::Ok));
+    /// ```
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.method_fallback.options = enabled;
+        self
+    }
+
+    /// Sets whether `HEAD` requests to resources without a `HEAD` route are handled by their `GET`
+    /// routes.
+    ///
+    /// The response body is discarded but its length is kept in the `Content-Length` header.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// // `HEAD /` responds with `Content-Length: 5` and no body
+    /// let app = App::new()
+    ///     .auto_head(true)
+    ///     .route("/", web::get().to(|| async { "hello" }));
+    /// ```
+    pub fn auto_head(mut self, enabled: bool) -> Self {
+        self.method_fallback.head = enabled;
+        self
+    }
+
+    /// Sets whether the app and its scopes index their services using a radix tree.
+    ///
+    /// Instead of testing every registered service's path pattern in turn, only services whose
+    /// pattern starts with a literal prefix of the request path are tested. This speeds up routing
+    /// for apps with many routes. Services are still tested in registration order, so the matched
+    /// service is the same either way.
+    ///
+    /// Disabled by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// let app = App::new()
+    ///     .radix_router(true)
+    ///     .route("/users/{id}", web::get().to(HttpResponse::Ok))
+    ///     .route("/posts/{id}", web::get().to(HttpResponse::Ok));
+    /// ```
+    pub fn radix_router(mut self, enabled: bool) -> Self {
+        self.radix_router = enabled;
+        self
+    }
+
     /// Register an external resource.
     ///
     /// External resources are useful for URL generation purposes only
@@ -359,6 +464,9 @@ where
             > + 'static,
         B: MessageBody,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<M>());
+
         App {
             endpoint: apply(mw, self.endpoint),
             data_factories: self.data_factories,
@@ -367,6 +475,9 @@ where
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
+            middleware,
+            method_fallback: self.method_fallback,
+            radix_router: self.radix_router,
         }
     }
 
@@ -422,6 +533,9 @@ where
         R: Future<Output = Result<ServiceResponse<B>, Error>>,
         B: MessageBody,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<F>());
+
         App {
             endpoint: apply_fn_factory(self.endpoint, mw),
             data_factories: self.data_factories,
@@ -430,6 +544,9 @@ where
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
+            middleware,
+            method_fallback: self.method_fallback,
+            radix_router: self.radix_router,
         }
     }
 }
@@ -454,6 +571,9 @@ where
             default: self.default,
             factory_ref: self.factory_ref,
             extensions: RefCell::new(Some(self.extensions)),
+            middleware: self.middleware,
+            method_fallback: self.method_fallback,
+            radix_router: self.radix_router,
         }
     }
 }
@@ -466,6 +586,7 @@ mod tests {
 
     use super::*;
     use crate::{
+        body::BodySize,
         http::{
             header::{self, HeaderValue},
             Method, StatusCode,
@@ -518,6 +639,119 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::CREATED);
     }
 
+    #[actix_rt::test]
+    async fn test_method_fallback() {
+        let srv = init_service(
+            App::new()
+                .service(web::resource("/test").route(web::get().to(|| async { "hello" })))
+                .route("/test", web::delete().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::PUT)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(resp.headers().get(header::ALLOW).unwrap(), "GET");
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let srv = init_service(App::new().route("/test", web::get().to(HttpResponse::Ok))).await;
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::POST)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let srv = init_service(
+            App::new()
+                .method_not_allowed(true)
+                .auto_head(true)
+                .auto_options(true)
+                .route("/test", web::get().to(|| async { "hello" }))
+                .route("/test", web::delete().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::PUT)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(
+            resp.headers().get(header::ALLOW).unwrap(),
+            "GET, DELETE, HEAD, OPTIONS"
+        );
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::OPTIONS)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            resp.headers().get(header::ALLOW).unwrap(),
+            "GET, DELETE, HEAD, OPTIONS"
+        );
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::HEAD)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.response().body().size(), BodySize::Sized(5));
+        assert!(read_body(resp).await.is_empty());
+
+        let srv = init_service(
+            App::new()
+                .method_not_allowed(false)
+                .service(web::resource("/test").get(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/test")
+            .method(Method::POST)
+            .to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_radix_router() {
+        let srv = init_service(
+            App::new()
+                .radix_router(true)
+                .route("/users/me", web::get().to(|| async { "me" }))
+                .route("/users/{id}", web::get().to(|| async { "user" }))
+                .service(
+                    web::scope("/posts")
+                        .route("", web::get().to(|| async { "posts" }))
+                        .route("/{id}", web::get().to(|| async { "post" })),
+                )
+                .route("/{tail}*", web::get().to(|| async { "fallback" })),
+        )
+        .await;
+
+        for (path, body) in [
+            ("/users/me", "me"),
+            ("/users/1", "user"),
+            ("/posts", "posts"),
+            ("/posts/1", "post"),
+            ("/users", "fallback"),
+            ("/other/path", "fallback"),
+        ] {
+            let req = TestRequest::with_uri(path).to_request();
+            let resp = srv.call(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            assert_eq!(read_body(resp).await, body, "unexpected body for {}", path);
+        }
+    }
+
     // allow deprecated App::data
     #[allow(deprecated)]
     #[actix_rt::test]
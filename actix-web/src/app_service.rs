@@ -1,6 +1,6 @@
 use std::{cell::RefCell, mem, rc::Rc};
 
-use actix_http::Request;
+use actix_http::{Method, Request};
 use actix_router::{Path, ResourceDef, Router, Url};
 use actix_service::{boxed, fn_service, Service, ServiceFactory};
 use futures_core::future::LocalBoxFuture;
@@ -12,8 +12,9 @@ use crate::{
     data::FnDataFactory,
     dev::Extensions,
     guard::Guard,
+    method_fallback::{self, MethodFallback, ServiceGuards},
     request::{HttpRequest, HttpRequestPool},
-    rmap::ResourceMap,
+    rmap::{ResourceMap, ServiceInfo},
     service::{
         AppServiceFactory, BoxedHttpService, BoxedHttpServiceFactory, ServiceRequest,
         ServiceResponse,
@@ -41,6 +42,9 @@ where
     pub(crate) default: Option<Rc<BoxedHttpServiceFactory>>,
     pub(crate) factory_ref: Rc<RefCell<Option<AppRoutingFactory>>>,
     pub(crate) external: RefCell<Vec<ResourceDef>>,
+    pub(crate) middleware: Vec<&'static str>,
+    pub(crate) method_fallback: MethodFallback,
+    pub(crate) radix_router: bool,
 }
 
 impl<T, B> ServiceFactory<Request> for AppInit<T, B>
@@ -71,7 +75,12 @@ where
         });
 
         // create App config to pass to child services
-        let mut config = AppService::new(config, default.clone());
+        let mut config = AppService::new(
+            config,
+            default.clone(),
+            self.method_fallback,
+            self.radix_router,
+        );
 
         // register services
         mem::take(&mut *self.services.borrow_mut())
@@ -79,17 +88,24 @@ where
             .for_each(|mut srv| srv.register(&mut config));
 
         let mut rmap = ResourceMap::new(ResourceDef::prefix(""));
+        rmap.set_info(ServiceInfo {
+            middleware: self.middleware.clone(),
+            ..Default::default()
+        });
 
         let (config, services) = config.into_services();
 
         // complete pipeline creation.
         *self.factory_ref.borrow_mut() = Some(AppRoutingFactory {
             default,
+            method_fallback: self.method_fallback,
+            radix_router: self.radix_router,
             services: services
                 .into_iter()
-                .map(|(mut rdef, srv, guards, nested)| {
-                    rmap.add(&mut rdef, nested);
-                    (rdef, srv, RefCell::new(guards))
+                .map(|(mut rdef, srv, guards, nested, info)| {
+                    let methods = info.methods();
+                    rmap.add_service(&mut rdef, nested, info);
+                    (rdef, srv, RefCell::new(guards), methods)
                 })
                 .collect::<Vec<_>>()
                 .into_boxed_slice()
@@ -243,9 +259,12 @@ pub struct AppRoutingFactory {
             ResourceDef,
             BoxedHttpServiceFactory,
             RefCell<Option<Vec<Box<dyn Guard>>>>,
+            Vec<Method>,
         )],
     >,
     default: Rc<BoxedHttpServiceFactory>,
+    method_fallback: MethodFallback,
+    radix_router: bool,
 }
 
 impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
@@ -258,43 +277,61 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
 
     fn new_service(&self, _: ()) -> Self::Future {
         // construct all services factory future with its resource def and guards.
-        let factory_fut = join_all(self.services.iter().map(|(path, factory, guards)| {
-            let path = path.clone();
-            let guards = guards.borrow_mut().take().unwrap_or_default();
-            let factory_fut = factory.new_service(());
-            async move {
-                let service = factory_fut.await?;
-                Ok((path, guards, service))
-            }
-        }));
+        let factory_fut = join_all(
+            self.services
+                .iter()
+                .map(|(path, factory, guards, methods)| {
+                    let path = path.clone();
+                    let guards = ServiceGuards {
+                        guards: guards.borrow_mut().take().unwrap_or_default(),
+                        methods: methods.clone(),
+                    };
+                    let factory_fut = factory.new_service(());
+                    async move {
+                        let service = factory_fut.await?;
+                        Ok((path, guards, service))
+                    }
+                }),
+        );
 
         // construct default service factory future
         let default_fut = self.default.new_service(());
 
+        let method_fallback = self.method_fallback;
+        let radix_router = self.radix_router;
+
         Box::pin(async move {
             let default = default_fut.await?;
 
+            let mut builder = Router::build();
+            builder.radix_tree(radix_router);
+
             // build router from the factory future result.
             let router = factory_fut
                 .await
                 .into_iter()
                 .collect::<Result<Vec<_>, _>>()?
                 .drain(..)
-                .fold(Router::build(), |mut router, (path, guards, service)| {
+                .fold(builder, |mut router, (path, guards, service)| {
                     router.push(path, service, guards);
                     router
                 })
                 .finish();
 
-            Ok(AppRouting { router, default })
+            Ok(AppRouting {
+                router,
+                default,
+                method_fallback,
+            })
         })
     }
 }
 
 /// The Actix Web router default entry point.
 pub struct AppRouting {
-    router: Router<BoxedHttpService, Vec<Box<dyn Guard>>>,
+    router: Router<BoxedHttpService, ServiceGuards>,
     default: BoxedHttpService,
+    method_fallback: MethodFallback,
 }
 
 impl Service<ServiceRequest> for AppRouting {
@@ -304,17 +341,8 @@ impl Service<ServiceRequest> for AppRouting {
 
     actix_service::always_ready!();
 
-    fn call(&self, mut req: ServiceRequest) -> Self::Future {
-        let res = self.router.recognize_fn(&mut req, |req, guards| {
-            let guard_ctx = req.guard_ctx();
-            guards.iter().all(|guard| guard.check(&guard_ctx))
-        });
-
-        if let Some((srv, _info)) = res {
-            srv.call(req)
-        } else {
-            self.default.call(req)
-        }
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        method_fallback::route(&self.router, &self.default, self.method_fallback, req)
     }
 }
 
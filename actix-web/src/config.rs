@@ -6,9 +6,10 @@ use crate::{
     data::Data,
     dev::{Extensions, ResourceDef},
     error::Error,
-    guard::Guard,
+    guard::{self, Guard},
+    method_fallback::MethodFallback,
     resource::Resource,
-    rmap::ResourceMap,
+    rmap::{ResourceMap, ServiceInfo},
     route::Route,
     service::{
         AppServiceFactory, BoxedHttpServiceFactory, HttpServiceFactory, ServiceFactoryWrapper,
@@ -18,26 +19,38 @@ use crate::{
 
 type Guards = Vec<Box<dyn Guard>>;
 
+/// A registered service along with its routing information.
+pub(crate) type RegisteredService = (
+    ResourceDef,
+    BoxedHttpServiceFactory,
+    Option<Guards>,
+    Option<Rc<ResourceMap>>,
+    ServiceInfo,
+);
+
 /// Application configuration
 pub struct AppService {
     config: AppConfig,
     root: bool,
     default: Rc<BoxedHttpServiceFactory>,
-    #[allow(clippy::type_complexity)]
-    services: Vec<(
-        ResourceDef,
-        BoxedHttpServiceFactory,
-        Option<Guards>,
-        Option<Rc<ResourceMap>>,
-    )>,
+    method_fallback: MethodFallback,
+    radix_router: bool,
+    services: Vec<RegisteredService>,
 }
 
 impl AppService {
     /// Crate server settings instance.
-    pub(crate) fn new(config: AppConfig, default: Rc<BoxedHttpServiceFactory>) -> Self {
+    pub(crate) fn new(
+        config: AppConfig,
+        default: Rc<BoxedHttpServiceFactory>,
+        method_fallback: MethodFallback,
+        radix_router: bool,
+    ) -> Self {
         AppService {
             config,
             default,
+            method_fallback,
+            radix_router,
             root: true,
             services: Vec::new(),
         }
@@ -48,18 +61,7 @@ impl AppService {
         self.root
     }
 
-    #[allow(clippy::type_complexity)]
-    pub(crate) fn into_services(
-        self,
-    ) -> (
-        AppConfig,
-        Vec<(
-            ResourceDef,
-            BoxedHttpServiceFactory,
-            Option<Guards>,
-            Option<Rc<ResourceMap>>,
-        )>,
-    ) {
+    pub(crate) fn into_services(self) -> (AppConfig, Vec<RegisteredService>) {
         (self.config, self.services)
     }
 
@@ -69,6 +71,8 @@ impl AppService {
         AppService {
             config: self.config.clone(),
             default: self.default.clone(),
+            method_fallback: self.method_fallback,
+            radix_router: self.radix_router,
             services: Vec::new(),
             root: false,
         }
@@ -84,6 +88,16 @@ impl AppService {
         self.default.clone()
     }
 
+    /// Returns settings for requests whose method is not handled by a resource.
+    pub(crate) fn method_fallback(&self) -> MethodFallback {
+        self.method_fallback
+    }
+
+    /// Returns true if routers should index their services using a radix tree.
+    pub(crate) fn radix_router(&self) -> bool {
+        self.radix_router
+    }
+
     /// Register HTTP service.
     pub fn register_service<F, S>(
         &mut self,
@@ -101,8 +115,48 @@ impl AppService {
                 InitError = (),
             > + 'static,
     {
-        self.services
-            .push((rdef, boxed::factory(factory.into_factory()), guards, nested));
+        let info = ServiceInfo {
+            guards: guard::guard_details(guards.iter().flatten()),
+            ..Default::default()
+        };
+
+        self.services.push((
+            rdef,
+            boxed::factory(factory.into_factory()),
+            guards,
+            nested,
+            info,
+        ));
+    }
+
+    /// Register HTTP service along with introspection information about it.
+    ///
+    /// Guard descriptions in `info` are filled in from `guards`.
+    pub(crate) fn register_service_with_info<F, S>(
+        &mut self,
+        rdef: ResourceDef,
+        guards: Option<Vec<Box<dyn Guard>>>,
+        factory: F,
+        mut info: ServiceInfo,
+    ) where
+        F: IntoServiceFactory<S, ServiceRequest>,
+        S: ServiceFactory<
+                ServiceRequest,
+                Response = ServiceResponse,
+                Error = Error,
+                Config = (),
+                InitError = (),
+            > + 'static,
+    {
+        info.guards = guard::guard_details(guards.iter().flatten());
+
+        self.services.push((
+            rdef,
+            boxed::factory(factory.into_factory()),
+            guards,
+            None,
+            info,
+        ));
     }
 }
 
@@ -17,12 +17,14 @@ pub use actix_service::{
     always_ready, fn_factory, fn_service, forward_ready, Service, ServiceFactory, Transform,
 };
 
+#[doc(hidden)]
+pub use crate::extract::FromRequestMapFuture;
 #[doc(hidden)]
 pub use crate::handler::Handler;
 pub use crate::{
     config::{AppConfig, AppService},
     info::{ConnectionInfo, PeerAddr},
-    rmap::ResourceMap,
+    rmap::{RegisteredRoute, ResourceMap},
     service::{HttpServiceFactory, ServiceRequest, ServiceResponse, WebService},
     types::{JsonBody, Readlines, UrlEncoded},
 };
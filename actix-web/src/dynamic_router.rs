@@ -0,0 +1,516 @@
+//! See [`DynamicRouter`] for service documentation.
+
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::Rc,
+    sync::{Arc, RwLock},
+};
+
+use actix_router::{ResourceDef, Router};
+use actix_service::{Service, ServiceFactory};
+use futures_core::future::LocalBoxFuture;
+use futures_util::{
+    future::{join_all, Shared},
+    FutureExt as _,
+};
+
+use crate::{
+    dev::{AppService, HttpServiceFactory},
+    error::ErrorInternalServerError,
+    guard::{Guard, GuardContext, GuardDetail},
+    route::RouteService,
+    service::{BoxedHttpService, BoxedHttpServiceFactory, ServiceRequest, ServiceResponse},
+    Error, HttpMessage as _, Route,
+};
+
+type RouteFactory = Arc<dyn Fn() -> Route + Send + Sync>;
+
+type Guards = Vec<Box<dyn Guard>>;
+
+/// A table of routes served by a [`DynamicRouter`].
+///
+/// Routes are given as functions that construct a [`Route`], since each worker thread builds its
+/// own services from the table. Patterns use the same syntax as [resources](crate::Resource) and
+/// are matched in the order they were added.
+///
+/// # Examples
+/// ```
+/// use actix_web::web;
+///
+/// let table = web::RoutingTable::new()
+///     .route("/hello/{name}", || {
+///         web::get().to(|name: web::Path<String>| async move { format!("Hello, {name}!") })
+///     })
+///     .route("/bye", || web::get().to(|| async { "Bye!" }));
+/// ```
+#[derive(Clone, Default)]
+pub struct RoutingTable {
+    routes: Vec<(ResourceDef, RouteFactory)>,
+}
+
+impl RoutingTable {
+    /// Constructs an empty routing table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a route for the given path pattern.
+    ///
+    /// Like resources, a pattern can have multiple routes with different guards; the first route
+    /// whose guards pass handles the request.
+    pub fn route<F>(mut self, path: &str, route: F) -> Self
+    where
+        F: Fn() -> Route + Send + Sync + 'static,
+    {
+        self.routes.push((ResourceDef::new(path), Arc::new(route)));
+        self
+    }
+
+    /// Removes all routes for the given path pattern.
+    pub fn remove(mut self, path: &str) -> Self {
+        self.routes
+            .retain(|(rdef, _)| rdef.pattern_iter().ne([path]));
+        self
+    }
+
+    /// Returns the path patterns of the table's routes, in order.
+    pub fn patterns(&self) -> impl Iterator<Item = &str> {
+        self.routes.iter().filter_map(|(rdef, _)| rdef.pattern())
+    }
+
+    /// Returns true if `path` matches the pattern of any route in the table.
+    fn is_match(&self, path: &str) -> bool {
+        self.routes.iter().any(|(rdef, _)| rdef.is_match(path))
+    }
+}
+
+impl fmt::Debug for RoutingTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RoutingTable")
+            .field("patterns", &self.patterns().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A service routing requests using a table that can be swapped at runtime.
+///
+/// Unlike other services, whose routes are fixed once each worker has built its `App`, a
+/// `DynamicRouter` routes requests using its current [`RoutingTable`]. Clones of a router share the
+/// same table, so it can be replaced from anywhere, like an admin endpoint or a task watching a
+/// configuration file, and the change takes effect in all workers. Requests already being handled
+/// keep using the table they were routed with.
+///
+/// A dynamic router is mounted at the path of the scope it is registered in. Requests whose path
+/// does not match any pattern in the current table are passed on to the following services, as if
+/// the router was not registered. Requests matching a pattern but none of its routes' guards are
+/// handled by the default service.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App, HttpResponse};
+///
+/// let plugins = web::DynamicRouter::new(
+///     web::RoutingTable::new().route("/hello", || web::get().to(|| async { "Hello!" })),
+/// );
+///
+/// let admin = plugins.clone();
+///
+/// App::new()
+///     .service(web::scope("/plugins").service(plugins.clone()))
+///     .route(
+///         "/admin/enable-bye",
+///         web::post().to(move || {
+///             admin.update(|table| table.route("/bye", || web::get().to(|| async { "Bye!" })));
+///             async { HttpResponse::NoContent() }
+///         }),
+///     );
+/// ```
+#[derive(Clone, Default)]
+pub struct DynamicRouter {
+    table: Arc<RwLock<Arc<RoutingTable>>>,
+}
+
+impl DynamicRouter {
+    /// Constructs a router with the given initial routing table.
+    pub fn new(table: RoutingTable) -> Self {
+        Self {
+            table: Arc::new(RwLock::new(Arc::new(table))),
+        }
+    }
+
+    /// Returns a snapshot of the current routing table.
+    pub fn table(&self) -> Arc<RoutingTable> {
+        let table = self.table.read().unwrap_or_else(|err| err.into_inner());
+        Arc::clone(&table)
+    }
+
+    /// Replaces the routing table.
+    pub fn set(&self, table: RoutingTable) {
+        *self.table.write().unwrap_or_else(|err| err.into_inner()) = Arc::new(table);
+    }
+
+    /// Replaces the routing table with one derived from the current table.
+    ///
+    /// Concurrent updates are applied one after another, so none of them are lost.
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(RoutingTable) -> RoutingTable,
+    {
+        let mut table = self.table.write().unwrap_or_else(|err| err.into_inner());
+        *table = Arc::new(f(RoutingTable::clone(&table)));
+    }
+}
+
+impl fmt::Debug for DynamicRouter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DynamicRouter")
+            .field("table", &self.table())
+            .finish()
+    }
+}
+
+impl HttpServiceFactory for DynamicRouter {
+    fn register(self, config: &mut AppService) {
+        let guard = TableGuard {
+            router: self.clone(),
+        };
+
+        let factory = DynamicRouterFactory {
+            router: self,
+            default: config.default_service(),
+        };
+
+        config.register_service(
+            ResourceDef::root_prefix(""),
+            Some(vec![Box::new(guard)]),
+            factory,
+            None,
+        )
+    }
+}
+
+/// Snapshot of the table a request was matched against by a router's guard.
+struct MatchedTable {
+    router: Arc<RwLock<Arc<RoutingTable>>>,
+    table: Arc<RoutingTable>,
+}
+
+/// Guard that passes requests whose remaining path matches a pattern in the current table.
+///
+/// The table that was matched against is stored in the request's extensions, so that the request
+/// is routed using the same table even if it is replaced in the meantime.
+struct TableGuard {
+    router: DynamicRouter,
+}
+
+impl Guard for TableGuard {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        let table = self.router.table();
+        let path = ctx.req.match_info().unprocessed();
+
+        if !table.is_match(path) {
+            return false;
+        }
+
+        ctx.req_data_mut().insert(MatchedTable {
+            router: Arc::clone(&self.router.table),
+            table,
+        });
+
+        true
+    }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        Some(vec![GuardDetail::Generic("dynamic_router".to_owned())])
+    }
+}
+
+struct DynamicRouterFactory {
+    router: DynamicRouter,
+    default: Rc<BoxedHttpServiceFactory>,
+}
+
+impl ServiceFactory<ServiceRequest> for DynamicRouterFactory {
+    type Response = ServiceResponse;
+    type Error = Error;
+    type Config = ();
+    type Service = DynamicRouterService;
+    type InitError = ();
+    type Future = LocalBoxFuture<'static, Result<Self::Service, Self::InitError>>;
+
+    fn new_service(&self, _: ()) -> Self::Future {
+        let router = self.router.clone();
+        let default_fut = self.default.new_service(());
+
+        Box::pin(async move {
+            Ok(DynamicRouterService {
+                router,
+                default: Rc::new(default_fut.await?),
+                cache: RefCell::new(None),
+            })
+        })
+    }
+}
+
+/// Routes built by a worker from a routing table.
+type WorkerRouter = Router<RouteService, Guards>;
+
+/// Build of a worker's routes from a routing table, shared by all requests using the table.
+type WorkerRouterBuild = Shared<LocalBoxFuture<'static, Result<Rc<WorkerRouter>, ()>>>;
+
+struct DynamicRouterService {
+    router: DynamicRouter,
+    default: Rc<BoxedHttpService>,
+
+    /// Routes built, or being built, from the most recently used table.
+    cache: RefCell<Option<(Arc<RoutingTable>, WorkerRouterBuild)>>,
+}
+
+impl DynamicRouterService {
+    /// Returns the table that the request was matched against by the router's guard, falling back
+    /// to the current table.
+    fn table(&self, req: &ServiceRequest) -> Arc<RoutingTable> {
+        match req.extensions_mut().remove::<MatchedTable>() {
+            Some(matched) if Arc::ptr_eq(&matched.router, &self.router.table) => matched.table,
+            _ => self.router.table(),
+        }
+    }
+
+    /// Returns the build of routes from `table`, starting it if the table has not been used yet.
+    ///
+    /// Routes are built once per table by each worker, even when many requests arrive before the
+    /// build is complete.
+    fn routes(&self, table: Arc<RoutingTable>) -> WorkerRouterBuild {
+        let mut cache = self.cache.borrow_mut();
+
+        if let Some((cached, build)) = &*cache {
+            if Arc::ptr_eq(cached, &table) {
+                return build.clone();
+            }
+        }
+
+        let build = {
+            let table = Arc::clone(&table);
+
+            async move {
+                build_router(&table).await.map(Rc::new).map_err(|()| {
+                    log::error!("Can not construct dynamic routes");
+                })
+            }
+            .boxed_local()
+            .shared()
+        };
+
+        *cache = Some((table, build.clone()));
+        build
+    }
+}
+
+/// Builds routes from a table.
+async fn build_router(table: &RoutingTable) -> Result<WorkerRouter, ()> {
+    let routes = join_all(table.routes.iter().map(|(rdef, route_factory)| {
+        let mut route = route_factory();
+        let guards = route.take_guards();
+        let fut = route.new_service(());
+
+        async move { Ok::<_, ()>((rdef.clone(), fut.await?, guards)) }
+    }))
+    .await;
+
+    let mut builder = Router::build();
+
+    for route in routes {
+        let (rdef, service, guards) = route?;
+        builder.push(rdef, service, guards);
+    }
+
+    Ok(builder.finish())
+}
+
+impl Service<ServiceRequest> for DynamicRouterService {
+    type Response = ServiceResponse;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::always_ready!();
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let table = self.table(&req);
+        let build = self.routes(table);
+
+        if let Some(Ok(router)) = build.peek() {
+            return route(router, &self.default, req);
+        }
+
+        let default = Rc::clone(&self.default);
+
+        Box::pin(async move {
+            match build.await {
+                Ok(router) => route(&router, &default, req).await,
+                Err(()) => Err(ErrorInternalServerError(
+                    "dynamic routes could not be constructed",
+                )),
+            }
+        })
+    }
+}
+
+/// Routes a request using routes built from a table, falling back to the default service.
+fn route(
+    router: &WorkerRouter,
+    default: &BoxedHttpService,
+    mut req: ServiceRequest,
+) -> LocalBoxFuture<'static, Result<ServiceResponse, Error>> {
+    let res = router.recognize_fn(&mut req, |req, guards| {
+        let guard_ctx = req.guard_ctx();
+        guards.iter().all(|guard| guard.check(&guard_ctx))
+    });
+
+    match res {
+        Some((service, _)) => service.call(req),
+        None => default.call(req),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::FutureExt as _;
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+    use crate::{
+        http::{Method, StatusCode},
+        test::{call_service, init_service, read_body, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    assert_impl_all!(DynamicRouter: Send, Sync, Clone);
+
+    #[actix_rt::test]
+    async fn swap_table() {
+        let router = DynamicRouter::new(RoutingTable::new().route("/hello/{name}", || {
+            web::get().to(|name: web::Path<String>| async move { format!("hello {name}") })
+        }));
+
+        let srv = init_service(
+            App::new().service(
+                web::scope("/plugins")
+                    .service(router.clone())
+                    .service(web::resource("/hello/{name}").to(HttpResponse::Gone))
+                    .default_service(web::to(HttpResponse::Gone)),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/plugins/hello/bob").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(read_body(res).await, "hello bob");
+
+        // unmatched paths fall through to following services
+        let req = TestRequest::with_uri("/plugins/bye").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::GONE);
+
+        router.update(|table| {
+            table
+                .remove("/hello/{name}")
+                .route("/bye", || web::get().to(|| async { "bye" }))
+        });
+        assert_eq!(router.table().patterns().collect::<Vec<_>>(), ["/bye"]);
+
+        let req = TestRequest::with_uri("/plugins/bye").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(read_body(res).await, "bye");
+
+        let req = TestRequest::with_uri("/plugins/hello/bob").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::GONE);
+
+        // matched path but no matching route guards
+        let req = TestRequest::with_uri("/plugins/bye")
+            .method(Method::POST)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        router.set(RoutingTable::new());
+
+        let req = TestRequest::with_uri("/plugins/bye").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::GONE);
+    }
+
+    #[actix_rt::test]
+    async fn in_flight_requests_keep_table() {
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let rx = Arc::new(std::sync::Mutex::new(Some(rx)));
+
+        let router = DynamicRouter::new(RoutingTable::new().route("/slow", move || {
+            let rx = Arc::clone(&rx);
+
+            web::get().to(move || {
+                let rx = rx.lock().unwrap().take();
+
+                async move {
+                    if let Some(rx) = rx {
+                        rx.await.unwrap();
+                    }
+
+                    "old"
+                }
+            })
+        }));
+
+        let srv = init_service(App::new().service(router.clone())).await;
+
+        let req = TestRequest::with_uri("/slow").to_request();
+        let mut in_flight = Box::pin(call_service(&srv, req));
+        assert!((&mut in_flight).now_or_never().is_none());
+
+        router.set(RoutingTable::new().route("/slow", || web::get().to(|| async { "new" })));
+
+        let req = TestRequest::with_uri("/slow").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(read_body(res).await, "new");
+
+        tx.send(()).unwrap();
+        let res = in_flight.await;
+        assert_eq!(read_body(res).await, "old");
+    }
+
+    #[actix_rt::test]
+    async fn routes_built_once_per_table() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let builds = Arc::new(AtomicUsize::new(0));
+
+        let table = {
+            let builds = Arc::clone(&builds);
+
+            RoutingTable::new().route("/count", move || {
+                builds.fetch_add(1, Ordering::SeqCst);
+                web::get().to(|| async { "counted" })
+            })
+        };
+
+        let router = DynamicRouter::new(table.clone());
+        let srv = init_service(App::new().service(router.clone())).await;
+
+        let reqs = (0..3).map(|_| call_service(&srv, TestRequest::with_uri("/count").to_request()));
+        for res in join_all(reqs).await {
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+
+        router.set(table);
+
+        let reqs = (0..3).map(|_| call_service(&srv, TestRequest::with_uri("/count").to_request()));
+        for res in join_all(reqs).await {
+            assert_eq!(read_body(res).await, "counted");
+        }
+        assert_eq!(builds.load(Ordering::SeqCst), 2);
+    }
+}
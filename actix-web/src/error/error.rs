@@ -1,4 +1,6 @@
-use std::{error::Error as StdError, fmt};
+#[cfg(all(feature = "debug-errors", debug_assertions))]
+use std::cell::Cell;
+use std::{any::TypeId, error::Error as StdError, fmt};
 
 use actix_http::{body::BoxBody, Response};
 
@@ -14,6 +16,10 @@ use crate::{HttpResponse, ResponseError};
 /// you can always get a `ResponseError` reference from it.
 pub struct Error {
     cause: Box<dyn ResponseError>,
+    /// Renders the error response in place of the cause, if set.
+    render: Option<fn(&(dyn ResponseError + 'static)) -> HttpResponse>,
+    #[cfg(all(feature = "debug-errors", debug_assertions))]
+    backtrace: std::backtrace::Backtrace,
 }
 
 impl Error {
@@ -27,9 +33,48 @@ impl Error {
         <dyn ResponseError>::downcast_ref(self.cause.as_ref())
     }
 
+    /// Attempts to downcast into the underlying error type, returning it by value.
+    ///
+    /// Returns `Err(self)` if the underlying error is not of type `T`.
+    pub fn downcast<T: ResponseError + 'static>(self) -> Result<T, Self> {
+        if self.cause_type_id() != TypeId::of::<T>() {
+            return Err(self);
+        }
+
+        match self.cause.downcast::<T>() {
+            Ok(err) => Ok(*err),
+            Err(_) => unreachable!("type ID has been checked"),
+        }
+    }
+
+    /// Returns the backtrace captured when this error was created.
+    ///
+    /// Backtraces are only captured for errors created inside [`capture_backtraces`].
+    #[cfg(all(feature = "debug-errors", debug_assertions))]
+    pub(crate) fn backtrace(&self) -> &std::backtrace::Backtrace {
+        &self.backtrace
+    }
+
+    /// Returns the type ID of the underlying error type.
+    pub(crate) fn cause_type_id(&self) -> TypeId {
+        self.cause.concrete_type_id()
+    }
+
+    /// Sets the function used to render the error response, keeping the underlying error as-is.
+    pub(crate) fn with_render(
+        mut self,
+        render: fn(&(dyn ResponseError + 'static)) -> HttpResponse,
+    ) -> Self {
+        self.render = Some(render);
+        self
+    }
+
     /// Shortcut for creating an `HttpResponse`.
     pub fn error_response(&self) -> HttpResponse {
-        self.cause.error_response()
+        match self.render {
+            Some(render) => render(self.cause.as_ref()),
+            None => self.cause.error_response(),
+        }
     }
 }
 
@@ -56,10 +101,46 @@ impl<T: ResponseError + 'static> From<T> for Error {
     fn from(err: T) -> Error {
         Error {
             cause: Box::new(err),
+            render: None,
+            #[cfg(all(feature = "debug-errors", debug_assertions))]
+            backtrace: if CAPTURE_BACKTRACES.with(Cell::get) {
+                std::backtrace::Backtrace::force_capture()
+            } else {
+                std::backtrace::Backtrace::disabled()
+            },
         }
     }
 }
 
+#[cfg(all(feature = "debug-errors", debug_assertions))]
+thread_local! {
+    static CAPTURE_BACKTRACES: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Calls `f`, capturing backtraces for all errors created on the current thread during the call.
+///
+/// Used by the `DebugErrors` middleware so that other apps do not pay for capturing backtraces.
+#[cfg(feature = "debug-errors")]
+pub(crate) fn capture_backtraces<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(debug_assertions)]
+    {
+        /// Restores the previous setting, even if `f` panics.
+        struct Reset(bool);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                CAPTURE_BACKTRACES.with(|capture| capture.set(self.0));
+            }
+        }
+
+        let _reset = Reset(CAPTURE_BACKTRACES.with(|capture| capture.replace(true)));
+        f()
+    }
+
+    #[cfg(not(debug_assertions))]
+    f()
+}
+
 impl From<Error> for Response<BoxBody> {
     fn from(err: Error) -> Response<BoxBody> {
         err.error_response().into()
@@ -70,6 +70,28 @@ macro_rules! downcast_dyn {
                     None
                 }
             }
+
+            /// Downcasts a boxed generic body to a specific type.
+            #[allow(dead_code)]
+            pub fn downcast<T: $name + 'static>(self: Box<Self>) -> Result<Box<T>, Box<Self>> {
+                if self.__private_get_type_id__(PrivateHelper(())).0 == std::any::TypeId::of::<T>()
+                {
+                    // SAFETY: external crates cannot override the default
+                    // implementation of `__private_get_type_id__`, since
+                    // it requires returning a private type. We can therefore
+                    // rely on the returned `TypeId`, which ensures that this
+                    // case is correct.
+                    unsafe { Ok(Box::from_raw(Box::into_raw(self) as *mut T)) }
+                } else {
+                    Err(self)
+                }
+            }
+
+            /// Returns the type ID of the concrete type behind this trait object.
+            #[allow(dead_code)]
+            pub(crate) fn concrete_type_id(&self) -> std::any::TypeId {
+                self.__private_get_type_id__(PrivateHelper(())).0
+            }
         }
     };
 }
@@ -102,5 +124,13 @@ mod tests {
         assert_eq!(body, "hello cast!");
         let not_body = resp_body.downcast_ref::<()>();
         assert!(not_body.is_none());
+
+        let boxed: Box<dyn MB> = Box::new(String::from("hello box"));
+        assert_eq!(boxed.concrete_type_id(), std::any::TypeId::of::<String>());
+        let boxed = match boxed.downcast::<()>() {
+            Ok(_) => panic!("downcast to wrong type should fail"),
+            Err(boxed) => boxed,
+        };
+        assert_eq!(*boxed.downcast::<String>().ok().unwrap(), "hello box");
     }
 }
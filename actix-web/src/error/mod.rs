@@ -5,22 +5,40 @@
 // expanded manually.
 //
 // See <https://github.com/rust-lang/rust/issues/83375>
+use std::fmt;
+
 pub use actix_http::error::{ContentTypeError, DispatchError, HttpError, ParseError, PayloadError};
 use derive_more::{Display, Error, From};
 use serde_json::error::Error as JsonError;
 use serde_urlencoded::{de::Error as FormDeError, ser::Error as FormError};
 use url::ParseError as UrlParseError;
 
-use crate::http::StatusCode;
+use crate::{
+    body::BoxBody,
+    http::{header, StatusCode},
+    HttpResponse,
+};
 
 #[allow(clippy::module_inception)]
 mod error;
 mod internal;
 mod macros;
+mod problem;
 mod response_error;
 
-pub(crate) use self::macros::{downcast_dyn, downcast_get_type_id};
-pub use self::{error::Error, internal::*, response_error::ResponseError};
+#[cfg(feature = "debug-errors")]
+pub(crate) use self::error::capture_backtraces;
+pub use self::{
+    error::Error,
+    internal::*,
+    problem::{ErrorFormat, Problem, ProblemBuilder},
+    response_error::ResponseError,
+};
+pub(crate) use self::{
+    macros::{downcast_dyn, downcast_get_type_id},
+    problem::extractor_error,
+    response_error::plain_text_response,
+};
 
 /// A convenience [`Result`](std::result::Result) for Actix Web operations.
 ///
@@ -93,6 +111,11 @@ pub enum UrlencodedError {
     /// Payload error.
     #[display(fmt = "Error that occur during reading payload: {}.", _0)]
     Payload(PayloadError),
+
+    /// Validation error.
+    #[display(fmt = "Validation error: {}.", _0)]
+    #[from(ignore)]
+    Validate(ValidationErrors),
 }
 
 impl ResponseError for UrlencodedError {
@@ -101,9 +124,17 @@ impl ResponseError for UrlencodedError {
             Self::Overflow { .. } => StatusCode::PAYLOAD_TOO_LARGE,
             Self::UnknownLength => StatusCode::LENGTH_REQUIRED,
             Self::Payload(err) => err.status_code(),
+            Self::Validate(err) => err.status_code(),
             _ => StatusCode::BAD_REQUEST,
         }
     }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Self::Validate(err) => err.error_response(),
+            _ => plain_text_response(self.status_code(), self),
+        }
+    }
 }
 
 /// A set of errors that can occur during parsing json payloads
@@ -137,6 +168,10 @@ pub enum JsonPayloadError {
     /// Payload error
     #[display(fmt = "Error that occur during reading payload: {}", _0)]
     Payload(PayloadError),
+
+    /// Validation error
+    #[display(fmt = "Json validation error: {}", _0)]
+    Validate(ValidationErrors),
 }
 
 impl From<PayloadError> for JsonPayloadError {
@@ -155,9 +190,17 @@ impl ResponseError for JsonPayloadError {
             Self::Overflow { limit: _ } => StatusCode::PAYLOAD_TOO_LARGE,
             Self::Serialize(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Payload(err) => err.status_code(),
+            Self::Validate(err) => err.status_code(),
             _ => StatusCode::BAD_REQUEST,
         }
     }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Self::Validate(err) => err.error_response(),
+            _ => plain_text_response(self.status_code(), self),
+        }
+    }
 }
 
 /// A set of errors that can occur during parsing request paths
@@ -167,12 +210,26 @@ pub enum PathError {
     /// Deserialize error
     #[display(fmt = "Path deserialize error: {}", _0)]
     Deserialize(serde::de::value::Error),
+
+    /// Validation error
+    #[display(fmt = "Path validation error: {}", _0)]
+    Validate(ValidationErrors),
 }
 
 /// Return `BadRequest` for `PathError`
 impl ResponseError for PathError {
     fn status_code(&self) -> StatusCode {
-        StatusCode::BAD_REQUEST
+        match self {
+            Self::Validate(err) => err.status_code(),
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Self::Validate(err) => err.error_response(),
+            _ => plain_text_response(self.status_code(), self),
+        }
     }
 }
 
@@ -183,11 +240,159 @@ pub enum QueryPayloadError {
     /// Query deserialize error.
     #[display(fmt = "Query deserialize error: {}", _0)]
     Deserialize(serde::de::value::Error),
+
+    /// Query validation error.
+    #[display(fmt = "Query validation error: {}", _0)]
+    #[from(ignore)]
+    Validate(ValidationErrors),
 }
 
 impl ResponseError for QueryPayloadError {
     fn status_code(&self) -> StatusCode {
-        StatusCode::BAD_REQUEST
+        match self {
+            Self::Validate(err) => err.status_code(),
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        match self {
+            Self::Validate(err) => err.error_response(),
+            _ => plain_text_response(self.status_code(), self),
+        }
+    }
+}
+
+/// A single field-level failure reported by a [`Validate`](crate::web::Validate) implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    field: String,
+    message: String,
+}
+
+impl FieldError {
+    /// Constructs a new field error from a field path and a human-readable message.
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Returns path of the field that failed validation (e.g., `address.zip` or `tags[2]`).
+    ///
+    /// An empty path refers to the validated value as a whole.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns the validation failure message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.field.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{}: {}", self.field, self.message)
+        }
+    }
+}
+
+/// A set of field-level errors produced when validating an extracted value.
+///
+/// Responds with a 422 Unprocessable Entity status code and a JSON body listing each failed field.
+///
+/// ```json
+/// { "errors": [{ "field": "address.zip", "message": "must be 5 digits" }] }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    errors: Vec<FieldError>,
+}
+
+impl ValidationErrors {
+    /// Constructs an empty set of validation errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an error for the field at `field`.
+    pub fn add(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(FieldError::new(field, message));
+    }
+
+    /// Adds all errors from `other`, prefixing their field paths with `field`.
+    ///
+    /// Useful when validating nested structures; a `zip` error merged under `address` will be
+    /// reported as `address.zip`.
+    pub fn merge(&mut self, field: &str, other: ValidationErrors) {
+        self.errors.extend(other.errors.into_iter().map(|err| {
+            let path = match (field.is_empty(), err.field.is_empty()) {
+                (true, _) => err.field,
+                (false, true) => field.to_owned(),
+                (false, false) if err.field.starts_with('[') => format!("{}{}", field, err.field),
+                (false, false) => format!("{}.{}", field, err.field),
+            };
+
+            FieldError::new(path, err.message)
+        }));
+    }
+
+    /// Returns true if no errors have been added.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns iterator over the contained field errors.
+    pub fn iter(&self) -> impl Iterator<Item = &FieldError> {
+        self.errors.iter()
+    }
+
+    /// Returns `Ok(())` if there are no errors, otherwise returns `Err(self)`.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, err) in self.errors.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("; ")?;
+            }
+
+            fmt::Display::fmt(err, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+impl ResponseError for ValidationErrors {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::UNPROCESSABLE_ENTITY
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        let errors = self
+            .errors
+            .iter()
+            .map(|err| serde_json::json!({ "field": err.field, "message": err.message }))
+            .collect::<Vec<_>>();
+
+        HttpResponse::build(self.status_code())
+            .insert_header((header::CONTENT_TYPE, mime::APPLICATION_JSON))
+            .body(serde_json::json!({ "errors": errors }).to_string())
     }
 }
 
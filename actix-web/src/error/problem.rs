@@ -0,0 +1,413 @@
+//! RFC 9457 problem details.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{
+    body::BoxBody,
+    error::{
+        Error, JsonPayloadError, PathError, QueryPayloadError, UrlencodedError, ValidationErrors,
+    },
+    http::{
+        header::{self, HeaderValue},
+        StatusCode,
+    },
+    HttpRequest, HttpResponse, ResponseError,
+};
+
+/// Names of the standard problem detail members, which can not be used for extension members.
+const STANDARD_MEMBERS: [&str; 5] = ["type", "title", "status", "detail", "instance"];
+
+/// Media type of problem detail documents.
+const APPLICATION_PROBLEM_JSON: HeaderValue = HeaderValue::from_static("application/problem+json");
+
+/// An RFC 9457 "problem details" error.
+///
+/// Renders an `application/problem+json` document with the standard `type`, `title`, `status`,
+/// `detail` and `instance` members plus any extension members that have been added.
+///
+/// When no title is set, the canonical reason phrase of the status code is used.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::Problem, get, http::StatusCode, Result};
+///
+/// #[get("/accounts/{id}")]
+/// async fn account() -> Result<&'static str> {
+///     Err(Problem::build(StatusCode::FORBIDDEN)
+///         .type_uri("https://example.com/probs/out-of-credit")
+///         .title("You do not have enough credit.")
+///         .detail("Your current balance is 30, but that costs 50.")
+///         .instance("/account/12345/msgs/abc")
+///         .extension("balance", 30)
+///         .finish()
+///         .into())
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    type_uri: Option<String>,
+    title: Option<String>,
+    status: StatusCode,
+    detail: Option<String>,
+    instance: Option<String>,
+    extensions: Map<String, Value>,
+}
+
+impl Problem {
+    /// Constructs a problem with given status code and no other members set.
+    pub fn new(status: StatusCode) -> Self {
+        Self {
+            type_uri: None,
+            title: None,
+            status,
+            detail: None,
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Creates a problem builder with given status code.
+    pub fn build(status: StatusCode) -> ProblemBuilder {
+        ProblemBuilder {
+            problem: Self::new(status),
+        }
+    }
+
+    /// Constructs a problem from any response error.
+    ///
+    /// The status code is taken from [`ResponseError::status_code`] and the detail member is set
+    /// to the error's `Display` output.
+    pub fn from_response_error(err: &(dyn ResponseError + 'static)) -> Self {
+        let mut problem = Self::build(err.status_code()).detail(err.to_string());
+
+        if let Some(errors) = validation_errors(err) {
+            let errors = errors
+                .iter()
+                .map(|err| serde_json::json!({ "field": err.field(), "message": err.message() }))
+                .collect::<Vec<_>>();
+
+            problem = problem.extension("errors", errors);
+        }
+
+        problem.finish()
+    }
+
+    /// Returns the problem type URI, if set.
+    ///
+    /// A missing type is equivalent to `about:blank`.
+    pub fn type_uri(&self) -> Option<&str> {
+        self.type_uri.as_deref()
+    }
+
+    /// Returns the problem title, if set.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the status code.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the problem detail, if set.
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+
+    /// Returns the problem instance URI, if set.
+    pub fn instance(&self) -> Option<&str> {
+        self.instance.as_deref()
+    }
+
+    /// Returns the extension members.
+    pub fn extensions(&self) -> &Map<String, Value> {
+        &self.extensions
+    }
+
+    /// Returns the problem document as a JSON object.
+    pub fn to_json(&self) -> Value {
+        let mut doc = self.extensions.clone();
+
+        if let Some(type_uri) = &self.type_uri {
+            doc.insert("type".to_owned(), type_uri.clone().into());
+        }
+
+        let title = self
+            .title
+            .as_deref()
+            .or_else(|| self.status.canonical_reason())
+            .unwrap_or_default();
+        doc.insert("title".to_owned(), title.into());
+        doc.insert("status".to_owned(), self.status.as_u16().into());
+
+        if let Some(detail) = &self.detail {
+            doc.insert("detail".to_owned(), detail.clone().into());
+        }
+
+        if let Some(instance) = &self.instance {
+            doc.insert("instance".to_owned(), instance.clone().into());
+        }
+
+        Value::Object(doc)
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = self
+            .title
+            .as_deref()
+            .or_else(|| self.status.canonical_reason())
+            .unwrap_or("Unknown Problem");
+
+        match &self.detail {
+            Some(detail) => write!(f, "{}: {}", title, detail),
+            None => f.write_str(title),
+        }
+    }
+}
+
+impl std::error::Error for Problem {}
+
+impl ResponseError for Problem {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        HttpResponse::build(self.status)
+            .insert_header((header::CONTENT_TYPE, APPLICATION_PROBLEM_JSON))
+            .body(self.to_json().to_string())
+    }
+}
+
+/// A builder for [`Problem`] errors.
+///
+/// See [`Problem::build`].
+#[derive(Debug)]
+pub struct ProblemBuilder {
+    problem: Problem,
+}
+
+impl ProblemBuilder {
+    /// Sets the problem type URI.
+    pub fn type_uri(mut self, type_uri: impl Into<String>) -> Self {
+        self.problem.type_uri = Some(type_uri.into());
+        self
+    }
+
+    /// Sets the short, human-readable summary of the problem type.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.problem.title = Some(title.into());
+        self
+    }
+
+    /// Sets the human-readable explanation specific to this occurrence of the problem.
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.problem.detail = Some(detail.into());
+        self
+    }
+
+    /// Sets the URI reference that identifies this occurrence of the problem.
+    pub fn instance(mut self, instance: impl Into<String>) -> Self {
+        self.problem.instance = Some(instance.into());
+        self
+    }
+
+    /// Adds an extension member.
+    ///
+    /// Extension members named the same as a standard member (`type`, `title`, `status`, `detail`
+    /// or `instance`) are ignored; use the corresponding builder methods instead. Values that fail
+    /// to serialize are stored as `null`.
+    pub fn extension(mut self, name: impl Into<String>, value: impl Serialize) -> Self {
+        let name = name.into();
+
+        if STANDARD_MEMBERS.contains(&name.as_str()) {
+            return self;
+        }
+
+        let value = serde_json::to_value(value).unwrap_or(Value::Null);
+        self.problem.extensions.insert(name, value);
+        self
+    }
+
+    /// Finishes building and returns the problem.
+    pub fn finish(self) -> Problem {
+        self.problem
+    }
+}
+
+impl From<ProblemBuilder> for Problem {
+    fn from(builder: ProblemBuilder) -> Self {
+        builder.finish()
+    }
+}
+
+/// Format used for the default responses of built-in extractor errors.
+///
+/// Applies to [`JsonPayloadError`], [`UrlencodedError`], [`QueryPayloadError`] and [`PathError`]
+/// when no custom error handler is set on the extractor's config. Register using `app_data` on an
+/// `App`, `Scope` or `Resource`.
+///
+/// When set to [`Problem`](Self::Problem), responses to extraction failures are rendered as
+/// [`Problem`] documents. The returned errors still wrap the original error type, so they can be
+/// downcast and matched by type-based error handlers as usual.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::ErrorFormat, App};
+///
+/// let app = App::new().app_data(ErrorFormat::Problem);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorFormat {
+    /// Plain text body containing the error's `Display` output.
+    #[default]
+    PlainText,
+
+    /// RFC 9457 `application/problem+json` documents.
+    Problem,
+}
+
+/// Converts an extractor error into an `Error` according to the configured [`ErrorFormat`].
+pub(crate) fn extractor_error<E>(err: E, req: &HttpRequest) -> Error
+where
+    E: ResponseError + 'static,
+{
+    match req.app_data::<ErrorFormat>() {
+        Some(ErrorFormat::Problem) => {
+            Error::from(err).with_render(|err| Problem::from_response_error(err).error_response())
+        }
+        _ => err.into(),
+    }
+}
+
+fn validation_errors<'a>(err: &'a (dyn ResponseError + 'static)) -> Option<&'a ValidationErrors> {
+    if let Some(JsonPayloadError::Validate(errors)) = err.downcast_ref() {
+        Some(errors)
+    } else if let Some(UrlencodedError::Validate(errors)) = err.downcast_ref() {
+        Some(errors)
+    } else if let Some(QueryPayloadError::Validate(errors)) = err.downcast_ref() {
+        Some(errors)
+    } else if let Some(PathError::Validate(errors)) = err.downcast_ref() {
+        Some(errors)
+    } else {
+        err.downcast_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test::{self, TestRequest},
+        web, App, FromRequest,
+    };
+
+    #[test]
+    fn builder() {
+        let problem = Problem::build(StatusCode::FORBIDDEN)
+            .type_uri("https://example.com/probs/out-of-credit")
+            .title("You do not have enough credit.")
+            .detail("Your current balance is 30, but that costs 50.")
+            .instance("/account/12345/msgs/abc")
+            .extension("balance", 30)
+            .extension("status", "overwritten")
+            .extension("detail", "overwritten")
+            .finish();
+
+        assert!(problem.extensions().get("status").is_none());
+        assert!(problem.extensions().get("detail").is_none());
+
+        assert_eq!(problem.status(), StatusCode::FORBIDDEN);
+        assert_eq!(
+            problem.to_string(),
+            "You do not have enough credit.: Your current balance is 30, but that costs 50."
+        );
+        assert_eq!(
+            problem.to_json(),
+            serde_json::json!({
+                "type": "https://example.com/probs/out-of-credit",
+                "title": "You do not have enough credit.",
+                "status": 403,
+                "detail": "Your current balance is 30, but that costs 50.",
+                "instance": "/account/12345/msgs/abc",
+                "balance": 30,
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn response() {
+        let res = Problem::new(StatusCode::NOT_FOUND).error_response();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+
+        let body = actix_http::body::to_bytes(res.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({ "title": "Not Found", "status": 404 })
+        );
+    }
+
+    #[test]
+    fn from_validation_error() {
+        let mut errors = ValidationErrors::new();
+        errors.add("name", "must not be empty");
+
+        let problem = Problem::from_response_error(&JsonPayloadError::Validate(errors));
+        assert_eq!(problem.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            problem.extensions()["errors"],
+            serde_json::json!([{ "field": "name", "message": "must not be empty" }])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn extractor_errors_as_problems() {
+        let (req, mut pl) = TestRequest::with_uri("/?id=abc")
+            .app_data(ErrorFormat::Problem)
+            .to_http_parts();
+        let err = web::Query::<(u32,)>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(err.as_error::<QueryPayloadError>().is_some());
+        let res = err.error_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+
+        let srv = test::init_service(
+            App::new()
+                .app_data(ErrorFormat::Problem)
+                .route("/", web::post().to(|_: web::Json<u32>| async { "" })),
+        )
+        .await;
+
+        let req = TestRequest::post().uri("/").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        let body: Value = test::read_body_json(res).await;
+        assert_eq!(body["detail"], "Content type error");
+
+        let (req, mut pl) = TestRequest::with_uri("/?id=abc").to_http_parts();
+        let err = web::Query::<(u32,)>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert!(err.as_error::<QueryPayloadError>().is_some());
+    }
+}
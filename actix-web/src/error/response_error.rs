@@ -37,15 +37,7 @@ pub trait ResponseError: fmt::Debug + fmt::Display {
     /// By default, the generated response uses a 500 Internal Server Error status code, a
     /// `Content-Type` of `text/plain`, and the body is set to `Self`'s `Display` impl.
     fn error_response(&self) -> HttpResponse<BoxBody> {
-        let mut res = HttpResponse::new(self.status_code());
-
-        let mut buf = BytesMut::new();
-        let _ = write!(helpers::MutWriter(&mut buf), "{}", self);
-
-        let mime = mime::TEXT_PLAIN_UTF_8.try_into_value().unwrap();
-        res.headers_mut().insert(header::CONTENT_TYPE, mime);
-
-        res.set_body(BoxBody::new(buf))
+        plain_text_response(self.status_code(), self)
     }
 
     downcast_get_type_id!();
@@ -53,6 +45,23 @@ pub trait ResponseError: fmt::Debug + fmt::Display {
 
 downcast_dyn!(ResponseError);
 
+/// Creates a `text/plain` response with the given status code and the error's `Display` output as
+/// the body. This is what [`ResponseError::error_response`] returns by default.
+pub(crate) fn plain_text_response(
+    status: StatusCode,
+    err: &(impl fmt::Display + ?Sized),
+) -> HttpResponse<BoxBody> {
+    let mut res = HttpResponse::new(status);
+
+    let mut buf = BytesMut::new();
+    let _ = write!(helpers::MutWriter(&mut buf), "{}", err);
+
+    let mime = mime::TEXT_PLAIN_UTF_8.try_into_value().unwrap();
+    res.headers_mut().insert(header::CONTENT_TYPE, mime);
+
+    res.set_body(BoxBody::new(buf))
+}
+
 impl ResponseError for Box<dyn StdError + 'static> {}
 
 impl ResponseError for Infallible {
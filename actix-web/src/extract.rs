@@ -265,6 +265,39 @@ where
     }
 }
 
+pin_project! {
+    /// Future used by `#[derive(FromRequest)]` implementations.
+    ///
+    /// Drives the extractor future of a tuple containing the field types, then maps its output
+    /// into the derived type.
+    #[doc(hidden)]
+    pub struct FromRequestMapFuture<Fut, T, S> {
+        #[pin]
+        fut: Fut,
+        map: fn(T) -> S,
+    }
+}
+
+impl<Fut, T, S> FromRequestMapFuture<Fut, T, S> {
+    #[doc(hidden)]
+    pub fn new(fut: Fut, map: fn(T) -> S) -> Self {
+        Self { fut, map }
+    }
+}
+
+impl<Fut, T, S> Future for FromRequestMapFuture<Fut, T, S>
+where
+    Fut: Future<Output = Result<T, Error>>,
+{
+    type Output = Result<S, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let res = ready!(this.fut.poll(cx));
+        Poll::Ready(res.map(*this.map))
+    }
+}
+
 /// Extract the request's URI.
 ///
 /// # Examples
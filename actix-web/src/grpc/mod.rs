@@ -0,0 +1,430 @@
+//! gRPC services.
+//!
+//! A [`GrpcService`], created with [`web::grpc`](crate::web::grpc), serves the methods of a gRPC
+//! service using [Prost](https://docs.rs/prost) message types. Methods are either unary, taking
+//! one request message and returning one response message, or streaming, taking a [`Streaming`]
+//! of request messages and returning a stream of response messages.
+//!
+//! Calls are completed with `grpc-status` and `grpc-message` trailers, which requires clients to
+//! connect using HTTP/2. Handlers fail a call by returning a [`Status`].
+//!
+//! Request messages may be compressed using any encoding enabled by the `compress-*` crate
+//! features. Response messages are not compressed. Calls whose `grpc-timeout` expires are failed
+//! with [`Code::DeadlineExceeded`].
+//!
+//! # Examples
+//! ```
+//! use actix_web::{
+//!     grpc::{Code, Status, Streaming},
+//!     web, App, HttpRequest,
+//! };
+//! use futures_util::{stream, StreamExt as _};
+//!
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! struct HelloRequest {
+//!     #[prost(string, tag = "1")]
+//!     name: String,
+//! }
+//!
+//! #[derive(Clone, PartialEq, prost::Message)]
+//! struct HelloReply {
+//!     #[prost(string, tag = "1")]
+//!     message: String,
+//! }
+//!
+//! async fn say_hello(_req: HttpRequest, msg: HelloRequest) -> Result<HelloReply, Status> {
+//!     if msg.name.is_empty() {
+//!         return Err(Status::new(Code::InvalidArgument, "name is required"));
+//!     }
+//!
+//!     Ok(HelloReply {
+//!         message: format!("Hello {}!", msg.name),
+//!     })
+//! }
+//!
+//! async fn say_hello_to_all(
+//!     _req: HttpRequest,
+//!     messages: Streaming<HelloRequest>,
+//! ) -> Result<impl stream::Stream<Item = Result<HelloReply, Status>>, Status> {
+//!     Ok(messages.map(|msg| {
+//!         msg.map(|msg| HelloReply {
+//!             message: format!("Hello {}!", msg.name),
+//!         })
+//!     }))
+//! }
+//!
+//! let app = App::new().service(
+//!     web::grpc("helloworld.Greeter")
+//!         .unary("SayHello", say_hello)
+//!         .streaming("SayHelloToAll", say_hello_to_all),
+//! );
+//! ```
+
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use actix_rt::time::{sleep_until, timeout, Instant, Sleep};
+use actix_utils::future::ready;
+use bytes::Bytes;
+use futures_core::{future::LocalBoxFuture, ready, Stream};
+use futures_util::{stream, StreamExt as _};
+
+mod status;
+mod streaming;
+
+use self::streaming::{accept_encoding, encode_message};
+pub use self::{
+    status::{Code, Status},
+    streaming::Streaming,
+};
+use crate::{
+    body::{BodySize, MessageBody},
+    dev::{self, fn_service, AppService, HttpServiceFactory, ResourceDef, ServiceRequest},
+    http::{
+        header::{self, HeaderMap, HeaderName, HeaderValue},
+        Method,
+    },
+    HttpRequest, HttpResponse,
+};
+
+/// Default maximum size of a request message, matching other gRPC implementations.
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 4 * 1024 * 1024;
+
+/// Stream of encoded response messages.
+type MessageStream = Pin<Box<dyn Stream<Item = Result<Bytes, Status>>>>;
+
+/// Type-erased method handler, called with the request, its payload and the maximum message size.
+type MethodHandler = Rc<
+    dyn Fn(
+        HttpRequest,
+        dev::Payload,
+        usize,
+    ) -> LocalBoxFuture<'static, Result<MessageStream, Status>>,
+>;
+
+/// A gRPC service, serving requests for the methods of a named service.
+///
+/// Requests are routed to methods by their path, `/{service}/{method}`. Calls to methods that
+/// have not been registered are failed with [`Code::Unimplemented`].
+///
+/// See the [module documentation](self) for an example.
+pub struct GrpcService {
+    name: String,
+    max_message_size: usize,
+    methods: HashMap<String, MethodHandler>,
+}
+
+impl GrpcService {
+    /// Constructs a service for the fully-qualified service name `name`, like
+    /// `helloworld.Greeter`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            methods: HashMap::new(),
+        }
+    }
+
+    /// Sets the maximum size of a request message, in bytes, after decompression.
+    ///
+    /// Calls with larger messages are failed with [`Code::ResourceExhausted`]. The default limit
+    /// is 4 MiB.
+    pub fn max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Registers a unary method, which takes one request message and returns one response
+    /// message.
+    pub fn unary<F, Fut, Req, Res>(mut self, method: &str, handler: F) -> Self
+    where
+        F: Fn(HttpRequest, Req) -> Fut + 'static,
+        Fut: Future<Output = Result<Res, Status>> + 'static,
+        Req: prost::Message + Default + 'static,
+        Res: prost::Message + 'static,
+    {
+        let handler = Rc::new(handler);
+
+        let method_handler: MethodHandler = Rc::new(move |req, payload, max_message_size| {
+            let handler = Rc::clone(&handler);
+
+            Box::pin(async move {
+                let mut messages =
+                    Streaming::<Req>::new(payload, grpc_encoding(&req), max_message_size);
+
+                let msg = match messages.next().await {
+                    Some(msg) => msg?,
+                    None => return Err(Status::new(Code::Internal, "missing request message")),
+                };
+
+                if messages.next().await.is_some() {
+                    return Err(Status::new(
+                        Code::Internal,
+                        "unary call received more than one request message",
+                    ));
+                }
+
+                let res = handler(req, msg).await?;
+
+                Ok(Box::pin(stream::once(ready(Ok(encode_message(&res))))) as MessageStream)
+            })
+        });
+
+        self.methods.insert(method.to_owned(), method_handler);
+        self
+    }
+
+    /// Registers a streaming method, which takes a stream of request messages and returns a
+    /// stream of response messages.
+    ///
+    /// Client-streaming, server-streaming and bidirectional streaming methods are all registered
+    /// as streaming methods. The call is completed once the returned stream ends or yields an
+    /// error.
+    pub fn streaming<F, Fut, Req, S, Res>(mut self, method: &str, handler: F) -> Self
+    where
+        F: Fn(HttpRequest, Streaming<Req>) -> Fut + 'static,
+        Fut: Future<Output = Result<S, Status>> + 'static,
+        Req: prost::Message + Default + 'static,
+        S: Stream<Item = Result<Res, Status>> + 'static,
+        Res: prost::Message + 'static,
+    {
+        let handler = Rc::new(handler);
+
+        let method_handler: MethodHandler = Rc::new(move |req, payload, max_message_size| {
+            let handler = Rc::clone(&handler);
+
+            Box::pin(async move {
+                let messages = Streaming::new(payload, grpc_encoding(&req), max_message_size);
+                let responses = handler(req, messages).await?;
+
+                Ok(
+                    Box::pin(responses.map(|res| res.map(|msg| encode_message(&msg))))
+                        as MessageStream,
+                )
+            })
+        });
+
+        self.methods.insert(method.to_owned(), method_handler);
+        self
+    }
+
+    async fn handle(&self, req: HttpRequest, payload: dev::Payload) -> HttpResponse {
+        if req.method() != Method::POST {
+            return HttpResponse::MethodNotAllowed().finish();
+        }
+
+        let content_type = match req.headers().get(header::CONTENT_TYPE) {
+            Some(val) if val.as_bytes().starts_with(b"application/grpc") => val.clone(),
+            _ => return HttpResponse::UnsupportedMediaType().finish(),
+        };
+
+        let handler = match req
+            .match_info()
+            .get("method")
+            .and_then(|method| self.methods.get(method))
+        {
+            Some(handler) => Rc::clone(handler),
+            None => {
+                let status = Status::new(
+                    Code::Unimplemented,
+                    format!("method is not implemented by `{}`", self.name),
+                );
+                return response(content_type, Err(status), None);
+            }
+        };
+
+        let deadline = match req.headers().get("grpc-timeout").map(parse_timeout) {
+            Some(Some(timeout)) => Some(Instant::now() + timeout),
+            Some(None) => {
+                let status = Status::new(Code::InvalidArgument, "malformed grpc-timeout header");
+                return response(content_type, Err(status), None);
+            }
+            None => None,
+        };
+
+        let call = handler(req, payload, self.max_message_size);
+
+        let res = match deadline {
+            Some(deadline) => timeout(deadline - Instant::now(), call)
+                .await
+                .unwrap_or_else(|_| Err(deadline_exceeded())),
+            None => call.await,
+        };
+
+        response(content_type, res, deadline)
+    }
+}
+
+impl HttpServiceFactory for GrpcService {
+    fn register(self, config: &mut AppService) {
+        let rdef = ResourceDef::new(format!("/{}/{{method}}", self.name));
+        let service = Rc::new(self);
+
+        let factory = fn_service(move |req: ServiceRequest| {
+            let service = Rc::clone(&service);
+
+            async move {
+                let (req, payload) = req.into_parts();
+                let res = service.handle(req.clone(), payload).await;
+                Ok(dev::ServiceResponse::new(req, res))
+            }
+        });
+
+        config.register_service(rdef, None, factory, None)
+    }
+}
+
+/// Creates the response for a call.
+///
+/// Calls that fail before producing a response stream get a "trailers-only" response, with the
+/// call status in the response headers.
+fn response(
+    content_type: HeaderValue,
+    res: Result<MessageStream, Status>,
+    deadline: Option<Instant>,
+) -> HttpResponse {
+    let mut builder = HttpResponse::Ok();
+    builder
+        .insert_header((header::CONTENT_TYPE, content_type))
+        .insert_header(("grpc-accept-encoding", accept_encoding()));
+
+    match res {
+        Ok(messages) => builder.body(GrpcBody {
+            messages,
+            deadline: deadline.map(|deadline| Box::pin(sleep_until(deadline))),
+            status: None,
+        }),
+
+        Err(status) => {
+            let mut res = builder.finish();
+            status.insert_into(res.headers_mut());
+            res
+        }
+    }
+}
+
+/// Response body that sends encoded messages followed by the call status as trailers.
+struct GrpcBody {
+    messages: MessageStream,
+    deadline: Option<Pin<Box<Sleep>>>,
+    status: Option<Status>,
+}
+
+impl MessageBody for GrpcBody {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.get_mut();
+
+        if this.status.is_some() {
+            return Poll::Ready(None);
+        }
+
+        if let Some(deadline) = this.deadline.as_mut() {
+            if deadline.as_mut().poll(cx).is_ready() {
+                this.status = Some(deadline_exceeded());
+                return Poll::Ready(None);
+            }
+        }
+
+        match ready!(this.messages.as_mut().poll_next(cx)) {
+            Some(Ok(msg)) => Poll::Ready(Some(Ok(msg))),
+            Some(Err(status)) => {
+                this.status = Some(status);
+                Poll::Ready(None)
+            }
+            None => {
+                this.status = Some(Status::ok());
+                Poll::Ready(None)
+            }
+        }
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        let status = self.get_mut().status.take().unwrap_or_else(Status::ok);
+
+        let mut trailers = HeaderMap::new();
+        status.insert_into(&mut trailers);
+
+        Poll::Ready(Ok(Some(trailers)))
+    }
+}
+
+/// Returns the request's `grpc-encoding`, used for compressed request messages.
+fn grpc_encoding(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(HeaderName::from_static("grpc-encoding"))
+        .and_then(|val| val.to_str().ok())
+        .map(str::to_owned)
+}
+
+/// Parses a `grpc-timeout` header value, made of up to 8 digits followed by a unit.
+fn parse_timeout(val: &HeaderValue) -> Option<Duration> {
+    let val = val.to_str().ok()?;
+
+    if !(2..=9).contains(&val.len()) {
+        return None;
+    }
+
+    let (digits, unit) = val.split_at(val.len() - 1);
+
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let value = digits.parse::<u64>().ok()?;
+
+    Some(match unit {
+        "H" => Duration::from_secs(value * 60 * 60),
+        "M" => Duration::from_secs(value * 60),
+        "S" => Duration::from_secs(value),
+        "m" => Duration::from_millis(value),
+        "u" => Duration::from_micros(value),
+        "n" => Duration::from_nanos(value),
+        _ => return None,
+    })
+}
+
+fn deadline_exceeded() -> Status {
+    Status::new(Code::DeadlineExceeded, "deadline exceeded")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_parsing() {
+        let parse = |val| parse_timeout(&HeaderValue::from_static(val));
+
+        assert_eq!(parse("1H"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse("2M"), Some(Duration::from_secs(120)));
+        assert_eq!(parse("3S"), Some(Duration::from_secs(3)));
+        assert_eq!(parse("99999999m"), Some(Duration::from_millis(99_999_999)));
+        assert_eq!(parse("5u"), Some(Duration::from_micros(5)));
+        assert_eq!(parse("6n"), Some(Duration::from_nanos(6)));
+
+        assert_eq!(parse("S"), None);
+        assert_eq!(parse("100"), None);
+        assert_eq!(parse("-1S"), None);
+        assert_eq!(parse("1s"), None);
+        assert_eq!(parse("123456789S"), None);
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use crate::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// Characters that are percent-encoded in `grpc-message` values.
+const GRPC_MESSAGE: &AsciiSet = &CONTROLS.add(b'%');
+
+/// Status codes used to report the outcome of a gRPC call.
+///
+/// See the [gRPC documentation](https://grpc.github.io/grpc/core/md_doc_statuscodes.html) for
+/// when each code should be used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Code {
+    /// The call completed successfully.
+    Ok = 0,
+
+    /// The call was cancelled, typically by the caller.
+    Cancelled = 1,
+
+    /// Unknown error.
+    Unknown = 2,
+
+    /// The client specified an invalid argument.
+    InvalidArgument = 3,
+
+    /// The deadline expired before the call could complete.
+    DeadlineExceeded = 4,
+
+    /// Some requested entity was not found.
+    NotFound = 5,
+
+    /// The entity that a client attempted to create already exists.
+    AlreadyExists = 6,
+
+    /// The caller does not have permission to execute the call.
+    PermissionDenied = 7,
+
+    /// Some resource has been exhausted, like a quota or the maximum message size.
+    ResourceExhausted = 8,
+
+    /// The system is not in a state required for the call's execution.
+    FailedPrecondition = 9,
+
+    /// The call was aborted, typically due to a concurrency issue.
+    Aborted = 10,
+
+    /// The call was attempted past the valid range.
+    OutOfRange = 11,
+
+    /// The call is not implemented or not supported.
+    Unimplemented = 12,
+
+    /// Internal error.
+    Internal = 13,
+
+    /// The service is currently unavailable.
+    Unavailable = 14,
+
+    /// Unrecoverable data loss or corruption.
+    DataLoss = 15,
+
+    /// The request does not have valid authentication credentials.
+    Unauthenticated = 16,
+}
+
+/// The outcome of a gRPC call, sent to clients in the `grpc-status` and `grpc-message` trailers.
+///
+/// Handlers return a `Status` to fail a call.
+///
+/// # Examples
+/// ```
+/// use actix_web::grpc::{Code, Status};
+///
+/// let status = Status::new(Code::NotFound, "user not found");
+/// assert_eq!(status.code(), Code::NotFound);
+/// assert_eq!(status.message(), "user not found");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    code: Code,
+    message: String,
+}
+
+impl Status {
+    /// Constructs a status from a code and a message for the client.
+    pub fn new(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Constructs a status for a successful call.
+    pub fn ok() -> Self {
+        Self::new(Code::Ok, "")
+    }
+
+    /// Returns the status code.
+    pub fn code(&self) -> Code {
+        self.code
+    }
+
+    /// Returns the status message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Inserts the `grpc-status` and `grpc-message` fields for this status into `headers`.
+    pub(crate) fn insert_into(&self, headers: &mut HeaderMap) {
+        headers.insert(
+            HeaderName::from_static("grpc-status"),
+            HeaderValue::from(self.code as u16),
+        );
+
+        if !self.message.is_empty() {
+            let message = utf8_percent_encode(&self.message, GRPC_MESSAGE).to_string();
+
+            // percent-encoded messages only contain visible ASCII characters and spaces
+            if let Ok(message) = HeaderValue::try_from(message) {
+                headers.insert(HeaderName::from_static("grpc-message"), message);
+            }
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gRPC status {:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for Status {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_headers() {
+        let mut headers = HeaderMap::new();
+        Status::ok().insert_into(&mut headers);
+        assert_eq!(headers.get("grpc-status").unwrap(), "0");
+        assert!(!headers.contains_key("grpc-message"));
+
+        let mut headers = HeaderMap::new();
+        Status::new(Code::Unauthenticated, "bad token: 100% wrong\nretry ✓")
+            .insert_into(&mut headers);
+        assert_eq!(headers.get("grpc-status").unwrap(), "16");
+        assert_eq!(
+            headers.get("grpc-message").unwrap(),
+            "bad token: 100%25 wrong%0Aretry %E2%9C%93"
+        );
+    }
+}
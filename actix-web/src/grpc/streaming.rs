@@ -0,0 +1,320 @@
+use std::{
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_utils::future::ready;
+use bytes::{Buf as _, BufMut as _, Bytes, BytesMut};
+use futures_core::{future::LocalBoxFuture, ready, Stream};
+use futures_util::stream;
+use once_cell::sync::Lazy;
+
+use super::{Code, Status};
+use crate::{
+    body::{self, BodyStream},
+    dev::{self, Decompress},
+    http::header::ContentEncoding,
+};
+
+/// Size of the prefix of each length-prefixed message.
+const PREFIX_LEN: usize = 5;
+
+/// Stream of request messages sent to a streaming gRPC method.
+///
+/// Messages are decoded from the request payload as they arrive. Compressed messages are
+/// decompressed using the encoding named by the request's `grpc-encoding` header. Messages that
+/// can not be decoded are yielded as an error, after which the stream ends.
+pub struct Streaming<T> {
+    payload: dev::Payload,
+    buf: BytesMut,
+    encoding: Option<String>,
+    max_message_size: usize,
+    decompress: Option<LocalBoxFuture<'static, Result<Bytes, Status>>>,
+    eof: bool,
+    done: bool,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<T> Streaming<T> {
+    pub(crate) fn new(
+        payload: dev::Payload,
+        encoding: Option<String>,
+        max_message_size: usize,
+    ) -> Self {
+        Self {
+            payload,
+            buf: BytesMut::new(),
+            encoding,
+            max_message_size,
+            decompress: None,
+            eof: false,
+            done: false,
+            _message: PhantomData,
+        }
+    }
+
+    /// Starts decompressing a message using the request's `grpc-encoding`.
+    fn decompress(
+        &self,
+        data: Bytes,
+    ) -> Result<LocalBoxFuture<'static, Result<Bytes, Status>>, Status> {
+        // compressed messages require a message encoding (gRPC over HTTP/2, "Message-Encoding")
+        let name = match self.encoding.as_deref() {
+            Some(name) if name != "identity" => name,
+            _ => {
+                return Err(Status::new(
+                    Code::Internal,
+                    "compressed message received without a message encoding",
+                ))
+            }
+        };
+
+        let encoding = supported_encoding(name).ok_or_else(|| {
+            Status::new(
+                Code::Unimplemented,
+                format!("message compression with `{name}` is not supported"),
+            )
+        })?;
+
+        let limit = self.max_message_size;
+        let stream = Decompress::new(stream::once(ready(Ok(data))), encoding);
+
+        Ok(Box::pin(async move {
+            match body::to_bytes_limited(BodyStream::new(stream), limit).await {
+                Ok(Ok(data)) => Ok(data),
+                Ok(Err(err)) => Err(Status::new(
+                    Code::Internal,
+                    format!("failed to decompress message: {err}"),
+                )),
+                Err(_) => Err(message_too_large(limit)),
+            }
+        }))
+    }
+
+    /// Splits the next complete message off the buffer, returning its compression flag.
+    fn next_message(&mut self) -> Result<Option<(u8, Bytes)>, Status> {
+        if self.buf.len() < PREFIX_LEN {
+            return Ok(None);
+        }
+
+        let len = u32::from_be_bytes([self.buf[1], self.buf[2], self.buf[3], self.buf[4]]) as usize;
+
+        if len > self.max_message_size {
+            return Err(message_too_large(self.max_message_size));
+        }
+
+        if self.buf.len() < PREFIX_LEN + len {
+            self.buf.reserve(PREFIX_LEN + len - self.buf.len());
+            return Ok(None);
+        }
+
+        let flag = self.buf.get_u8();
+        self.buf.advance(4);
+
+        Ok(Some((flag, self.buf.split_to(len).freeze())))
+    }
+}
+
+impl<T: prost::Message + Default> Streaming<T> {
+    fn poll_message(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<T, Status>>> {
+        loop {
+            if let Some(fut) = self.decompress.as_mut() {
+                let data = ready!(fut.as_mut().poll(cx));
+                self.decompress = None;
+                return Poll::Ready(Some(data.and_then(decode_message)));
+            }
+
+            match self.next_message()? {
+                Some((0, data)) => return Poll::Ready(Some(decode_message(data))),
+                Some((1, data)) => {
+                    self.decompress = Some(self.decompress(data)?);
+                    continue;
+                }
+                Some((flag, _)) => {
+                    return Poll::Ready(Some(Err(Status::new(
+                        Code::Internal,
+                        format!("invalid message compression flag: {flag}"),
+                    ))));
+                }
+                None => {}
+            }
+
+            if self.eof {
+                return Poll::Ready(if self.buf.is_empty() {
+                    None
+                } else {
+                    Some(Err(Status::new(
+                        Code::Internal,
+                        "request payload ended with an incomplete message",
+                    )))
+                });
+            }
+
+            match ready!(Pin::new(&mut self.payload).poll_next(cx)) {
+                Some(Ok(chunk)) => self.buf.extend_from_slice(&chunk),
+                Some(Err(err)) => {
+                    return Poll::Ready(Some(Err(Status::new(
+                        Code::Internal,
+                        format!("failed to read request payload: {err}"),
+                    ))));
+                }
+                None => self.eof = true,
+            }
+        }
+    }
+}
+
+impl<T: prost::Message + Default> Stream for Streaming<T> {
+    type Item = Result<T, Status>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        let res = ready!(this.poll_message(cx));
+
+        // end the stream after the first error
+        if !matches!(res, Some(Ok(_))) {
+            this.done = true;
+        }
+
+        Poll::Ready(res)
+    }
+}
+
+/// Encodes a message as an uncompressed length-prefixed message.
+pub(crate) fn encode_message(msg: &impl prost::Message) -> Bytes {
+    let len = msg.encoded_len();
+
+    let mut buf = BytesMut::with_capacity(PREFIX_LEN + len);
+    buf.put_u8(0);
+    buf.put_u32(len as u32);
+
+    // encoding can only fail when the buffer is too small
+    msg.encode(&mut buf).unwrap();
+
+    buf.freeze()
+}
+
+fn decode_message<T: prost::Message + Default>(data: Bytes) -> Result<T, Status> {
+    T::decode(data).map_err(|err| {
+        Status::new(
+            Code::Internal,
+            format!("failed to decode request message: {err}"),
+        )
+    })
+}
+
+fn message_too_large(limit: usize) -> Status {
+    Status::new(
+        Code::ResourceExhausted,
+        format!("request message is larger than {limit} bytes"),
+    )
+}
+
+/// Returns the content encoding for a `grpc-encoding` name, if supported.
+fn supported_encoding(name: &str) -> Option<ContentEncoding> {
+    match name {
+        #[cfg(feature = "compress-brotli")]
+        "br" => Some(ContentEncoding::Brotli),
+
+        #[cfg(feature = "compress-gzip")]
+        "gzip" => Some(ContentEncoding::Gzip),
+
+        #[cfg(feature = "compress-gzip")]
+        "deflate" => Some(ContentEncoding::Deflate),
+
+        #[cfg(feature = "compress-zstd")]
+        "zstd" => Some(ContentEncoding::Zstd),
+
+        _ => None,
+    }
+}
+
+/// Returns the value of the `grpc-accept-encoding` header sent in responses.
+pub(crate) fn accept_encoding() -> &'static str {
+    static ACCEPT_ENCODING: Lazy<String> = Lazy::new(|| {
+        #[allow(unused_mut)] // only unused when no compress features enabled
+        let mut encodings = vec!["identity"];
+
+        #[cfg(feature = "compress-brotli")]
+        encodings.push("br");
+
+        #[cfg(feature = "compress-gzip")]
+        encodings.extend(["gzip", "deflate"]);
+
+        #[cfg(feature = "compress-zstd")]
+        encodings.push("zstd");
+
+        encodings.join(",")
+    });
+
+    &ACCEPT_ENCODING
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt as _;
+
+    use super::*;
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Greeting {
+        #[prost(string, tag = "1")]
+        name: String,
+    }
+
+    fn greeting(name: &str) -> Greeting {
+        Greeting {
+            name: name.to_owned(),
+        }
+    }
+
+    fn streaming(chunks: Vec<Bytes>) -> Streaming<Greeting> {
+        let (mut sender, payload) = actix_http::h1::Payload::create(false);
+
+        for chunk in chunks {
+            sender.feed_data(chunk);
+        }
+        sender.feed_eof();
+
+        Streaming::new(dev::Payload::from(payload), None, 64)
+    }
+
+    #[actix_rt::test]
+    async fn decode_split_messages() {
+        let mut data = BytesMut::from(&encode_message(&greeting("alice"))[..]);
+        data.extend_from_slice(&encode_message(&greeting("bob")));
+        let data = data.freeze();
+
+        let mut stream = streaming(vec![data.slice(..3), data.slice(3..9), data.slice(9..)]);
+        assert_eq!(stream.next().await.unwrap().unwrap(), greeting("alice"));
+        assert_eq!(stream.next().await.unwrap().unwrap(), greeting("bob"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn decode_errors() {
+        let mut stream = streaming(vec![Bytes::from_static(b"\x00\x00\x00\x00\x03ab")]);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.code(), Code::Internal);
+        assert!(stream.next().await.is_none());
+
+        let mut stream = streaming(vec![Bytes::from_static(b"\x00\x00\x00\x01\x00")]);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.code(), Code::ResourceExhausted);
+
+        // compressed message without a message encoding
+        let mut stream = streaming(vec![Bytes::from_static(b"\x01\x00\x00\x00\x00")]);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.code(), Code::Internal);
+
+        let mut stream = streaming(vec![Bytes::from_static(b"\x02\x00\x00\x00\x00")]);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(err.code(), Code::Internal);
+    }
+}
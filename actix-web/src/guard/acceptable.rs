@@ -1,4 +1,4 @@
-use super::{Guard, GuardContext};
+use super::{Guard, GuardContext, GuardDetail};
 use crate::http::header::Accept;
 
 /// A guard that verifies that an `Accept` header is present and it contains a compatible MIME type.
@@ -63,6 +63,10 @@ impl Guard for Acceptable {
 
         false
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        Some(vec![GuardDetail::Generic(format!("accept({})", self.mime))])
+    }
 }
 
 #[cfg(test)]
@@ -1,6 +1,7 @@
 use actix_http::{header, uri::Uri, RequestHead};
+use actix_router::{Path, ResourceDef};
 
-use super::{Guard, GuardContext};
+use super::{Guard, GuardContext, GuardDetail};
 
 /// Creates a guard that matches requests targeting a specific host.
 ///
@@ -116,6 +117,72 @@ impl Guard for HostGuard {
         // all conditions passed
         true
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        let host = match &self.scheme {
+            Some(scheme) => format!("{scheme}://{}", self.host),
+            None => self.host.clone(),
+        };
+
+        Some(vec![GuardDetail::Generic(format!("host({host})"))])
+    }
+}
+
+/// Guard that matches requests whose host matches a [`ResourceDef`] pattern.
+///
+/// Used by [`Scope::host`](crate::Scope::host).
+pub(crate) struct HostPatternGuard {
+    pattern: ResourceDef,
+}
+
+impl HostPatternGuard {
+    pub(crate) fn new(pattern: ResourceDef) -> Self {
+        Self { pattern }
+    }
+}
+
+impl Guard for HostPatternGuard {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        match_host_pattern(&self.pattern, ctx.head()).is_some()
+    }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        let pattern = self.pattern.pattern().unwrap_or_default();
+        Some(vec![GuardDetail::Generic(format!("host({pattern})"))])
+    }
+}
+
+/// Constructs a host pattern, lowercasing its static parts so it matches hosts case-insensitively.
+///
+/// Dynamic segments are left as-is so that segment names and custom regexes are not changed.
+pub(crate) fn host_pattern(pattern: &str) -> ResourceDef {
+    let mut depth = 0usize;
+
+    let pattern = pattern
+        .chars()
+        .map(|ch| {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth = depth.saturating_sub(1),
+                _ if depth == 0 => return ch.to_ascii_lowercase(),
+                _ => {}
+            }
+
+            ch
+        })
+        .collect::<String>();
+
+    ResourceDef::new(pattern)
+}
+
+/// Matches the request's host against a host pattern, returning the captured segments on success.
+///
+/// Hosts are matched case-insensitively and without their port.
+pub(crate) fn match_host_pattern(pattern: &ResourceDef, req: &RequestHead) -> Option<Path<String>> {
+    let host = get_host_uri(req)?.host()?.to_ascii_lowercase();
+
+    let mut path = Path::new(host);
+    pattern.capture_match_info(&mut path).then_some(path)
 }
 
 #[cfg(test)]
@@ -51,6 +51,7 @@
 
 use std::{
     cell::{Ref, RefMut},
+    fmt,
     rc::Rc,
 };
 
@@ -61,6 +62,7 @@ use crate::{http::header::Header, service::ServiceRequest, HttpMessage as _};
 mod acceptable;
 mod host;
 
+pub(crate) use self::host::{host_pattern, match_host_pattern, HostPatternGuard};
 pub use self::{
     acceptable::Acceptable,
     host::{Host, HostGuard},
@@ -118,12 +120,94 @@ impl<'a> GuardContext<'a> {
 pub trait Guard {
     /// Returns true if predicate condition is met for a given request.
     fn check(&self, ctx: &GuardContext<'_>) -> bool;
+
+    /// Returns details about the conditions this guard checks, used for route introspection.
+    ///
+    /// The default implementation returns `None`, meaning no details are available.
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        None
+    }
 }
 
 impl Guard for Rc<dyn Guard> {
     fn check(&self, ctx: &GuardContext<'_>) -> bool {
         (**self).check(ctx)
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        (**self).details()
+    }
+}
+
+/// Introspection details of a guard.
+///
+/// See [`Guard::details`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GuardDetail {
+    /// Request method must be one of the listed HTTP methods.
+    HttpMethods(Vec<HttpMethod>),
+
+    /// Any other condition, described in text.
+    Generic(String),
+}
+
+impl fmt::Display for GuardDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GuardDetail::HttpMethods(methods) => {
+                let methods = methods.iter().map(HttpMethod::as_str).collect::<Vec<_>>();
+                write!(f, "method({})", methods.join(", "))
+            }
+            GuardDetail::Generic(desc) => f.write_str(desc),
+        }
+    }
+}
+
+/// Describes guard details that must all hold, e.g., for use inside another description.
+fn describe_all(details: &[GuardDetail]) -> String {
+    match details {
+        [detail] => detail.to_string(),
+        details => {
+            let details = details.iter().map(ToString::to_string).collect::<Vec<_>>();
+            format!("all({})", details.join(", "))
+        }
+    }
+}
+
+/// Collects the details of a list of guards, skipping guards that do not provide any.
+pub(crate) fn guard_details<'a>(
+    guards: impl IntoIterator<Item = &'a Box<dyn Guard>>,
+) -> Vec<GuardDetail> {
+    guards
+        .into_iter()
+        .filter_map(|guard| guard.details())
+        .flatten()
+        .collect()
+}
+
+/// Returns the HTTP methods allowed by a list of guards that must all match.
+///
+/// Returns `None` if the guards do not restrict the request method.
+pub(crate) fn allowed_methods(guards: &[Box<dyn Guard>]) -> Option<Vec<HttpMethod>> {
+    let mut allowed = None::<Vec<HttpMethod>>;
+
+    for detail in guards.iter().filter_map(|guard| guard.details()).flatten() {
+        let methods = match detail {
+            GuardDetail::HttpMethods(methods) => methods,
+            _ => continue,
+        };
+
+        allowed = Some(match allowed {
+            Some(allowed) => allowed
+                .into_iter()
+                .filter(|method| methods.contains(method))
+                .collect(),
+            None => methods,
+        });
+    }
+
+    allowed
 }
 
 /// Creates a guard using the given function.
@@ -213,6 +297,40 @@ impl Guard for AnyGuard {
 
         false
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        let details = self
+            .guards
+            .iter()
+            .map(|guard| guard.details())
+            .collect::<Option<Vec<_>>>()?;
+
+        // method guards combine into a single method detail
+        let methods = details
+            .iter()
+            .flatten()
+            .map(|detail| match detail {
+                GuardDetail::HttpMethods(methods) => Some(methods.iter().cloned()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+
+        if let Some(methods) = methods {
+            return Some(vec![GuardDetail::HttpMethods(
+                methods.into_iter().flatten().collect(),
+            )]);
+        }
+
+        let details = details
+            .iter()
+            .map(|details| describe_all(details))
+            .collect::<Vec<_>>();
+
+        Some(vec![GuardDetail::Generic(format!(
+            "any({})",
+            details.join(", ")
+        ))])
+    }
 }
 
 /// Creates a guard that matches if all added guards match.
@@ -265,6 +383,11 @@ impl Guard for AllGuard {
 
         true
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        let details = guard_details(&self.guards);
+        (!details.is_empty()).then_some(details)
+    }
 }
 
 /// Wraps a guard and inverts the outcome of its `Guard` implementation.
@@ -285,6 +408,14 @@ impl<G: Guard> Guard for Not<G> {
     fn check(&self, ctx: &GuardContext<'_>) -> bool {
         !self.0.check(ctx)
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        let details = self.0.details()?;
+        Some(vec![GuardDetail::Generic(format!(
+            "not({})",
+            describe_all(&details)
+        ))])
+    }
 }
 
 /// Creates a guard that matches a specified HTTP method.
@@ -293,27 +424,18 @@ pub fn Method(method: HttpMethod) -> impl Guard {
     MethodGuard(method)
 }
 
-#[derive(Debug, Clone)]
-pub(crate) struct RegisteredMethods(pub(crate) Vec<HttpMethod>);
-
 /// HTTP method guard.
 #[derive(Debug)]
 pub(crate) struct MethodGuard(HttpMethod);
 
 impl Guard for MethodGuard {
     fn check(&self, ctx: &GuardContext<'_>) -> bool {
-        let registered = ctx.req_data_mut().remove::<RegisteredMethods>();
-
-        if let Some(mut methods) = registered {
-            methods.0.push(self.0.clone());
-            ctx.req_data_mut().insert(methods);
-        } else {
-            ctx.req_data_mut()
-                .insert(RegisteredMethods(vec![self.0.clone()]));
-        }
-
         ctx.head().method == self.0
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        Some(vec![GuardDetail::HttpMethods(vec![self.0.clone()])])
+    }
 }
 
 macro_rules! method_guard {
@@ -376,6 +498,14 @@ impl Guard for HeaderGuard {
 
         false
     }
+
+    fn details(&self) -> Option<Vec<GuardDetail>> {
+        Some(vec![GuardDetail::Generic(format!(
+            "header({}: {})",
+            self.0,
+            String::from_utf8_lossy(self.1.as_bytes())
+        ))])
+    }
 }
 
 #[cfg(test)]
@@ -512,4 +642,41 @@ mod tests {
             .to_srv_request();
         assert!(guard.check(&req.guard_ctx()));
     }
+
+    #[test]
+    fn details() {
+        let describe = |guard: &dyn Guard| {
+            guard
+                .details()
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(describe(&Get()), ["method(GET)"]);
+        assert_eq!(describe(&Any(Get()).or(Post())), ["method(GET, POST)"]);
+        assert_eq!(
+            describe(&Any(Get()).or(Header("x-a", "1"))),
+            ["any(method(GET), header(x-a: 1))"]
+        );
+        assert_eq!(
+            describe(&All(Put()).and(Not(Host("example.com")))),
+            ["method(PUT)", "not(host(example.com))"]
+        );
+
+        assert!(fn_guard(|_| true).details().is_none());
+        assert!(Any(Get()).or(fn_guard(|_| true)).details().is_none());
+        assert!(All(fn_guard(|_| true)).details().is_none());
+
+        let guards: Vec<Box<dyn Guard>> = vec![
+            Box::new(Any(Get()).or(Post())),
+            Box::new(Header("x-a", "1")),
+            Box::new(Not(Get())),
+        ];
+        assert_eq!(
+            allowed_methods(&guards),
+            Some(vec![Method::GET, Method::POST])
+        );
+    }
 }
@@ -2,4 +2,6 @@
 
 pub mod header;
 
-pub use actix_http::{uri, ConnectionType, Error, KeepAlive, Method, StatusCode, Uri, Version};
+pub use actix_http::{
+    uri, ConnectionType, Error, H1Limits, H2Settings, KeepAlive, Method, StatusCode, Uri, Version,
+};
@@ -65,7 +65,10 @@
 //! - `compress-zstd` - zstd content encoding compression support (enabled by default)
 //! - `openssl` - HTTPS support via `openssl` crate, supports `HTTP/2`
 //! - `rustls` - HTTPS support via `rustls` crate, supports `HTTP/2`
+//! - `http3` - `HTTP/3` support over QUIC via `quinn` and `h3` crates
 //! - `secure-cookies` - secure cookies support
+//! - `openapi` - OpenAPI document generation from registered routes
+//! - `grpc` - gRPC services using Prost message types
 
 #![deny(rust_2018_idioms, nonstandard_style)]
 #![warn(future_incompatible)]
@@ -83,14 +86,20 @@ mod app_service;
 mod config;
 mod data;
 pub mod dev;
+mod dynamic_router;
 pub mod error;
 mod extract;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod guard;
 mod handler;
 mod helpers;
 pub mod http;
 mod info;
+mod method_fallback;
 pub mod middleware;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 mod redirect;
 mod request;
 mod request_data;
@@ -133,6 +142,7 @@ codegen_reexport!(main);
 codegen_reexport!(test);
 codegen_reexport!(route);
 codegen_reexport!(routes);
+codegen_reexport!(scope);
 codegen_reexport!(head);
 codegen_reexport!(get);
 codegen_reexport!(post);
@@ -142,5 +152,8 @@ codegen_reexport!(delete);
 codegen_reexport!(trace);
 codegen_reexport!(connect);
 codegen_reexport!(options);
+codegen_reexport!(FromRequest);
+codegen_reexport!(ResponseError);
+codegen_reexport!(Responder);
 
 pub(crate) type BoxError = Box<dyn std::error::Error>;
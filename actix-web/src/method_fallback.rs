@@ -0,0 +1,232 @@
+//! Automatic responses to requests whose method is not handled by a matching resource.
+
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_http::{
+    body::{BodySize, BoxBody, MessageBody},
+    Method,
+};
+use actix_router::Router;
+use actix_utils::future::ready;
+use bytes::Bytes;
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    guard::Guard,
+    http::header,
+    service::{BoxedHttpService, ServiceRequest, ServiceResponse},
+    Error, HttpResponse,
+};
+
+/// Settings for responding to requests whose method is not handled by any route of a resource.
+///
+/// See [`App::method_not_allowed`](crate::App::method_not_allowed),
+/// [`App::auto_options`](crate::App::auto_options) and
+/// [`App::auto_head`](crate::App::auto_head).
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MethodFallback {
+    /// When to respond with `405 Method Not Allowed`.
+    pub(crate) not_allowed: NotAllowed,
+
+    /// Respond to `OPTIONS` requests with `204 No Content`.
+    pub(crate) options: bool,
+
+    /// Handle `HEAD` requests using `GET` routes.
+    pub(crate) head: bool,
+}
+
+/// When requests matching a resource's path but none of its routes' methods get a
+/// `405 Method Not Allowed` response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum NotAllowed {
+    /// Resources respond with `405`, but requests whose path only matches resources guarded by
+    /// other methods are passed to the default service.
+    ///
+    /// Used unless configured using [`App::method_not_allowed`](crate::App::method_not_allowed).
+    #[default]
+    ResourcesOnly,
+
+    /// Resources respond with `405`, and so do apps and scopes for requests whose path only matches
+    /// resources guarded by other methods.
+    Enabled,
+
+    /// Requests are passed to the default service.
+    Disabled,
+}
+
+impl MethodFallback {
+    /// Returns the settings used by a resource, which responds with `405 Method Not Allowed` unless
+    /// disabled or the resource has its own default service.
+    pub(crate) fn for_resource(mut self, has_default: bool) -> Self {
+        self.not_allowed = match self.not_allowed {
+            NotAllowed::ResourcesOnly | NotAllowed::Enabled if has_default => NotAllowed::Disabled,
+            NotAllowed::ResourcesOnly | NotAllowed::Enabled => NotAllowed::Enabled,
+            NotAllowed::Disabled => NotAllowed::Disabled,
+        };
+        self
+    }
+
+    /// Returns the methods listed in `Allow` headers for a resource whose routes handle `methods`.
+    fn allowed(&self, methods: &[Method]) -> Vec<Method> {
+        let mut allowed = Vec::with_capacity(methods.len() + 2);
+
+        for method in methods {
+            if !allowed.contains(method) {
+                allowed.push(method.clone());
+            }
+        }
+
+        if self.head && allowed.contains(&Method::GET) && !allowed.contains(&Method::HEAD) {
+            allowed.push(Method::HEAD);
+        }
+
+        if self.options && !allowed.contains(&Method::OPTIONS) {
+            allowed.push(Method::OPTIONS);
+        }
+
+        allowed
+    }
+
+    /// Responds to a request that was not handled by any route of a resource whose routes handle
+    /// `methods`, or returns the request back if no automatic response is enabled for it.
+    pub(crate) fn respond(
+        &self,
+        req: ServiceRequest,
+        methods: &[Method],
+    ) -> Result<ServiceResponse, ServiceRequest> {
+        if self.options
+            && req.method() == Method::OPTIONS
+            && !methods.is_empty()
+            && !methods.contains(&Method::OPTIONS)
+        {
+            let res = HttpResponse::NoContent()
+                .insert_header(header::Allow(self.allowed(methods)))
+                .finish();
+
+            return Ok(req.into_response(res));
+        }
+
+        if self.not_allowed == NotAllowed::Enabled {
+            let mut res = HttpResponse::MethodNotAllowed();
+
+            if !methods.is_empty() {
+                res.insert_header(header::Allow(self.allowed(methods)));
+            }
+
+            return Ok(req.into_response(res.finish()));
+        }
+
+        Err(req)
+    }
+
+    /// Returns true if a `HEAD` request should be handled by a `GET` route.
+    pub(crate) fn use_get_for_head(&self, req: &ServiceRequest, methods: &[Method]) -> bool {
+        self.head
+            && req.method() == Method::HEAD
+            && methods.contains(&Method::GET)
+            && !methods.contains(&Method::HEAD)
+    }
+}
+
+/// Runs `check` against `req` as if it was a `GET` request.
+pub(crate) fn check_as_get<T>(
+    req: &mut ServiceRequest,
+    check: impl FnOnce(&mut ServiceRequest) -> T,
+) -> T {
+    let method = std::mem::replace(&mut req.head_mut().method, Method::GET);
+    let res = check(req);
+    req.head_mut().method = method;
+    res
+}
+
+/// Discards the body of a response to a `HEAD` request that was handled by a `GET` route.
+pub(crate) fn head_response(
+    fut: LocalBoxFuture<'static, Result<ServiceResponse, Error>>,
+) -> LocalBoxFuture<'static, Result<ServiceResponse, Error>> {
+    Box::pin(async move {
+        let res = fut.await?;
+        Ok(res.map_body(|_, body| BoxBody::new(HeadBody(body.size()))))
+    })
+}
+
+/// Body of a response to a `HEAD` request, which reports the size of the discarded `GET` response
+/// body without yielding any data.
+struct HeadBody(BodySize);
+
+impl MessageBody for HeadBody {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        self.0
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        Poll::Ready(None)
+    }
+}
+
+/// Guards of a service in an app or scope router, along with the methods handled by its routes.
+pub(crate) struct ServiceGuards {
+    pub(crate) guards: Vec<Box<dyn Guard>>,
+    pub(crate) methods: Vec<Method>,
+}
+
+impl ServiceGuards {
+    fn check(&self, req: &ServiceRequest) -> bool {
+        let guard_ctx = req.guard_ctx();
+        self.guards.iter().all(|guard| guard.check(&guard_ctx))
+    }
+}
+
+/// Routes a request in an app or scope router.
+///
+/// When no service matches the request but resources match its path, automatic responses are
+/// used based on the methods those resources handle before falling back to `default`.
+pub(crate) fn route(
+    router: &Router<BoxedHttpService, ServiceGuards>,
+    default: &BoxedHttpService,
+    fallback: MethodFallback,
+    mut req: ServiceRequest,
+) -> LocalBoxFuture<'static, Result<ServiceResponse, Error>> {
+    let res = router.recognize_fn(&mut req, |req, guards| guards.check(req));
+
+    if let Some((srv, _info)) = res {
+        return srv.call(req);
+    }
+
+    // collect methods handled by resources that match the path, ignoring guards
+    let mut methods = Vec::new();
+    router.recognize_fn(&mut req, |_, guards| {
+        methods.extend(guards.methods.iter().cloned());
+        false
+    });
+
+    // request failed other guards of a resource that handles its method
+    if methods.is_empty() || methods.contains(req.method()) {
+        return default.call(req);
+    }
+
+    if fallback.use_get_for_head(&req, &methods) {
+        let res = check_as_get(&mut req, |req| {
+            router
+                .recognize_fn(req, |req, guards| guards.check(req))
+                .map(|(srv, _info)| srv)
+        });
+
+        if let Some(srv) = res {
+            return head_response(srv.call(req));
+        }
+    }
+
+    match fallback.respond(req, &methods) {
+        Ok(res) => Box::pin(ready(Ok(res))),
+        Err(req) => default.call(req),
+    }
+}
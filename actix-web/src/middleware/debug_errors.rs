@@ -0,0 +1,628 @@
+//! For middleware documentation, see [`DebugErrors`].
+
+use std::{
+    any::type_name,
+    error::Error as StdError,
+    fmt::{self, Write as _},
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use actix_utils::future::{ready, Ready};
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use crate::{
+    body::{EitherBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    error::{
+        self, BlockingError, ContentTypeError, JsonPayloadError, ParseError, PathError,
+        PayloadError, Problem, QueryPayloadError, ReadlinesError, UrlGenerationError,
+        UrlencodedError, ValidationErrors,
+    },
+    http::header::{self, HeaderName, HeaderValue},
+    Error, HttpRequest,
+};
+
+/// Whether debug error pages can be rendered in this build.
+const ENABLED: bool = cfg!(debug_assertions);
+
+/// Placeholder shown in place of redacted header values.
+const REDACTED: &str = "[redacted]";
+
+/// Formats the app data of a type shown in reports, if the request has any.
+type AppDataFormatter = fn(&HttpRequest) -> Option<String>;
+
+/// Middleware for rendering detailed error pages during development.
+///
+/// Responses that were created from an [`Error`] and have a 4xx or 5xx status code have their body
+/// replaced with a report. This includes errors returned from handlers and extractors, but not
+/// errors returned by middleware wrapping this one. The report contains:
+/// - the error message, its `Debug` representation and, where available, its source chain;
+/// - a backtrace captured when the `Error` was created;
+/// - the request method, URI, HTTP version and headers;
+/// - the matched route pattern, name and path parameters;
+/// - the app data of types added using [`show_app_data`](Self::show_app_data).
+///
+/// The report is rendered as JSON when the request's `Accept` header contains `application/json`
+/// and as HTML otherwise.
+///
+/// Values of the `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie` headers are
+/// redacted from the report. Additional headers can be redacted using
+/// [`redact_header`](Self::redact_header).
+///
+/// # Availability
+/// This middleware requires the `debug-errors` crate feature. Additionally, it only has an effect
+/// in builds with `debug_assertions` enabled; in release builds it passes all responses through
+/// unchanged and logs a warning when constructed. Backtraces are only captured for errors created
+/// while handling requests passed through this middleware.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::DebugErrors, App};
+///
+/// let app = App::new().wrap(DebugErrors::new());
+/// ```
+#[derive(Debug, Clone)]
+pub struct DebugErrors {
+    inner: Rc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    redacted: Vec<HeaderName>,
+    app_data: Vec<(&'static str, AppDataFormatter)>,
+}
+
+impl DebugErrors {
+    /// Constructs new debug error page middleware with the default header redaction rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header whose value should be redacted from reports.
+    ///
+    /// # Panics
+    /// Panics if called after the middleware has been cloned.
+    pub fn redact_header(mut self, name: HeaderName) -> Self {
+        Rc::get_mut(&mut self.inner)
+            .expect("All redacted headers must be added before cloning.")
+            .redacted
+            .push(name);
+
+        self
+    }
+
+    /// Adds an app data type whose `Debug` representation should be shown in reports.
+    ///
+    /// The type is looked up the same way as [`HttpRequest::app_data`], so data added using
+    /// [`App::app_data`](crate::App::app_data) with a [`Data`](crate::web::Data) wrapper is shown
+    /// using `show_app_data::<Data<T>>()`.
+    ///
+    /// # Panics
+    /// Panics if called after the middleware has been cloned.
+    pub fn show_app_data<T: fmt::Debug + 'static>(mut self) -> Self {
+        fn format<T: fmt::Debug + 'static>(req: &HttpRequest) -> Option<String> {
+            req.app_data::<T>().map(|data| format!("{:#?}", data))
+        }
+
+        Rc::get_mut(&mut self.inner)
+            .expect("All app data types must be added before cloning.")
+            .app_data
+            .push((type_name::<T>(), format::<T>));
+
+        self
+    }
+}
+
+impl Default for DebugErrors {
+    fn default() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                redacted: vec![
+                    header::AUTHORIZATION,
+                    header::PROXY_AUTHORIZATION,
+                    header::COOKIE,
+                    header::SET_COOKIE,
+                ],
+                app_data: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for DebugErrors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = DebugErrorsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        if !ENABLED {
+            log::warn!("DebugErrors middleware has no effect in builds without debug assertions");
+        }
+
+        ready(Ok(DebugErrorsMiddleware {
+            service,
+            inner: Rc::clone(&self.inner),
+        }))
+    }
+}
+
+#[doc(hidden)]
+pub struct DebugErrorsMiddleware<S> {
+    service: S,
+    inner: Rc<Inner>,
+}
+
+impl<S, B> Service<ServiceRequest> for DebugErrorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = DebugErrorsFuture<S::Future>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        DebugErrorsFuture {
+            fut: error::capture_backtraces(|| self.service.call(req)),
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
+pin_project! {
+    pub struct DebugErrorsFuture<Fut> {
+        #[pin]
+        fut: Fut,
+        inner: Rc<Inner>,
+    }
+}
+
+impl<Fut, B> Future for DebugErrorsFuture<Fut>
+where
+    Fut: Future<Output = Result<ServiceResponse<B>, Error>>,
+    B: MessageBody + 'static,
+{
+    type Output = Result<ServiceResponse<EitherBody<B>>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let res = ready!(error::capture_backtraces(|| this.fut.poll(cx)))?;
+        Poll::Ready(Ok(render(res, this.inner)))
+    }
+}
+
+/// Replaces the body of error responses with a rendered report.
+fn render<B>(res: ServiceResponse<B>, inner: &Inner) -> ServiceResponse<EitherBody<B>> {
+    let status = res.status();
+
+    if !ENABLED || !(status.is_client_error() || status.is_server_error()) {
+        return res.map_into_left_body();
+    }
+
+    let (body, content_type) = match res.response().error() {
+        Some(err) => {
+            let report = Report::new(err, res.request(), inner);
+
+            if prefers_json(res.request()) {
+                (report.to_json(), mime::APPLICATION_JSON)
+            } else {
+                (report.to_html(), mime::TEXT_HTML_UTF_8)
+            }
+        }
+
+        None => return res.map_into_left_body(),
+    };
+
+    res.map_body(|head, _| {
+        head.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_str(content_type.as_ref()).unwrap(),
+        );
+        head.headers.remove(header::CONTENT_LENGTH);
+
+        EitherBody::right(body.boxed())
+    })
+}
+
+/// Returns true if the request's `Accept` header asks for JSON.
+fn prefers_json(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .is_some_and(|accept| accept.contains("application/json"))
+}
+
+/// Information about an error response gathered for rendering.
+struct Report {
+    status: String,
+    message: String,
+    debug: String,
+    sources: Vec<String>,
+    backtrace: String,
+    method: String,
+    uri: String,
+    version: String,
+    headers: Vec<(String, String)>,
+    route_pattern: Option<String>,
+    route_name: Option<String>,
+    route_params: Vec<(String, String)>,
+    app_data: Vec<(&'static str, String)>,
+}
+
+impl Report {
+    fn new(err: &Error, req: &HttpRequest, inner: &Inner) -> Self {
+        let headers = req
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                let value = if inner.redacted.contains(name) {
+                    REDACTED.to_owned()
+                } else {
+                    String::from_utf8_lossy(value.as_bytes()).into_owned()
+                };
+
+                (name.as_str().to_owned(), value)
+            })
+            .collect();
+
+        let route_params = req
+            .match_info()
+            .iter()
+            .map(|(name, value)| (name.to_owned(), value.to_owned()))
+            .collect();
+
+        let app_data = inner
+            .app_data
+            .iter()
+            .filter_map(|(name, format)| Some((*name, format(req)?)))
+            .collect();
+
+        Self {
+            status: err.as_response_error().status_code().to_string(),
+            message: err.to_string(),
+            debug: format!("{:#?}", err),
+            sources: source_chain(err),
+            backtrace: backtrace(err),
+            method: req.method().to_string(),
+            uri: req.uri().to_string(),
+            version: format!("{:?}", req.version()),
+            headers,
+            route_pattern: req.match_pattern(),
+            route_name: req.match_name().map(ToOwned::to_owned),
+            route_params,
+            app_data,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| serde_json::json!([name, value]))
+            .collect::<Vec<_>>();
+
+        let route_params = self
+            .route_params
+            .iter()
+            .map(|(name, value)| serde_json::json!([name, value]))
+            .collect::<Vec<_>>();
+
+        let app_data = self
+            .app_data
+            .iter()
+            .map(|(name, value)| serde_json::json!([name, value]))
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "error": {
+                "status": self.status,
+                "message": self.message,
+                "debug": self.debug,
+                "sources": self.sources,
+                "backtrace": self.backtrace,
+            },
+            "request": {
+                "method": self.method,
+                "uri": self.uri,
+                "version": self.version,
+                "headers": headers,
+            },
+            "route": {
+                "pattern": self.route_pattern,
+                "name": self.route_name,
+                "params": route_params,
+            },
+            "app_data": app_data,
+        })
+        .to_string()
+    }
+
+    fn to_html(&self) -> String {
+        let mut html = String::new();
+
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title></head><body>\n\
+             <h1>{}</h1>\n<p>{}</p>\n",
+            escape_html(&self.status),
+            escape_html(&self.status),
+            escape_html(&self.message),
+        );
+
+        if !self.sources.is_empty() {
+            html.push_str("<h2>Caused by</h2>\n<ol>\n");
+            for source in &self.sources {
+                let _ = writeln!(html, "<li>{}</li>", escape_html(source));
+            }
+            html.push_str("</ol>\n");
+        }
+
+        let _ = write!(
+            html,
+            "<h2>Debug</h2>\n<pre>{}</pre>\n<h2>Request</h2>\n<pre>{} {} {}</pre>\n<table>\n",
+            escape_html(&self.debug),
+            escape_html(&self.method),
+            escape_html(&self.uri),
+            escape_html(&self.version),
+        );
+
+        for (name, value) in &self.headers {
+            let _ = writeln!(
+                html,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape_html(name),
+                escape_html(value),
+            );
+        }
+
+        let _ = write!(
+            html,
+            "</table>\n<h2>Route</h2>\n<p>Pattern: {}<br>Name: {}</p>\n<table>\n",
+            escape_html(self.route_pattern.as_deref().unwrap_or("(none)")),
+            escape_html(self.route_name.as_deref().unwrap_or("(none)")),
+        );
+
+        for (name, value) in &self.route_params {
+            let _ = writeln!(
+                html,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape_html(name),
+                escape_html(value),
+            );
+        }
+
+        html.push_str("</table>\n<h2>App Data</h2>\n");
+
+        for (name, value) in &self.app_data {
+            let _ = writeln!(
+                html,
+                "<h3><code>{}</code></h3>\n<pre>{}</pre>",
+                escape_html(name),
+                escape_html(value),
+            );
+        }
+
+        let _ = write!(
+            html,
+            "<h2>Backtrace</h2>\n<pre>{}</pre>\n</body></html>\n",
+            escape_html(&self.backtrace),
+        );
+
+        html
+    }
+}
+
+/// Returns the messages of the error's sources, outermost first.
+///
+/// `ResponseError` does not require `std::error::Error` so sources can only be found for known
+/// error types and boxed standard errors.
+fn source_chain(err: &Error) -> Vec<String> {
+    let mut sources = Vec::new();
+    let mut source = as_std_error(err).and_then(StdError::source);
+
+    while let Some(err) = source {
+        sources.push(err.to_string());
+        source = err.source();
+    }
+
+    sources
+}
+
+fn as_std_error(err: &Error) -> Option<&(dyn StdError + 'static)> {
+    macro_rules! downcast_std_error {
+        ($($ty:ty),+ $(,)?) => {
+            $(
+                if let Some(err) = err.as_error::<$ty>() {
+                    return Some(err);
+                }
+            )+
+        };
+    }
+
+    downcast_std_error!(
+        JsonPayloadError,
+        UrlencodedError,
+        PathError,
+        QueryPayloadError,
+        ReadlinesError,
+        UrlGenerationError,
+        BlockingError,
+        ValidationErrors,
+        Problem,
+        PayloadError,
+        ParseError,
+        ContentTypeError,
+        std::io::Error,
+        serde_json::Error,
+    );
+
+    err.as_error::<Box<dyn StdError>>()
+        .map(|err| err.as_ref() as &(dyn StdError + 'static))
+}
+
+#[cfg(debug_assertions)]
+fn backtrace(err: &Error) -> String {
+    err.backtrace().to_string()
+}
+
+#[cfg(not(debug_assertions))]
+fn backtrace(_err: &Error) -> String {
+    String::new()
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    #[actix_rt::test]
+    async fn renders_json_report() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(
+                    DebugErrors::new()
+                        .redact_header(HeaderName::from_static("x-secret"))
+                        .show_app_data::<web::Data<u32>>()
+                        .show_app_data::<web::Data<String>>(),
+                )
+                .app_data(web::Data::new(42u32))
+                .service(
+                    web::resource("/user/{id}")
+                        .name("user")
+                        .route(web::post().to(|_: web::Json<u32>| async { "" })),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/user/1")
+            .insert_header((header::ACCEPT, "application/json"))
+            .insert_header((header::CONTENT_TYPE, "application/json"))
+            .insert_header((header::AUTHORIZATION, "Bearer hunter2"))
+            .insert_header(("x-secret", "shh"))
+            .insert_header(("x-public", "hello"))
+            .set_payload("{")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert!(body["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Json deserialize error"));
+        assert_eq!(body["error"]["sources"].as_array().unwrap().len(), 1);
+        assert!(!body["error"]["backtrace"].as_str().unwrap().is_empty());
+        assert_eq!(body["request"]["method"], "POST");
+        assert_eq!(body["route"]["pattern"], "/user/{id}");
+        assert_eq!(body["route"]["name"], "user");
+        assert_eq!(body["route"]["params"], serde_json::json!([["id", "1"]]));
+
+        // only registered app data that was found is shown
+        let app_data = body["app_data"].as_array().unwrap();
+        assert_eq!(app_data.len(), 1);
+        assert!(app_data[0][0].as_str().unwrap().contains("Data<u32>"));
+        assert!(app_data[0][1].as_str().unwrap().contains("42"));
+
+        let headers = body["request"]["headers"].as_array().unwrap();
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|pair| pair[0] == name)
+                .map(|pair| pair[1].as_str().unwrap().to_owned())
+                .unwrap()
+        };
+        assert_eq!(header("authorization"), REDACTED);
+        assert_eq!(header("x-secret"), REDACTED);
+        assert_eq!(header("x-public"), "hello");
+    }
+
+    #[actix_rt::test]
+    async fn renders_html_report() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(DebugErrors::new())
+                .route(
+                    "/",
+                    web::get().to(|| async {
+                        Err::<HttpResponse, _>(crate::error::ErrorInternalServerError("<oops>"))
+                    }),
+                )
+                .route("/ok", web::get().to(HttpResponse::NotFound)),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        let body = test::read_body(res).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains("&lt;oops&gt;"));
+        assert!(!body.contains("<oops>"));
+
+        // responses without a source error are passed through
+        let req = TestRequest::get().uri("/ok").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(test::read_body(res).await.is_empty());
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn backtraces_only_captured_by_middleware() {
+        use std::backtrace::BacktraceStatus;
+
+        let err = crate::error::ErrorNotFound("missing");
+        assert_eq!(err.backtrace().status(), BacktraceStatus::Disabled);
+
+        let err = error::capture_backtraces(|| crate::error::ErrorNotFound("missing"));
+        assert_eq!(err.backtrace().status(), BacktraceStatus::Captured);
+
+        let err = crate::error::ErrorNotFound("missing");
+        assert_eq!(err.backtrace().status(), BacktraceStatus::Disabled);
+    }
+}
@@ -1,6 +1,7 @@
 //! For middleware documentation, see [`ErrorHandlers`].
 
 use std::{
+    any::TypeId,
     future::Future,
     pin::Pin,
     rc::Rc,
@@ -16,7 +17,7 @@ use crate::{
     body::EitherBody,
     dev::{ServiceRequest, ServiceResponse},
     http::StatusCode,
-    Error, Result,
+    Error, ResponseError, Result,
 };
 
 /// Return type for [`ErrorHandlers`] custom handlers.
@@ -30,6 +31,8 @@ pub enum ErrorHandlerResponse<B> {
 
 type ErrorHandler<B> = dyn Fn(ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>>;
 
+type TypedErrorHandler<B> = dyn Fn(&Error, ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>>;
+
 type DefaultHandler<B> = Option<Rc<ErrorHandler<B>>>;
 
 /// Middleware for registering custom status code based error handlers.
@@ -46,6 +49,10 @@ type DefaultHandler<B> = Option<Rc<ErrorHandler<B>>>;
 /// [`ErrorHandlers::default_handler_client()`] and [`ErrorHandlers::default_handler_server()`]
 /// methods, respectively.
 ///
+/// To handle responses created from a specific error type, use the
+/// [`ErrorHandlers::typed_handler()`] method. Typed handlers take precedence over status code based
+/// handlers.
+///
 /// Any response with a status code that isn't covered by a specific handler or a default handler
 /// will pass by unchanged by this middleware.
 ///
@@ -179,20 +186,56 @@ type DefaultHandler<B> = Option<Rc<ErrorHandler<B>>>;
 ///     )
 ///     .service(web::resource("/").route(web::get().to(HttpResponse::InternalServerError)));
 /// ```
+///
+/// Handling a specific error type:
+///
+/// ```
+/// use actix_web::{
+///     dev::ServiceResponse,
+///     error::JsonPayloadError,
+///     middleware::{ErrorHandlerResponse, ErrorHandlers},
+///     web, App, HttpResponse, Result,
+/// };
+///
+/// fn handle_json_error<B>(
+///     err: &JsonPayloadError,
+///     res: ServiceResponse<B>,
+/// ) -> Result<ErrorHandlerResponse<B>> {
+///     let (req, _res) = res.into_parts();
+///
+///     let res = match err {
+///         JsonPayloadError::Deserialize(err) => {
+///             HttpResponse::BadRequest().body(format!("invalid JSON: {err}"))
+///         }
+///         _ => HttpResponse::BadRequest().finish(),
+///     };
+///
+///     let res = ServiceResponse::new(req, res).map_into_right_body();
+///     Ok(ErrorHandlerResponse::Response(res))
+/// }
+///
+/// let app = App::new()
+///     .wrap(ErrorHandlers::new().typed_handler(handle_json_error))
+///     .service(web::resource("/").route(web::post().to(|_: web::Json<u32>| async { "" })));
+/// ```
 pub struct ErrorHandlers<B> {
     default_client: DefaultHandler<B>,
     default_server: DefaultHandler<B>,
     handlers: Handlers<B>,
+    typed_handlers: TypedHandlers<B>,
 }
 
 type Handlers<B> = Rc<AHashMap<StatusCode, Box<ErrorHandler<B>>>>;
 
+type TypedHandlers<B> = Rc<AHashMap<TypeId, Box<TypedErrorHandler<B>>>>;
+
 impl<B> Default for ErrorHandlers<B> {
     fn default() -> Self {
         ErrorHandlers {
             default_client: Default::default(),
             default_server: Default::default(),
             handlers: Default::default(),
+            typed_handlers: Default::default(),
         }
     }
 }
@@ -214,6 +257,34 @@ impl<B> ErrorHandlers<B> {
         self
     }
 
+    /// Register error handler for responses created from errors of type `E`.
+    ///
+    /// The handler is called for any response whose [source error](crate::HttpResponse::error)
+    /// is an `E`, regardless of status code. The error stays the source error of the response
+    /// returned by the handler, unless that response has a source error of its own, so that it is
+    /// still visible to outer middleware like [`Logger`](crate::middleware::Logger).
+    ///
+    /// Typed handlers are checked before handlers registered for status codes. Only the outermost
+    /// error type is matched; errors wrapped in, for example, [`InternalError`] are not unwrapped.
+    ///
+    /// [`InternalError`]: crate::error::InternalError
+    pub fn typed_handler<E, F>(mut self, handler: F) -> Self
+    where
+        E: ResponseError + 'static,
+        F: Fn(&E, ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>> + 'static,
+    {
+        let handler = move |err: &Error, res: ServiceResponse<B>| match err.as_error::<E>() {
+            Some(err) => handler(err, res),
+            // handlers are looked up by the error's type ID so this is never reached
+            None => Ok(ErrorHandlerResponse::Response(res.map_into_left_body())),
+        };
+
+        Rc::get_mut(&mut self.typed_handlers)
+            .unwrap()
+            .insert(TypeId::of::<E>(), Box::new(handler));
+        self
+    }
+
     /// Register a default error handler.
     ///
     /// Any request with a status code that hasn't been given a specific other handler (by calling
@@ -289,6 +360,7 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         let handlers = self.handlers.clone();
+        let typed_handlers = self.typed_handlers.clone();
         let default_client = self.default_client.clone();
         let default_server = self.default_server.clone();
         Box::pin(async move {
@@ -297,6 +369,7 @@ where
                 default_client,
                 default_server,
                 handlers,
+                typed_handlers,
             })
         })
     }
@@ -308,6 +381,7 @@ pub struct ErrorHandlersMiddleware<S, B> {
     default_client: DefaultHandler<B>,
     default_server: DefaultHandler<B>,
     handlers: Handlers<B>,
+    typed_handlers: TypedHandlers<B>,
 }
 
 impl<S, B> Service<ServiceRequest> for ErrorHandlersMiddleware<S, B>
@@ -324,6 +398,7 @@ where
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let handlers = self.handlers.clone();
+        let typed_handlers = self.typed_handlers.clone();
         let default_client = self.default_client.clone();
         let default_server = self.default_server.clone();
         let fut = self.service.call(req);
@@ -332,6 +407,7 @@ where
             default_client,
             default_server,
             handlers,
+            typed_handlers,
         }
     }
 }
@@ -348,9 +424,11 @@ pin_project! {
             default_client: DefaultHandler<B>,
             default_server: DefaultHandler<B>,
             handlers: Handlers<B>,
+            typed_handlers: TypedHandlers<B>,
         },
         ErrorHandlerFuture {
             fut: LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>>,
+            error: Option<Error>,
         },
     }
 }
@@ -368,35 +446,70 @@ where
                 default_client,
                 default_server,
                 handlers,
+                typed_handlers,
             } => {
-                let res = ready!(fut.poll(cx))?;
-                let status = res.status();
-
-                let handler = ErrorHandlers::get_handler(
-                    &status,
-                    default_client.as_mut().map(|f| Rc::as_ref(f)),
-                    default_server.as_mut().map(|f| Rc::as_ref(f)),
-                    handlers,
-                );
-                match handler {
-                    Some(handler) => match handler(res)? {
-                        ErrorHandlerResponse::Response(res) => Poll::Ready(Ok(res)),
-                        ErrorHandlerResponse::Future(fut) => {
-                            self.as_mut()
-                                .set(ErrorHandlersFuture::ErrorHandlerFuture { fut });
-
-                            self.poll(cx)
+                let mut res = ready!(fut.poll(cx))?;
+
+                let typed_handler = res
+                    .response()
+                    .error()
+                    .map(Error::cause_type_id)
+                    .and_then(|type_id| typed_handlers.get(&type_id));
+
+                let (handler_res, error) = match typed_handler {
+                    Some(handler) => {
+                        // the error is lent to the handler and attached to the response it returns
+                        let err = res.response_mut().take_error().unwrap();
+                        (handler(&err, res)?, Some(err))
+                    }
+
+                    None => {
+                        let status = res.status();
+
+                        let handler = ErrorHandlers::get_handler(
+                            &status,
+                            default_client.as_mut().map(|f| Rc::as_ref(f)),
+                            default_server.as_mut().map(|f| Rc::as_ref(f)),
+                            handlers,
+                        );
+
+                        match handler {
+                            Some(handler) => (handler(res)?, None),
+                            None => return Poll::Ready(Ok(res.map_into_left_body())),
                         }
-                    },
-                    None => Poll::Ready(Ok(res.map_into_left_body())),
+                    }
+                };
+
+                match handler_res {
+                    ErrorHandlerResponse::Response(res) => Poll::Ready(Ok(with_error(res, error))),
+                    ErrorHandlerResponse::Future(fut) => {
+                        self.as_mut()
+                            .set(ErrorHandlersFuture::ErrorHandlerFuture { fut, error });
+
+                        self.poll(cx)
+                    }
                 }
             }
 
-            ErrorHandlersProj::ErrorHandlerFuture { fut } => fut.as_mut().poll(cx),
+            ErrorHandlersProj::ErrorHandlerFuture { fut, error } => {
+                let res = ready!(fut.as_mut().poll(cx))?;
+                Poll::Ready(Ok(with_error(res, error.take())))
+            }
         }
     }
 }
 
+/// Sets the source error of a response taken by a typed handler unless it already has one.
+fn with_error<B>(mut res: ServiceResponse<B>, error: Option<Error>) -> ServiceResponse<B> {
+    if let Some(error) = error {
+        if res.response().error().is_none() {
+            res.response_mut().set_error(error);
+        }
+    }
+
+    res
+}
+
 #[cfg(test)]
 mod tests {
     use actix_service::IntoService;
@@ -407,8 +520,10 @@ mod tests {
     use super::*;
     use crate::{
         body,
+        error::JsonPayloadError,
         http::header::{HeaderValue, CONTENT_TYPE},
         test::{self, TestRequest},
+        web, App, HttpResponse,
     };
 
     #[actix_rt::test]
@@ -617,4 +732,61 @@ mod tests {
         let resp = test::call_service(&mw_specific, TestRequest::default().to_srv_request()).await;
         assert_eq!(resp.headers().get(CONTENT_TYPE).unwrap(), "0003");
     }
+
+    #[actix_rt::test]
+    async fn typed_handler() {
+        fn error_handler<B: 'static>(
+            err: &JsonPayloadError,
+            res: ServiceResponse<B>,
+        ) -> Result<ErrorHandlerResponse<B>> {
+            let body = match err {
+                JsonPayloadError::ContentType => "bad content type",
+                _ => "other json error",
+            };
+
+            Ok(ErrorHandlerResponse::Future(Box::pin(async move {
+                let (req, _) = res.into_parts();
+                let res = HttpResponse::UnsupportedMediaType().body(body);
+                Ok(ServiceResponse::new(req, res).map_into_right_body())
+            })))
+        }
+
+        #[allow(clippy::unnecessary_wraps)]
+        fn status_handler<B>(mut res: ServiceResponse<B>) -> Result<ErrorHandlerResponse<B>> {
+            res.response_mut()
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static("0001"));
+            Ok(ErrorHandlerResponse::Response(res.map_into_left_body()))
+        }
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(
+                    ErrorHandlers::new()
+                        .handler(StatusCode::BAD_REQUEST, status_handler)
+                        .typed_handler(error_handler),
+                )
+                .route("/json", web::post().to(|_: web::Json<u32>| async { "" }))
+                .route(
+                    "/query",
+                    web::get().to(|_: web::Query<(u32,)>| async { "" }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::post().uri("/json").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        // error stays attached for outer middleware
+        let err = res.response().error().unwrap();
+        assert!(err.as_error::<JsonPayloadError>().is_some());
+
+        assert_eq!(test::read_body(res).await, "bad content type");
+
+        let req = TestRequest::get().uri("/query?a=b").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "0001");
+    }
 }
@@ -0,0 +1,182 @@
+//! For middleware documentation, see [`ExpectContinue`].
+
+use std::{future::Future, rc::Rc};
+
+use actix_utils::future::{ready, Ready};
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    dev::{forward_ready, Service, Transform},
+    http::header,
+    service::{ServiceRequest, ServiceResponse},
+    Error,
+};
+
+type CheckFn = dyn Fn(&ServiceRequest) -> LocalBoxFuture<'static, Result<(), Error>>;
+
+/// Middleware for deciding whether to accept request payloads sent with `Expect: 100-continue`.
+///
+/// HTTP/1.1 clients sending large payloads can include an `Expect: 100-continue` header and wait
+/// for a `100 Continue` response before sending the payload. When
+/// [`HttpServer::defer_continue()`](crate::HttpServer::defer_continue) is enabled, this response is
+/// only sent once the payload is first read, so a request can be rejected before the payload is
+/// sent at all. Otherwise, the response is sent before the app is called and this middleware can
+/// only reject the request after the client has started sending its payload.
+///
+/// The check is called with each request that has an `Expect: 100-continue` header before the
+/// wrapped service. It has access to the request head, extensions and app data and returns a
+/// future resolving to `Ok(())` to continue handling the request or an error to respond with
+/// instead. Requests without the header are passed to the wrapped service directly.
+///
+/// Since the check runs as middleware, it can be registered for the whole app or for individual
+/// scopes, resources and routes. A client may still send the payload of a rejected request, so the
+/// connection is closed after responding to it.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error, http::header, middleware::ExpectContinue, web, App, HttpResponse};
+///
+/// const MAX_UPLOAD_SIZE: u64 = 10 * 1024 * 1024;
+///
+/// let app = App::new().service(
+///     web::resource("/upload")
+///         .wrap(ExpectContinue::new(|req| {
+///             let len = req
+///                 .headers()
+///                 .get(header::CONTENT_LENGTH)
+///                 .and_then(|len| len.to_str().ok()?.parse::<u64>().ok());
+///
+///             async move {
+///                 match len {
+///                     Some(len) if len <= MAX_UPLOAD_SIZE => Ok(()),
+///                     _ => Err(error::ErrorPayloadTooLarge("upload is too large")),
+///                 }
+///             }
+///         }))
+///         .route(web::post().to(|body: web::Bytes| async move {
+///             HttpResponse::Ok().body(format!("received {} bytes", body.len()))
+///         })),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct ExpectContinue {
+    check: Rc<CheckFn>,
+}
+
+impl ExpectContinue {
+    /// Constructs new middleware that uses `check` to decide whether to accept request payloads.
+    pub fn new<F, Fut>(check: F) -> Self
+    where
+        F: Fn(&ServiceRequest) -> Fut + 'static,
+        Fut: Future<Output = Result<(), Error>> + 'static,
+    {
+        Self {
+            check: Rc::new(move |req| Box::pin(check(req))),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ExpectContinue
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = ExpectContinueMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ExpectContinueMiddleware {
+            service: Rc::new(service),
+            check: Rc::clone(&self.check),
+        }))
+    }
+}
+
+pub struct ExpectContinueMiddleware<S> {
+    service: Rc<S>,
+    check: Rc<CheckFn>,
+}
+
+impl<S, B> Service<ServiceRequest> for ExpectContinueMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let expects_continue = req
+            .headers()
+            .get(header::EXPECT)
+            .is_some_and(|val| val.as_bytes().eq_ignore_ascii_case(b"100-continue"));
+
+        if !expects_continue {
+            return Box::pin(self.service.call(req));
+        }
+
+        let check = (self.check)(&req);
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            check.await?;
+            service.call(req).await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error,
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    #[actix_rt::test]
+    async fn check_expect_requests() {
+        let app = test::init_service(
+            App::new()
+                .wrap(ExpectContinue::new(|req| {
+                    let allowed = req.headers().contains_key(header::AUTHORIZATION);
+
+                    async move {
+                        if allowed {
+                            Ok(())
+                        } else {
+                            Err(error::ErrorUnauthorized("missing credentials"))
+                        }
+                    }
+                }))
+                .route("/", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header((header::EXPECT, "100-continue"))
+            .to_request();
+        let err = test::try_call_service(&app, req).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            StatusCode::UNAUTHORIZED
+        );
+
+        let req = TestRequest::post()
+            .insert_header((header::EXPECT, "100-continue"))
+            .insert_header((header::AUTHORIZATION, "Bearer token"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // check is only called for requests expecting `100 Continue`
+        let req = TestRequest::post().to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+}
@@ -26,7 +26,7 @@ use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
 use crate::{
     body::{BodySize, MessageBody},
-    http::header::HeaderName,
+    http::header::{HeaderMap, HeaderName},
     service::{ServiceRequest, ServiceResponse},
     Error, Result,
 };
@@ -453,6 +453,14 @@ impl<B: MessageBody> MessageBody for StreamLog<B> {
             None => Poll::Ready(None),
         }
     }
+
+    #[inline]
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+        self.project().body.poll_trailers(cx)
+    }
 }
 
 /// A formatting style for the `Logger` consisting of multiple concatenated `FormatText` items.
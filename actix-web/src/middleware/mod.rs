@@ -221,6 +221,7 @@ mod compat;
 mod condition;
 mod default_headers;
 mod err_handlers;
+mod expect_continue;
 mod logger;
 #[cfg(test)]
 mod noop;
@@ -233,15 +234,20 @@ pub use self::{
     condition::Condition,
     default_headers::DefaultHeaders,
     err_handlers::{ErrorHandlerResponse, ErrorHandlers},
+    expect_continue::ExpectContinue,
     logger::Logger,
     normalize::{NormalizePath, TrailingSlash},
 };
 
 #[cfg(feature = "__compress")]
 mod compress;
+#[cfg(feature = "debug-errors")]
+mod debug_errors;
 
 #[cfg(feature = "__compress")]
 pub use self::compress::Compress;
+#[cfg(feature = "debug-errors")]
+pub use self::debug_errors::DebugErrors;
 
 #[cfg(test)]
 mod tests {
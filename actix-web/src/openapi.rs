@@ -0,0 +1,1194 @@
+//! OpenAPI document generation.
+//!
+//! When the `openapi` feature is enabled, the routing tree of an [`App`](crate::App) can be
+//! described as an [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document. Paths and
+//! their parameters are taken from registered resource patterns and HTTP methods are taken from
+//! method guards. A pattern with optional segments is described as several paths, one with and
+//! one without each optional segment.
+//!
+//! Everything else about an operation is described by an [`Operation`], attached to a route or
+//! resource using [`Route::openapi`](crate::Route::openapi) or
+//! [`Resource::openapi`](crate::Resource::openapi).
+//!
+//! Routes defined with the routing macros (like [`get`](macro@crate::get)) have an operation
+//! attached automatically. Its summary and description are taken from the handler's doc comment
+//! and its parameters, request body and responses are taken from the handler's argument and
+//! return types, where they implement [`OperationInput`] or [`OperationOutput`].
+//!
+//! Types used in extractors and responses describe themselves using the [`Schema`] trait.
+//!
+//! The document is served by registering an [`OpenApi`] service.
+//!
+//! # Examples
+//! ```
+//! use actix_web::{
+//!     openapi::{OpenApi, Schema},
+//!     web, App,
+//! };
+//! use serde::Deserialize;
+//! use serde_json::{json, Value};
+//!
+//! #[derive(Deserialize)]
+//! struct Search {
+//!     q: String,
+//!     limit: Option<u32>,
+//! }
+//!
+//! impl Schema for Search {
+//!     fn schema() -> Value {
+//!         json!({
+//!             "type": "object",
+//!             "properties": { "q": String::schema(), "limit": u32::schema() },
+//!             "required": ["q"],
+//!         })
+//!     }
+//! }
+//!
+//! /// Search for users.
+//! #[actix_web::get("/users")]
+//! async fn search(query: web::Query<Search>) -> web::Json<Vec<String>> {
+//!     web::Json(vec![query.q.clone()])
+//! }
+//!
+//! let app = App::new()
+//!     .service(search)
+//!     .service(OpenApi::new("/openapi.json").title("Users").version("1.0.0"));
+//! ```
+
+use std::{
+    cell::OnceCell,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque},
+    future,
+    marker::PhantomData,
+    rc::Rc,
+    sync::Arc,
+};
+
+use actix_http::Method;
+use bytes::Bytes;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    dev::{AppService, HttpServiceFactory},
+    guard,
+    rmap::ResourceMap,
+    web, Either, HttpRequest, HttpResponse, Resource,
+};
+
+/// A type that can be described using a JSON Schema.
+///
+/// Implementations are provided for primitive types, strings and common collections. Types that
+/// are used in [`Json`](web::Json), [`Form`](web::Form), [`Query`](web::Query) or
+/// [`Path`](web::Path) extractors (or returned in `Json` responses) should implement this trait
+/// in order to be documented.
+pub trait Schema {
+    /// Returns a JSON Schema describing the type.
+    fn schema() -> Value;
+}
+
+macro_rules! schema_impl {
+    ($($ty:ty => $schema:tt),* $(,)?) => {
+        $(
+            impl Schema for $ty {
+                fn schema() -> Value {
+                    json!($schema)
+                }
+            }
+        )*
+    };
+}
+
+schema_impl! {
+    bool => { "type": "boolean" },
+    i8 => { "type": "integer", "format": "int32" },
+    i16 => { "type": "integer", "format": "int32" },
+    i32 => { "type": "integer", "format": "int32" },
+    i64 => { "type": "integer", "format": "int64" },
+    i128 => { "type": "integer" },
+    isize => { "type": "integer", "format": "int64" },
+    u8 => { "type": "integer", "format": "int32", "minimum": 0 },
+    u16 => { "type": "integer", "format": "int32", "minimum": 0 },
+    u32 => { "type": "integer", "format": "int64", "minimum": 0 },
+    u64 => { "type": "integer", "format": "int64", "minimum": 0 },
+    u128 => { "type": "integer", "minimum": 0 },
+    usize => { "type": "integer", "format": "int64", "minimum": 0 },
+    f32 => { "type": "number", "format": "float" },
+    f64 => { "type": "number", "format": "double" },
+    char => { "type": "string", "minLength": 1, "maxLength": 1 },
+    str => { "type": "string" },
+    String => { "type": "string" },
+    () => { "type": "null" },
+    Value => {},
+}
+
+impl<T: Schema + ?Sized> Schema for &T {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: Schema + ?Sized> Schema for Box<T> {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: Schema + ?Sized> Schema for Rc<T> {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: Schema + ?Sized> Schema for Arc<T> {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: Schema> Schema for Option<T> {
+    fn schema() -> Value {
+        T::schema()
+    }
+}
+
+impl<T: Schema> Schema for [T] {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema() })
+    }
+}
+
+impl<T: Schema> Schema for Vec<T> {
+    fn schema() -> Value {
+        <[T]>::schema()
+    }
+}
+
+impl<T: Schema> Schema for VecDeque<T> {
+    fn schema() -> Value {
+        <[T]>::schema()
+    }
+}
+
+impl<T: Schema, S> Schema for HashSet<T, S> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema(), "uniqueItems": true })
+    }
+}
+
+impl<T: Schema> Schema for BTreeSet<T> {
+    fn schema() -> Value {
+        json!({ "type": "array", "items": T::schema(), "uniqueItems": true })
+    }
+}
+
+impl<V: Schema, S> Schema for HashMap<String, V, S> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": V::schema() })
+    }
+}
+
+impl<V: Schema> Schema for BTreeMap<String, V> {
+    fn schema() -> Value {
+        json!({ "type": "object", "additionalProperties": V::schema() })
+    }
+}
+
+macro_rules! schema_tuple_impl {
+    ($len:literal; $($ty:ident),+) => {
+        impl<$($ty: Schema),+> Schema for ($($ty,)+) {
+            fn schema() -> Value {
+                json!({
+                    "type": "array",
+                    "prefixItems": [$($ty::schema()),+],
+                    "minItems": $len,
+                    "maxItems": $len,
+                })
+            }
+        }
+    };
+}
+
+schema_tuple_impl!(1; A);
+schema_tuple_impl!(2; A, B);
+schema_tuple_impl!(3; A, B, C);
+schema_tuple_impl!(4; A, B, C, D);
+schema_tuple_impl!(5; A, B, C, D, E);
+schema_tuple_impl!(6; A, B, C, D, E, F);
+
+/// Location of an operation [`Parameter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterIn {
+    /// Dynamic segment of the path.
+    Path,
+
+    /// Query string parameter.
+    Query,
+
+    /// Request header.
+    Header,
+
+    /// Request cookie.
+    Cookie,
+}
+
+impl ParameterIn {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ParameterIn::Path => "path",
+            ParameterIn::Query => "query",
+            ParameterIn::Header => "header",
+            ParameterIn::Cookie => "cookie",
+        }
+    }
+}
+
+/// A parameter of an [`Operation`].
+///
+/// Path parameters do not usually need to be added by hand since they are derived from the
+/// resource pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    name: String,
+    location: ParameterIn,
+    required: bool,
+    description: Option<String>,
+    schema: Value,
+}
+
+impl Parameter {
+    /// Constructs a new string parameter.
+    ///
+    /// Path parameters are always required; other parameters are optional by default.
+    pub fn new(name: impl Into<String>, location: ParameterIn) -> Self {
+        Self {
+            name: name.into(),
+            location,
+            required: location == ParameterIn::Path,
+            description: None,
+            schema: String::schema(),
+        }
+    }
+
+    /// Sets whether the parameter is required.
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = required || self.location == ParameterIn::Path;
+        self
+    }
+
+    /// Sets the description of the parameter.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets the schema of the parameter's value.
+    pub fn schema(mut self, schema: Value) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    fn to_json(&self) -> Value {
+        let mut param = Map::new();
+        param.insert("name".to_owned(), json!(self.name));
+        param.insert("in".to_owned(), json!(self.location.as_str()));
+
+        if let Some(description) = &self.description {
+            param.insert("description".to_owned(), json!(description));
+        }
+
+        param.insert("required".to_owned(), json!(self.required));
+        param.insert("schema".to_owned(), self.schema.clone());
+
+        Value::Object(param)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RequestBody {
+    content_type: String,
+    schema: Value,
+    required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Response {
+    description: String,
+    content: Option<(String, Value)>,
+}
+
+/// Documentation of a single API operation (a path and method pair).
+///
+/// # Examples
+/// ```
+/// use actix_web::{
+///     openapi::{Operation, Parameter, ParameterIn},
+///     web,
+/// };
+///
+/// let op = Operation::new()
+///     .summary("List users")
+///     .tag("users")
+///     .input::<web::Json<Vec<String>>>()
+///     .parameter(Parameter::new("X-Request-Id", ParameterIn::Header))
+///     .response(404, "No users exist");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Operation {
+    summary: Option<String>,
+    description: Option<String>,
+    operation_id: Option<String>,
+    tags: Vec<String>,
+    deprecated: bool,
+    hidden: bool,
+    parameters: Vec<Parameter>,
+    path_schema: Option<Value>,
+    request_body: Option<RequestBody>,
+    responses: BTreeMap<u16, Response>,
+}
+
+impl Operation {
+    /// Constructs a new, empty operation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets a short summary of what the operation does.
+    pub fn summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Sets a verbose explanation of the operation. Markdown is allowed.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Sets a unique identifier of the operation.
+    pub fn operation_id(mut self, operation_id: impl Into<String>) -> Self {
+        self.operation_id = Some(operation_id.into());
+        self
+    }
+
+    /// Adds a tag, used for logical grouping of operations.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Marks the operation as deprecated.
+    pub fn deprecated(mut self) -> Self {
+        self.deprecated = true;
+        self
+    }
+
+    /// Excludes the operation from generated documents.
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Adds a parameter.
+    ///
+    /// A parameter with the same name and location as an existing one replaces it.
+    pub fn parameter(mut self, parameter: Parameter) -> Self {
+        self.parameters
+            .retain(|param| param.name != parameter.name || param.location != parameter.location);
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Sets the schema of path parameters.
+    ///
+    /// The properties of an object schema, or the items of a tuple schema, are matched to the
+    /// dynamic segments of the resource pattern by name or by position, respectively. Any other
+    /// schema is used for the only dynamic segment. Segments that are not matched are documented
+    /// as strings.
+    pub fn path_parameters(mut self, schema: Value) -> Self {
+        self.path_schema = Some(schema);
+        self
+    }
+
+    /// Sets the request body.
+    pub fn request_body(
+        mut self,
+        content_type: impl Into<String>,
+        schema: Value,
+        required: bool,
+    ) -> Self {
+        self.request_body = Some(RequestBody {
+            content_type: content_type.into(),
+            schema,
+            required,
+        });
+        self
+    }
+
+    /// Adds a response without content.
+    ///
+    /// # Panics
+    /// Panics if `status` is not in the range 100 to 599.
+    pub fn response(self, status: u16, description: impl Into<String>) -> Self {
+        self.add_response(status, description.into(), None)
+    }
+
+    /// Adds a response with content.
+    ///
+    /// # Panics
+    /// Panics if `status` is not in the range 100 to 599.
+    pub fn response_content(
+        self,
+        status: u16,
+        description: impl Into<String>,
+        content_type: impl Into<String>,
+        schema: Value,
+    ) -> Self {
+        self.add_response(
+            status,
+            description.into(),
+            Some((content_type.into(), schema)),
+        )
+    }
+
+    /// Describes the operation's inputs using an extractor type.
+    pub fn input<T: OperationInput>(self) -> Self {
+        T::describe(self)
+    }
+
+    /// Describes the operation's responses using a responder type.
+    pub fn output<T: OperationOutput>(self) -> Self {
+        T::describe(self)
+    }
+
+    fn add_response(
+        mut self,
+        status: u16,
+        description: String,
+        content: Option<(String, Value)>,
+    ) -> Self {
+        assert!(
+            (100..600).contains(&status),
+            "response status must be in the range 100 to 599"
+        );
+
+        self.responses.insert(
+            status,
+            Response {
+                description,
+                content,
+            },
+        );
+        self
+    }
+
+    /// Returns the schema of the path parameter `name`, at position `idx` of `count` parameters.
+    fn path_param_schema(&self, name: &str, idx: usize, count: usize) -> Value {
+        let schema = match &self.path_schema {
+            Some(schema) => schema,
+            None => return String::schema(),
+        };
+
+        let found = if let Some(properties) = schema.get("properties") {
+            properties.get(name)
+        } else if let Some(items) = schema.get("prefixItems") {
+            items.get(idx)
+        } else if count == 1 {
+            Some(schema)
+        } else {
+            None
+        };
+
+        found.cloned().unwrap_or_else(String::schema)
+    }
+
+    fn to_json(&self, path_params: &[String]) -> Value {
+        let mut op = Map::new();
+
+        if !self.tags.is_empty() {
+            op.insert("tags".to_owned(), json!(self.tags));
+        }
+
+        if let Some(summary) = &self.summary {
+            op.insert("summary".to_owned(), json!(summary));
+        }
+
+        if let Some(description) = &self.description {
+            op.insert("description".to_owned(), json!(description));
+        }
+
+        if let Some(operation_id) = &self.operation_id {
+            op.insert("operationId".to_owned(), json!(operation_id));
+        }
+
+        let mut params = path_params
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                Parameter::new(name, ParameterIn::Path).schema(self.path_param_schema(
+                    name,
+                    idx,
+                    path_params.len(),
+                ))
+            })
+            .filter(|param| {
+                !self
+                    .parameters
+                    .iter()
+                    .any(|other| other.location == ParameterIn::Path && other.name == param.name)
+            })
+            .collect::<Vec<_>>();
+
+        params.extend(self.parameters.iter().cloned());
+
+        if !params.is_empty() {
+            let params = params.iter().map(Parameter::to_json).collect();
+            op.insert("parameters".to_owned(), Value::Array(params));
+        }
+
+        if let Some(body) = &self.request_body {
+            op.insert(
+                "requestBody".to_owned(),
+                json!({
+                    "content": { &body.content_type: { "schema": body.schema } },
+                    "required": body.required,
+                }),
+            );
+        }
+
+        if !self.responses.is_empty() {
+            let responses = self
+                .responses
+                .iter()
+                .map(|(status, res)| {
+                    let mut json = json!({ "description": res.description });
+
+                    if let Some((content_type, schema)) = &res.content {
+                        json["content"] = json!({ content_type: { "schema": schema } });
+                    }
+
+                    (status.to_string(), json)
+                })
+                .collect();
+
+            op.insert("responses".to_owned(), Value::Object(responses));
+        }
+
+        if self.deprecated {
+            op.insert("deprecated".to_owned(), json!(true));
+        }
+
+        Value::Object(op)
+    }
+}
+
+/// An extractor that can describe the inputs of an [`Operation`].
+pub trait OperationInput {
+    /// Adds the parameters or request body extracted by this type to `operation`.
+    fn describe(operation: Operation) -> Operation;
+}
+
+impl<T: Schema> OperationInput for web::Json<T> {
+    fn describe(operation: Operation) -> Operation {
+        operation.request_body("application/json", T::schema(), true)
+    }
+}
+
+impl<T: Schema> OperationInput for web::Form<T> {
+    fn describe(operation: Operation) -> Operation {
+        operation.request_body("application/x-www-form-urlencoded", T::schema(), true)
+    }
+}
+
+impl<T: Schema> OperationInput for web::Query<T> {
+    fn describe(mut operation: Operation) -> Operation {
+        let schema = T::schema();
+
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (name, schema) in properties {
+                operation = operation.parameter(
+                    Parameter::new(name, ParameterIn::Query)
+                        .required(required.contains(&json!(name)))
+                        .schema(schema.clone()),
+                );
+            }
+        }
+
+        operation
+    }
+}
+
+impl<T: Schema> OperationInput for web::Path<T> {
+    fn describe(operation: Operation) -> Operation {
+        operation.path_parameters(T::schema())
+    }
+}
+
+impl<T: OperationInput> OperationInput for crate::types::Valid<T> {
+    fn describe(operation: Operation) -> Operation {
+        T::describe(operation)
+    }
+}
+
+/// A responder that can describe the responses of an [`Operation`].
+pub trait OperationOutput {
+    /// Adds the responses produced by this type to `operation`.
+    fn describe(operation: Operation) -> Operation;
+}
+
+impl<T: Schema> OperationOutput for web::Json<T> {
+    fn describe(operation: Operation) -> Operation {
+        operation.response_content(200, "OK", "application/json", T::schema())
+    }
+}
+
+impl OperationOutput for String {
+    fn describe(operation: Operation) -> Operation {
+        operation.response_content(200, "OK", "text/plain", String::schema())
+    }
+}
+
+impl OperationOutput for &'static str {
+    fn describe(operation: Operation) -> Operation {
+        String::describe(operation)
+    }
+}
+
+impl<T: OperationOutput, E> OperationOutput for Result<T, E> {
+    fn describe(operation: Operation) -> Operation {
+        T::describe(operation)
+    }
+}
+
+impl<L: OperationOutput, R: OperationOutput> OperationOutput for Either<L, R> {
+    fn describe(operation: Operation) -> Operation {
+        R::describe(L::describe(operation))
+    }
+}
+
+/// Service that serves the OpenAPI document of the application it is registered in.
+///
+/// The document describes every resource of the application that responds to a fixed set of
+/// HTTP methods, except for the document itself and operations marked as
+/// [hidden](Operation::hidden).
+///
+/// # Examples
+/// ```
+/// use actix_web::{openapi::OpenApi, App};
+///
+/// let app = App::new().service(
+///     OpenApi::new("/openapi.json")
+///         .title("Pet Store")
+///         .version("1.0.0"),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenApi {
+    path: String,
+    title: String,
+    version: String,
+    description: Option<String>,
+}
+
+impl OpenApi {
+    /// Constructs a new service that serves the document at `path`.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            title: "API".to_owned(),
+            version: "0.1.0".to_owned(),
+            description: None,
+        }
+    }
+
+    /// Sets the title of the API. Defaults to "API".
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the version of the API. Defaults to "0.1.0".
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = version.into();
+        self
+    }
+
+    /// Sets the description of the API.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Generates the OpenAPI document for the resources in `rmap`.
+    ///
+    /// The resource map of an application can be obtained from any of its requests using
+    /// [`HttpRequest::resource_map`].
+    pub fn document(&self, rmap: &ResourceMap) -> Value {
+        let mut info = json!({ "title": self.title, "version": self.version });
+
+        if let Some(description) = &self.description {
+            info["description"] = json!(description);
+        }
+
+        json!({
+            "openapi": "3.1.0",
+            "info": info,
+            "paths": collect_paths(rmap),
+        })
+    }
+}
+
+impl HttpServiceFactory for OpenApi {
+    fn register(self, config: &mut AppService) {
+        let path = self.path.clone();
+
+        // the resource map is only complete once all services are registered, so the document is
+        // serialized on the first request and reused afterwards
+        let openapi = Rc::new(self);
+        let doc = Rc::new(OnceCell::new());
+
+        Resource::new(path)
+            .guard(guard::Get())
+            .openapi(Operation::new().hidden())
+            .to(move |req: HttpRequest| {
+                let doc = doc.get_or_init(|| {
+                    let doc = openapi.document(req.resource_map());
+                    Bytes::from(serde_json::to_vec(&doc).unwrap())
+                });
+
+                future::ready(
+                    HttpResponse::Ok()
+                        .content_type(mime::APPLICATION_JSON)
+                        .body(doc.clone()),
+                )
+            })
+            .register(config);
+    }
+}
+
+/// Operation methods, in the order they appear in path item objects.
+const METHODS: &[Method] = &[
+    Method::GET,
+    Method::PUT,
+    Method::POST,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::HEAD,
+    Method::PATCH,
+    Method::TRACE,
+];
+
+fn collect_paths(rmap: &ResourceMap) -> Map<String, Value> {
+    let mut paths = Map::new();
+
+    for route in rmap.routes() {
+        let (methods, operation) = match (route.methods(), &route.operation) {
+            (Some(methods), Some(op)) if !op.hidden => (methods, Some(&**op)),
+            (Some(methods), None) => (methods, None),
+            _ => continue,
+        };
+
+        let methods = METHODS
+            .iter()
+            .filter(|method| methods.contains(method))
+            .collect::<Vec<_>>();
+
+        if methods.is_empty() {
+            continue;
+        }
+
+        for (path, params) in openapi_paths(route.pattern()) {
+            let item = paths
+                .entry(path)
+                .or_insert_with(|| json!({}))
+                .as_object_mut()
+                .unwrap();
+
+            for method in &methods {
+                let key = method.as_str().to_ascii_lowercase();
+
+                if item.contains_key(&key) {
+                    continue;
+                }
+
+                let op = match operation {
+                    Some(op) => op.to_json(&params),
+                    None => Operation::new().to_json(&params),
+                };
+
+                item.insert(key, op);
+            }
+        }
+    }
+
+    paths
+}
+
+/// Converts a resource pattern to OpenAPI path templates, returning them along with the names of
+/// their dynamic segments.
+///
+/// Custom regexes, constraints and tail markers are removed, e.g., `/user/{id:\d+}/{tail}*`
+/// becomes `/user/{id}/{tail}`. Since path parameters are always required in OpenAPI, a path is
+/// returned for each combination of optional segments being present or omitted, e.g.,
+/// `/files{/lang?}` becomes `/files/{lang}` and `/files`.
+fn openapi_paths(pattern: &str) -> Vec<(String, Vec<String>)> {
+    let mut paths = vec![(String::with_capacity(pattern.len()), Vec::new())];
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch != '{' {
+            for (path, _) in &mut paths {
+                path.push(ch);
+            }
+
+            continue;
+        }
+
+        let mut name = String::new();
+        let mut depth = 1;
+        let mut in_regex = false;
+
+        for ch in chars.by_ref() {
+            match ch {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                ':' | '<' if depth == 1 => in_regex = true,
+                _ => {}
+            }
+
+            if !in_regex && ch != '}' && ch != '{' {
+                name.push(ch);
+            }
+        }
+
+        // tail segment marker
+        if chars.peek() == Some(&'*') {
+            chars.next();
+        }
+
+        // optional segments: `{name?}`, `{name=default}`, optionally with a leading slash
+        let (slash, name) = match name.strip_prefix('/') {
+            Some(name) => (true, name),
+            None => (false, name.as_str()),
+        };
+
+        let (name, optional) = match name.strip_suffix('?') {
+            Some(name) => (name, true),
+            None => match name.split_once('=') {
+                Some((name, _default)) => (name, true),
+                None => (name, false),
+            },
+        };
+
+        let mut omitted = if optional { paths.clone() } else { Vec::new() };
+
+        for (path, params) in &mut paths {
+            if slash {
+                path.push('/');
+            }
+
+            path.push('{');
+            path.push_str(name);
+            path.push('}');
+            params.push(name.to_owned());
+        }
+
+        paths.append(&mut omitted);
+    }
+
+    paths
+}
+
+#[doc(hidden)]
+pub mod __private {
+    //! Support for describing handlers in the routing macros.
+    //!
+    //! Argument and return types that do not implement [`OperationInput`] or
+    //! [`OperationOutput`] are ignored using autoref specialization.
+
+    use super::*;
+
+    pub struct Probe<T>(PhantomData<T>);
+
+    impl<T> Probe<T> {
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    pub trait DescribeInput {
+        fn describe_input(&self, operation: Operation) -> Operation;
+    }
+
+    impl<T: OperationInput> DescribeInput for Probe<T> {
+        fn describe_input(&self, operation: Operation) -> Operation {
+            T::describe(operation)
+        }
+    }
+
+    pub trait DescribeInputFallback {
+        fn describe_input(&self, operation: Operation) -> Operation {
+            operation
+        }
+    }
+
+    impl<T> DescribeInputFallback for &Probe<T> {}
+
+    pub trait DescribeOutput {
+        fn describe_output(&self, operation: Operation) -> Operation;
+    }
+
+    impl<T: OperationOutput> DescribeOutput for Probe<T> {
+        fn describe_output(&self, operation: Operation) -> Operation {
+            T::describe(operation)
+        }
+    }
+
+    pub trait DescribeOutputFallback {
+        fn describe_output(&self, operation: Operation) -> Operation {
+            operation
+        }
+    }
+
+    impl<T> DescribeOutputFallback for &Probe<T> {}
+
+    /// Constructs an operation from a handler's doc comment.
+    pub fn operation(summary: &str, description: &str) -> Operation {
+        let mut op = Operation::new();
+
+        if !summary.is_empty() {
+            op = op.summary(summary);
+        }
+
+        if !description.is_empty() {
+            op = op.description(description);
+        }
+
+        op
+    }
+}
+
+/// Attaches an operation describing a macro-defined handler to its resource.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __openapi_resource {
+    ($resource:expr, $summary:expr, $description:expr, [$($input:ty),*], [$($output:ty)?]) => {{
+        #[allow(unused_imports)]
+        use $crate::openapi::__private::{
+            DescribeInput as _, DescribeInputFallback as _, DescribeOutput as _,
+            DescribeOutputFallback as _,
+        };
+
+        #[allow(unused_mut)]
+        let mut __op = $crate::openapi::__private::operation($summary, $description);
+        $(
+            __op = (&$crate::openapi::__private::Probe::<$input>::new()).describe_input(__op);
+        )*
+        $(
+            __op = (&$crate::openapi::__private::Probe::<$output>::new()).describe_output(__op);
+        )?
+
+        $resource.openapi(__op)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{call_and_read_body_json, init_service, TestRequest},
+        web, App,
+    };
+
+    #[derive(Deserialize, Serialize)]
+    struct User {
+        name: String,
+        age: Option<u8>,
+    }
+
+    impl Schema for User {
+        fn schema() -> Value {
+            json!({
+                "type": "object",
+                "properties": { "name": String::schema(), "age": u8::schema() },
+                "required": ["name"],
+            })
+        }
+    }
+
+    #[test]
+    fn pattern_conversion() {
+        fn path(path: &str, params: &[&str]) -> (String, Vec<String>) {
+            let params = params.iter().map(|param| param.to_string()).collect();
+            (path.to_owned(), params)
+        }
+
+        assert_eq!(openapi_paths("/"), [path("/", &[])]);
+        assert_eq!(
+            openapi_paths("/user/{id}/{name}"),
+            [path("/user/{id}/{name}", &["id", "name"])]
+        );
+        assert_eq!(
+            openapi_paths(r"/user/{id:\d{1,3}}/{tail}*"),
+            [path("/user/{id}/{tail}", &["id", "tail"])]
+        );
+        assert_eq!(
+            openapi_paths("/user/{id<u64>}"),
+            [path("/user/{id}", &["id"])]
+        );
+        assert_eq!(
+            openapi_paths("/files{/lang?}"),
+            [path("/files/{lang}", &["lang"]), path("/files", &[])]
+        );
+        assert_eq!(
+            openapi_paths("/files/{page=index}"),
+            [path("/files/{page}", &["page"]), path("/files/", &[])]
+        );
+        assert_eq!(
+            openapi_paths(r"/docs{/lang?:[a-z]{2}}/{page=index<slug>}"),
+            [
+                path("/docs/{lang}/{page}", &["lang", "page"]),
+                path("/docs/{page}", &["page"]),
+                path("/docs/{lang}/", &["lang"]),
+                path("/docs/", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn operation_json() {
+        let op = Operation::new()
+            .summary("Update user")
+            .tag("users")
+            .input::<web::Path<(u32,)>>()
+            .input::<web::Json<User>>()
+            .output::<Result<web::Json<User>, crate::Error>>()
+            .response(404, "Not Found")
+            .deprecated();
+
+        assert_eq!(
+            op.to_json(&["id".to_owned()]),
+            json!({
+                "tags": ["users"],
+                "summary": "Update user",
+                "parameters": [{
+                    "name": "id",
+                    "in": "path",
+                    "required": true,
+                    "schema": u32::schema(),
+                }],
+                "requestBody": {
+                    "content": { "application/json": { "schema": User::schema() } },
+                    "required": true,
+                },
+                "responses": {
+                    "200": {
+                        "description": "OK",
+                        "content": { "application/json": { "schema": User::schema() } },
+                    },
+                    "404": { "description": "Not Found" },
+                },
+                "deprecated": true,
+            })
+        );
+    }
+
+    #[test]
+    fn query_parameters() {
+        let op = Operation::new().input::<web::Query<User>>();
+
+        assert_eq!(
+            op.to_json(&[]),
+            json!({
+                "parameters": [
+                    { "name": "age", "in": "query", "required": false, "schema": u8::schema() },
+                    { "name": "name", "in": "query", "required": true, "schema": String::schema() },
+                ],
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn serves_document() {
+        async fn handler() -> &'static str {
+            ""
+        }
+
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::scope("/api/{version}")
+                        .service(
+                            web::resource("/users/{id:\\d+}")
+                                .route(
+                                    web::get()
+                                        .to(handler)
+                                        .openapi(Operation::new().summary("Get user")),
+                                )
+                                .route(web::delete().to(handler)),
+                        )
+                        .route(
+                            "/hidden",
+                            web::get().to(handler).openapi(Operation::new().hidden()),
+                        )
+                        .route("/any", web::to(handler)),
+                )
+                .external_resource("youtube", "https://youtube.com/watch/{video_id}")
+                .service(OpenApi::new("/openapi.json").title("Test").version("1.2.3")),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/openapi.json").to_request();
+        let doc: Value = call_and_read_body_json(&srv, req).await;
+
+        let params = json!([
+            { "name": "version", "in": "path", "required": true, "schema": String::schema() },
+            { "name": "id", "in": "path", "required": true, "schema": String::schema() },
+        ]);
+
+        assert_eq!(
+            doc,
+            json!({
+                "openapi": "3.1.0",
+                "info": { "title": "Test", "version": "1.2.3" },
+                "paths": {
+                    "/api/{version}/users/{id}": {
+                        "get": { "summary": "Get user", "parameters": params },
+                        "delete": { "parameters": params },
+                    },
+                },
+            })
+        );
+
+        // the document is only generated once
+        let req = TestRequest::get().uri("/openapi.json").to_request();
+        let res = crate::test::call_service(&srv, req).await;
+        assert_eq!(
+            res.headers()
+                .get(crate::http::header::CONTENT_TYPE)
+                .unwrap(),
+            "application/json"
+        );
+        let body = crate::test::read_body(res).await;
+        assert_eq!(serde_json::from_slice::<Value>(&body).unwrap(), doc);
+
+        let req = TestRequest::post().uri("/openapi.json").to_request();
+        let res = crate::test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
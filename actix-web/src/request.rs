@@ -5,7 +5,7 @@ use std::{
     str,
 };
 
-use actix_http::{Message, RequestHead};
+use actix_http::{EarlyHints, Message, RequestHead};
 use actix_router::{Path, Url};
 use actix_utils::future::{ok, Ready};
 #[cfg(feature = "cookies")]
@@ -260,6 +260,46 @@ impl HttpRequest {
         Ref::map(self.extensions(), |data| data.get().unwrap())
     }
 
+    /// Sends a `103 Early Hints` informational response with the given headers before the final
+    /// response.
+    ///
+    /// Early hints are typically `Link` headers with `rel=preload` that allow clients to start
+    /// fetching resources while the final response is still being prepared. This can be called
+    /// more than once to send several informational responses.
+    ///
+    /// Returns `false` if the hints are not sent because the final response has already been sent
+    /// or the connection does not support early hints. Currently, early hints are only sent to
+    /// HTTP/1.1 clients.
+    ///
+    /// # Panics
+    /// Panics if request's extensions container is already mutably borrowed.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{
+    ///     http::header::{HeaderMap, HeaderValue, LINK},
+    ///     HttpRequest, HttpResponse,
+    /// };
+    ///
+    /// async fn index(req: HttpRequest) -> HttpResponse {
+    ///     let mut hints = HeaderMap::new();
+    ///     hints.append(LINK, HeaderValue::from_static("</style.css>; rel=preload; as=style"));
+    ///     req.send_early_hints(hints);
+    ///
+    ///     // render page...
+    ///
+    ///     HttpResponse::Ok()
+    ///         .insert_header((LINK, "</style.css>; rel=preload; as=style"))
+    ///         .body("<!doctype html>")
+    /// }
+    /// ```
+    pub fn send_early_hints(&self, headers: HeaderMap) -> bool {
+        match self.extensions().get::<EarlyHints>() {
+            Some(early_hints) => early_hints.send(headers),
+            None => false,
+        }
+    }
+
     /// Returns a reference to the application's connection configuration.
     #[inline]
     pub fn app_config(&self) -> &AppConfig {
@@ -1,11 +1,12 @@
-use std::{cell::RefCell, fmt, future::Future, rc::Rc};
+use std::{any, cell::RefCell, fmt, future::Future, rc::Rc};
 
-use actix_http::Extensions;
+use actix_http::{Extensions, Method};
 use actix_router::{IntoPatterns, Patterns};
 use actix_service::{
-    apply, apply_fn_factory, boxed, fn_service, IntoServiceFactory, Service, ServiceFactory,
-    ServiceFactoryExt, Transform,
+    apply, apply_fn_factory, boxed, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt,
+    Transform,
 };
+use actix_utils::future::ready;
 use futures_core::future::LocalBoxFuture;
 use futures_util::future::join_all;
 
@@ -15,13 +16,14 @@ use crate::{
     dev::{ensure_leading_slash, AppService, ResourceDef},
     guard::{self, Guard},
     handler::Handler,
-    http::header,
+    method_fallback::{self, MethodFallback},
+    rmap::ServiceInfo,
     route::{Route, RouteService},
     service::{
         BoxedHttpService, BoxedHttpServiceFactory, HttpServiceFactory, ServiceRequest,
         ServiceResponse,
     },
-    web, Error, FromRequest, HttpResponse, Responder,
+    web, Error, FromRequest, Responder,
 };
 
 /// A collection of [`Route`]s that respond to the same path pattern.
@@ -45,7 +47,8 @@ use crate::{
 ///
 /// If no matching route is found, an empty 405 response is returned which includes an
 /// [appropriate Allow header][RFC 9110 §15.5.6]. This default behavior can be overridden using
-/// [`default_service()`](Self::default_service).
+/// [`default_service()`](Self::default_service) or configured for the whole app using
+/// [`App::method_not_allowed()`](crate::App::method_not_allowed).
 ///
 /// [RFC 9110 §15.5.6]: https://www.rfc-editor.org/rfc/rfc9110.html#section-15.5.6
 pub struct Resource<T = ResourceEndpoint> {
@@ -55,8 +58,11 @@ pub struct Resource<T = ResourceEndpoint> {
     routes: Vec<Route>,
     app_data: Option<Extensions>,
     guards: Vec<Box<dyn Guard>>,
-    default: BoxedHttpServiceFactory,
+    default: Option<BoxedHttpServiceFactory>,
     factory_ref: Rc<RefCell<Option<ResourceFactory>>>,
+    middleware: Vec<&'static str>,
+    #[cfg(feature = "openapi")]
+    operation: Option<Rc<crate::openapi::Operation>>,
 }
 
 impl Resource {
@@ -70,23 +76,12 @@ impl Resource {
             name: None,
             endpoint: ResourceEndpoint::new(fref.clone()),
             factory_ref: fref,
+            middleware: Vec::new(),
+            #[cfg(feature = "openapi")]
+            operation: None,
             guards: Vec::new(),
             app_data: None,
-            default: boxed::factory(fn_service(|req: ServiceRequest| async {
-                use crate::HttpMessage as _;
-
-                let allowed = req.extensions().get::<guard::RegisteredMethods>().cloned();
-
-                if let Some(methods) = allowed {
-                    Ok(req.into_response(
-                        HttpResponse::MethodNotAllowed()
-                            .insert_header(header::Allow(methods.0))
-                            .finish(),
-                    ))
-                } else {
-                    Ok(req.into_response(HttpResponse::MethodNotAllowed()))
-                }
-            })),
+            default: None,
         }
     }
 }
@@ -129,6 +124,16 @@ where
         self
     }
 
+    /// Attaches OpenAPI documentation to the resource's routes.
+    ///
+    /// Applies to routes that do not have their own documentation attached using
+    /// [`Route::openapi`]. See the [`openapi`](crate::openapi) module for more.
+    #[cfg(feature = "openapi")]
+    pub fn openapi(mut self, operation: crate::openapi::Operation) -> Self {
+        self.operation = Some(Rc::new(operation));
+        self
+    }
+
     pub(crate) fn add_guards(mut self, guards: Vec<Box<dyn Guard>>) -> Self {
         self.guards.extend(guards);
         self
@@ -278,6 +283,9 @@ where
             > + 'static,
         B: MessageBody,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<M>());
+
         Resource {
             endpoint: apply(mw, self.endpoint),
             rdef: self.rdef,
@@ -287,6 +295,9 @@ where
             default: self.default,
             app_data: self.app_data,
             factory_ref: self.factory_ref,
+            middleware,
+            #[cfg(feature = "openapi")]
+            operation: self.operation,
         }
     }
 
@@ -316,6 +327,9 @@ where
         R: Future<Output = Result<ServiceResponse<B>, Error>>,
         B: MessageBody,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<F>());
+
         Resource {
             endpoint: apply_fn_factory(self.endpoint, mw),
             rdef: self.rdef,
@@ -325,6 +339,9 @@ where
             default: self.default,
             app_data: self.app_data,
             factory_ref: self.factory_ref,
+            middleware,
+            #[cfg(feature = "openapi")]
+            operation: self.operation,
         }
     }
 
@@ -334,7 +351,9 @@ where
     /// use a [`Route`] as default service.
     ///
     /// If a custom default service is not registered, an empty `405 Method Not Allowed` response
-    /// with an appropriate Allow header will be sent instead.
+    /// with an appropriate Allow header will be sent instead, unless disabled using
+    /// [`App::method_not_allowed()`](crate::App::method_not_allowed), in which case the parent's
+    /// default service is used.
     ///
     /// # Examples
     /// ```
@@ -358,10 +377,9 @@ where
         U::InitError: fmt::Debug,
     {
         // create and configure default resource
-        self.default = boxed::factory(
-            f.into_factory()
-                .map_init_err(|e| log::error!("Can not construct default service: {:?}", e)),
-        );
+        self.default = Some(boxed::factory(f.into_factory().map_init_err(|e| {
+            log::error!("Can not construct default service: {:?}", e)
+        })));
 
         self
     }
@@ -418,6 +436,24 @@ where
     B: MessageBody + 'static,
 {
     fn register(mut self, config: &mut AppService) {
+        let resource_methods = guard::allowed_methods(&self.guards);
+
+        let routes = self
+            .routes
+            .iter()
+            .map(|route| {
+                #[allow(unused_mut)]
+                let mut info = route.info(resource_methods.as_deref());
+
+                #[cfg(feature = "openapi")]
+                if info.operation.is_none() {
+                    info.operation = self.operation.clone();
+                }
+
+                info
+            })
+            .collect();
+
         let guards = if self.guards.is_empty() {
             None
         } else {
@@ -434,9 +470,24 @@ where
             rdef.set_name(name);
         }
 
+        let info = ServiceInfo {
+            middleware: self.middleware,
+            routes,
+            ..Default::default()
+        };
+
+        let fallback = config
+            .method_fallback()
+            .for_resource(self.default.is_some());
+
         *self.factory_ref.borrow_mut() = Some(ResourceFactory {
             routes: self.routes,
-            default: self.default,
+            methods: Rc::new(info.methods()),
+            default: match self.default {
+                Some(default) => Rc::new(default),
+                None => config.default_service(),
+            },
+            fallback,
         });
 
         let resource_data = self.app_data.map(Rc::new);
@@ -452,13 +503,15 @@ where
             async { Ok(fut.await?.map_into_boxed_body()) }
         });
 
-        config.register_service(rdef, guards, endpoint, None)
+        config.register_service_with_info(rdef, guards, endpoint, info)
     }
 }
 
 pub struct ResourceFactory {
     routes: Vec<Route>,
-    default: BoxedHttpServiceFactory,
+    methods: Rc<Vec<Method>>,
+    default: Rc<BoxedHttpServiceFactory>,
+    fallback: MethodFallback,
 }
 
 impl ServiceFactory<ServiceRequest> for ResourceFactory {
@@ -476,6 +529,9 @@ impl ServiceFactory<ServiceRequest> for ResourceFactory {
         // construct route service factory futures
         let factory_fut = join_all(self.routes.iter().map(|route| route.new_service(())));
 
+        let methods = Rc::clone(&self.methods);
+        let fallback = self.fallback;
+
         Box::pin(async move {
             let default = default_fut.await?;
             let routes = factory_fut
@@ -483,14 +539,21 @@ impl ServiceFactory<ServiceRequest> for ResourceFactory {
                 .into_iter()
                 .collect::<Result<Vec<_>, _>>()?;
 
-            Ok(ResourceService { routes, default })
+            Ok(ResourceService {
+                routes,
+                methods,
+                default,
+                fallback,
+            })
         })
     }
 }
 
 pub struct ResourceService {
     routes: Vec<RouteService>,
+    methods: Rc<Vec<Method>>,
     default: BoxedHttpService,
+    fallback: MethodFallback,
 }
 
 impl Service<ServiceRequest> for ResourceService {
@@ -507,7 +570,20 @@ impl Service<ServiceRequest> for ResourceService {
             }
         }
 
-        self.default.call(req)
+        if self.fallback.use_get_for_head(&req, &self.methods) {
+            let route = method_fallback::check_as_get(&mut req, |req| {
+                self.routes.iter().find(|route| route.check(req))
+            });
+
+            if let Some(route) = route {
+                return method_fallback::head_response(route.call(req));
+            }
+        }
+
+        match self.fallback.respond(req, &self.methods) {
+            Ok(res) => Box::pin(ready(Ok(res))),
+            Err(req) => self.default.call(req),
+        }
     }
 }
 
@@ -544,10 +620,13 @@ mod tests {
 
     use super::*;
     use crate::{
-        http::{header::HeaderValue, Method, StatusCode},
+        http::{
+            header::{self, HeaderValue},
+            Method, StatusCode,
+        },
         middleware::DefaultHeaders,
         test::{call_service, init_service, TestRequest},
-        App, HttpMessage,
+        App, HttpMessage, HttpResponse,
     };
 
     #[test]
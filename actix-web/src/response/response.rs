@@ -45,7 +45,7 @@ impl HttpResponse<BoxBody> {
     #[inline]
     pub fn from_error(error: impl Into<Error>) -> Self {
         let error = error.into();
-        let mut response = error.as_response_error().error_response();
+        let mut response = error.error_response();
         response.error = Some(error);
         response
     }
@@ -79,6 +79,18 @@ impl<B> HttpResponse<B> {
         self.error.as_ref()
     }
 
+    /// Removes and returns the source `error` for this response.
+    #[inline]
+    pub(crate) fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// Sets the source `error` for this response.
+    #[inline]
+    pub(crate) fn set_error(&mut self, error: Error) {
+        self.error = Some(error);
+    }
+
     /// Get the response status code
     #[inline]
     pub fn status(&self) -> StatusCode {
@@ -1,15 +1,16 @@
 use std::{
     borrow::Cow,
     cell::RefCell,
-    fmt::Write as _,
+    fmt::{self, Write as _},
     rc::{Rc, Weak},
 };
 
+use actix_http::{uri::Authority, Method};
 use actix_router::ResourceDef;
 use ahash::AHashMap;
 use url::Url;
 
-use crate::{error::UrlGenerationError, request::HttpRequest};
+use crate::{error::UrlGenerationError, guard::GuardDetail, request::HttpRequest};
 
 const AVG_PATH_LEN: usize = 24;
 
@@ -17,6 +18,9 @@ const AVG_PATH_LEN: usize = 24;
 pub struct ResourceMap {
     pattern: ResourceDef,
 
+    /// Host pattern that requests to this node must match, if restricted by host.
+    host: Option<ResourceDef>,
+
     /// Named resources within the tree or, for external resources, it points to isolated nodes
     /// outside the tree.
     named: AHashMap<String, Rc<ResourceMap>>,
@@ -25,6 +29,139 @@ pub struct ResourceMap {
 
     /// Must be `None` for "edge" nodes.
     nodes: Option<Vec<Rc<ResourceMap>>>,
+
+    /// Introspection information about the service registered at this node.
+    info: ServiceInfo,
+}
+
+/// Introspection information about a registered service.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ServiceInfo {
+    /// Descriptions of the service's guards.
+    pub(crate) guards: Vec<GuardDetail>,
+
+    /// Type names of the service's middleware, in registration order.
+    pub(crate) middleware: Vec<&'static str>,
+
+    /// Routes of a registered resource. Empty for other kinds of services.
+    pub(crate) routes: Vec<RouteInfo>,
+}
+
+impl ServiceInfo {
+    /// Returns the methods handled by the service's routes, without duplicates.
+    pub(crate) fn methods(&self) -> Vec<Method> {
+        let mut methods = Vec::new();
+
+        for method in self
+            .routes
+            .iter()
+            .filter_map(|route| route.methods.as_ref())
+            .flatten()
+        {
+            if !methods.contains(method) {
+                methods.push(method.clone());
+            }
+        }
+
+        methods
+    }
+}
+
+/// Information about a route of a registered resource.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RouteInfo {
+    /// HTTP methods the route responds to, or `None` if it is not restricted by method.
+    pub(crate) methods: Option<Vec<Method>>,
+
+    /// Descriptions of the route's guards.
+    pub(crate) guards: Vec<GuardDetail>,
+
+    /// Type names of the route's middleware, in registration order.
+    pub(crate) middleware: Vec<&'static str>,
+
+    /// OpenAPI documentation attached to the route.
+    #[cfg(feature = "openapi")]
+    pub(crate) operation: Option<Rc<crate::openapi::Operation>>,
+}
+
+/// A route registered in an application.
+///
+/// See [`ResourceMap::routes`].
+#[derive(Debug, Clone)]
+pub struct RegisteredRoute {
+    pattern: String,
+    name: Option<String>,
+    methods: Option<Vec<Method>>,
+    guards: Vec<GuardDetail>,
+    scopes: Vec<String>,
+    middleware: Vec<&'static str>,
+    #[cfg(feature = "openapi")]
+    pub(crate) operation: Option<Rc<crate::openapi::Operation>>,
+}
+
+impl RegisteredRoute {
+    /// Returns the full pattern of the route, including the patterns of enclosing scopes.
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Returns the name of the route's resource, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Returns the HTTP methods the route responds to, as restricted by method guards.
+    ///
+    /// Returns `None` if the route is not restricted to specific methods.
+    pub fn methods(&self) -> Option<&[Method]> {
+        self.methods.as_deref()
+    }
+
+    /// Returns descriptions of the guards of the route and its enclosing resource and scopes,
+    /// outermost first.
+    ///
+    /// Guards that do not provide [details](crate::guard::Guard::details) are not listed.
+    pub fn guards(&self) -> &[GuardDetail] {
+        &self.guards
+    }
+
+    /// Returns the patterns of the scopes the route is nested in, outermost first.
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Returns the type names of the middleware applied to the route, from the application down
+    /// to the route itself, in registration order within each level.
+    pub fn middleware(&self) -> &[&'static str] {
+        &self.middleware
+    }
+}
+
+impl fmt::Display for RegisteredRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.methods {
+            Some(methods) => {
+                let methods = methods.iter().map(Method::as_str).collect::<Vec<_>>();
+                write!(f, "{} {}", methods.join(","), self.pattern)?;
+            }
+            None => write!(f, "* {}", self.pattern)?,
+        }
+
+        if let Some(name) = &self.name {
+            write!(f, " ({name})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Context inherited by routes from their enclosing scopes.
+#[derive(Clone, Default)]
+struct RouteContext {
+    prefix: String,
+    guards: Vec<GuardDetail>,
+    scopes: Vec<String>,
+    middleware: Vec<&'static str>,
 }
 
 impl ResourceMap {
@@ -32,9 +169,11 @@ impl ResourceMap {
     pub fn new(root: ResourceDef) -> Self {
         ResourceMap {
             pattern: root,
+            host: None,
             named: AHashMap::default(),
             parent: RefCell::new(Weak::new()),
             nodes: Some(Vec::new()),
+            info: ServiceInfo::default(),
         }
     }
 
@@ -73,6 +212,18 @@ impl ResourceMap {
     /// To add external resource, supply a pattern without a leading `/`.
     /// The root pattern of `nested`, if present, should match `pattern`.
     pub fn add(&mut self, pattern: &mut ResourceDef, nested: Option<Rc<ResourceMap>>) {
+        self.add_service(pattern, nested, ServiceInfo::default());
+    }
+
+    /// Adds a (possibly nested) resource along with introspection information about it.
+    ///
+    /// `info` is ignored when `nested` is `Some`; see [`set_info`](Self::set_info).
+    pub(crate) fn add_service(
+        &mut self,
+        pattern: &mut ResourceDef,
+        nested: Option<Rc<ResourceMap>>,
+        info: ServiceInfo,
+    ) {
         pattern.set_id(self.nodes.as_ref().unwrap().len() as u16);
 
         if let Some(new_node) = nested {
@@ -86,9 +237,11 @@ impl ResourceMap {
         } else {
             let new_node = Rc::new(ResourceMap {
                 pattern: pattern.clone(),
+                host: None,
                 named: AHashMap::default(),
                 parent: RefCell::new(Weak::new()),
                 nodes: None,
+                info,
             });
 
             if let Some(name) = pattern.name() {
@@ -107,6 +260,104 @@ impl ResourceMap {
         }
     }
 
+    /// Sets the host pattern that requests to this container node must match.
+    pub(crate) fn set_host(&mut self, host: ResourceDef) {
+        self.host = Some(host);
+    }
+
+    /// Sets introspection information about the service owning this container node.
+    pub(crate) fn set_info(&mut self, info: ServiceInfo) {
+        self.info = info;
+    }
+
+    /// Returns every route registered in the tree, in registration order.
+    ///
+    /// Routes are listed for each route of every registered resource. Services that are not
+    /// resources, like file servers, are listed as a single route that is not restricted by method.
+    /// Patterns are relative to this node, which is the application root for the resource map
+    /// returned by [`HttpRequest::resource_map`]. External resources are not listed.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpRequest, HttpResponse};
+    ///
+    /// async fn routes(req: HttpRequest) -> HttpResponse {
+    ///     let routes = req
+    ///         .resource_map()
+    ///         .routes()
+    ///         .iter()
+    ///         .map(ToString::to_string)
+    ///         .collect::<Vec<_>>();
+    ///
+    ///     // e.g., ["GET /api/routes (routes)"]
+    ///     HttpResponse::Ok().json(routes)
+    /// }
+    ///
+    /// let app = App::new().service(
+    ///     web::scope("/api").service(web::resource("/routes").name("routes").get(routes)),
+    /// );
+    /// ```
+    pub fn routes(&self) -> Vec<RegisteredRoute> {
+        let ctx = RouteContext {
+            guards: self.info.guards.clone(),
+            middleware: self.info.middleware.clone(),
+            ..Default::default()
+        };
+
+        let mut routes = Vec::new();
+        self.collect_routes(&ctx, &mut routes);
+        routes
+    }
+
+    fn collect_routes(&self, ctx: &RouteContext, routes: &mut Vec<RegisteredRoute>) {
+        for node in self.nodes.iter().flatten() {
+            for pattern in node.pattern.pattern_iter() {
+                let mut ctx = ctx.clone();
+                ctx.prefix.push_str(pattern);
+                ctx.guards.extend(node.info.guards.iter().cloned());
+                ctx.middleware.extend(node.info.middleware.iter().copied());
+
+                if node.nodes.is_some() {
+                    ctx.scopes.push(pattern.to_owned());
+                    node.collect_routes(&ctx, routes);
+                    continue;
+                }
+
+                let route = |info: Option<&RouteInfo>| {
+                    let mut route = RegisteredRoute {
+                        pattern: ctx.prefix.clone(),
+                        name: node.pattern.name().map(ToOwned::to_owned),
+                        methods: None,
+                        guards: ctx.guards.clone(),
+                        scopes: ctx.scopes.clone(),
+                        middleware: ctx.middleware.clone(),
+                        #[cfg(feature = "openapi")]
+                        operation: None,
+                    };
+
+                    if let Some(info) = info {
+                        route.methods = info.methods.clone();
+                        route.guards.extend(info.guards.iter().cloned());
+                        route.middleware.extend(info.middleware.iter().copied());
+
+                        #[cfg(feature = "openapi")]
+                        {
+                            route.operation = info.operation.clone();
+                        }
+                    }
+
+                    route
+                };
+
+                if node.info.routes.is_empty() {
+                    routes.push(route(None));
+                } else {
+                    routes.extend(node.info.routes.iter().map(Some).map(route));
+                }
+            }
+        }
+    }
+
     pub(crate) fn finish(self: &Rc<Self>) {
         for node in self.nodes.iter().flatten() {
             node.parent.replace(Rc::downgrade(self));
@@ -129,21 +380,57 @@ impl ResourceMap {
     {
         let mut elements = elements.into_iter();
 
-        let path = self
+        let (host, path) = self
             .named
             .get(name)
             .ok_or(UrlGenerationError::ResourceNotFound)?
-            .root_rmap_fn(String::with_capacity(AVG_PATH_LEN), |mut acc, node| {
-                node.pattern
-                    .resource_path_from_iter(&mut acc, &mut elements)
-                    .then_some(acc)
-            })
+            .root_rmap_fn(
+                (None, String::with_capacity(AVG_PATH_LEN)),
+                |(mut host, mut acc), node| {
+                    if !node
+                        .pattern
+                        .resource_path_from_iter(&mut acc, &mut elements)
+                    {
+                        return None;
+                    }
+
+                    // innermost host pattern determines the host
+                    if let Some(pattern) = &node.host {
+                        let mut buf = String::new();
+
+                        if !pattern.resource_path_from_iter(&mut buf, &mut elements) {
+                            return None;
+                        }
+
+                        host = Some(buf);
+                    }
+
+                    Some((host, acc))
+                },
+            )
             .ok_or(UrlGenerationError::NotEnoughElements)?;
 
         let (base, path): (Cow<'_, _>, _) = if path.starts_with('/') {
-            // build full URL from connection info parts and resource path
+            // build full URL from connection info parts, host pattern and resource path
             let conn = req.connection_info();
-            let base = format!("{}://{}", conn.scheme(), conn.host());
+
+            let base = match host {
+                Some(host) => {
+                    // host patterns do not match ports so the request's port, if any, is kept
+                    let port = conn
+                        .host()
+                        .parse::<Authority>()
+                        .ok()
+                        .and_then(|authority| authority.port_u16());
+
+                    match port {
+                        Some(port) => format!("{}://{}:{}", conn.scheme(), host, port),
+                        None => format!("{}://{}", conn.scheme(), host),
+                    }
+                }
+                None => format!("{}://{}", conn.scheme(), conn.host()),
+            };
+
             (Cow::Owned(base), path.as_str())
         } else {
             // external resource; third slash would be the root slash in the path
@@ -457,6 +744,34 @@ mod tests {
         assert!(rmap.url_for(&req, "missing", ["u123"]).is_err());
     }
 
+    #[test]
+    fn url_for_optional_segments() {
+        let mut root = ResourceMap::new(ResourceDef::prefix(""));
+
+        let mut rdef = ResourceDef::new("/files{/lang?}/{page=index}");
+        rdef.set_name("files");
+        root.add(&mut rdef, None);
+
+        let rmap = Rc::new(root);
+        ResourceMap::finish(&rmap);
+
+        let mut req = crate::test::TestRequest::default();
+        req.set_server_hostname("localhost:8888");
+        let req = req.to_http_request();
+
+        let url = rmap.url_for(&req, "files", [""; 0]).unwrap().to_string();
+        assert_eq!(url, "http://localhost:8888/files/");
+
+        let url = rmap.url_for(&req, "files", ["en"]).unwrap().to_string();
+        assert_eq!(url, "http://localhost:8888/files/en/");
+
+        let url = rmap
+            .url_for(&req, "files", ["en", "about"])
+            .unwrap()
+            .to_string();
+        assert_eq!(url, "http://localhost:8888/files/en/about");
+    }
+
     #[test]
     fn url_for_parser() {
         let mut root = ResourceMap::new(ResourceDef::prefix(""));
@@ -557,4 +872,99 @@ mod tests {
 
         assert!(rmap.url_for(&req, "missing", ["u123"]).is_err());
     }
+
+    #[actix_rt::test]
+    async fn registered_routes() {
+        use crate::{
+            guard,
+            middleware::{Compat, Logger, NormalizePath},
+            test::{call_service, init_service, TestRequest},
+            web, App, HttpResponse,
+        };
+
+        async fn routes(req: HttpRequest) -> HttpResponse {
+            let routes = req.resource_map().routes();
+
+            let summary = routes.iter().map(ToString::to_string).collect::<Vec<_>>();
+            assert_eq!(
+                summary,
+                [
+                    "GET /routes (routes)",
+                    "GET,POST /api/{v}/users/{id}",
+                    "DELETE /api/{v}/users/{id}",
+                    "* /api/{v}/files",
+                    "* /a",
+                    "* /b",
+                ]
+            );
+
+            let users = &routes[1];
+            assert_eq!(users.methods(), Some(&[Method::GET, Method::POST][..]));
+            assert_eq!(users.scopes(), ["/api/{v}"]);
+            assert_eq!(
+                users
+                    .guards()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                ["header(x-api: 1)", "method(GET, POST)"]
+            );
+            assert_eq!(
+                users.middleware(),
+                [
+                    std::any::type_name::<NormalizePath>(),
+                    std::any::type_name::<Logger>(),
+                ]
+            );
+
+            let delete = &routes[2];
+            assert_eq!(delete.guards().len(), 2);
+            assert_eq!(delete.middleware().len(), 3);
+            assert_eq!(
+                delete.middleware()[2],
+                std::any::type_name::<Compat<Logger>>()
+            );
+
+            assert_eq!(routes[3].methods(), None);
+            assert!(routes[3].name().is_none());
+
+            HttpResponse::Ok().finish()
+        }
+
+        let srv = init_service(
+            App::new()
+                .wrap(NormalizePath::trim())
+                .service(web::resource("/routes").name("routes").get(routes))
+                .service(
+                    web::scope("/api/{v}")
+                        .guard(guard::Header("x-api", "1"))
+                        .wrap(Logger::default())
+                        .service(
+                            web::resource("/users/{id}")
+                                .route(
+                                    web::route()
+                                        .guard(guard::Any(guard::Get()).or(guard::Post()))
+                                        .to(HttpResponse::Ok),
+                                )
+                                .route(
+                                    web::delete()
+                                        .to(HttpResponse::Ok)
+                                        .wrap(Compat::new(Logger::default())),
+                                ),
+                        )
+                        .service(web::service("/files").finish(
+                            |req: crate::dev::ServiceRequest| async {
+                                Ok(req.into_response(HttpResponse::Ok()))
+                            },
+                        )),
+                )
+                .service(web::resource(["/a", "/b"]))
+                .external_resource("youtube", "https://youtube.com/watch/{video_id}"),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/routes").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), crate::http::StatusCode::OK);
+    }
 }
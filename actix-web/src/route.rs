@@ -1,4 +1,4 @@
-use std::{mem, rc::Rc};
+use std::{any, mem, rc::Rc};
 
 use actix_http::{body::MessageBody, Method};
 use actix_service::{
@@ -12,6 +12,7 @@ use crate::{
     guard::{self, Guard},
     handler::{handler_service, Handler},
     middleware::Compat,
+    rmap::RouteInfo,
     service::{BoxedHttpServiceFactory, ServiceRequest, ServiceResponse},
     Error, FromRequest, HttpResponse, Responder,
 };
@@ -23,6 +24,9 @@ use crate::{
 pub struct Route {
     service: BoxedHttpServiceFactory,
     guards: Rc<Vec<Box<dyn Guard>>>,
+    middleware: Vec<&'static str>,
+    #[cfg(feature = "openapi")]
+    operation: Option<Rc<crate::openapi::Operation>>,
 }
 
 impl Route {
@@ -34,6 +38,9 @@ impl Route {
                 Ok(req.into_response(HttpResponse::NotFound()))
             })),
             guards: Rc::new(Vec::new()),
+            middleware: Vec::new(),
+            #[cfg(feature = "openapi")]
+            operation: None,
         }
     }
 
@@ -56,15 +63,45 @@ impl Route {
             > + 'static,
         B: MessageBody + 'static,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<M>());
+
         Route {
             service: boxed::factory(apply(Compat::new(mw), self.service)),
             guards: self.guards,
+            middleware,
+            #[cfg(feature = "openapi")]
+            operation: self.operation,
         }
     }
 
     pub(crate) fn take_guards(&mut self) -> Vec<Box<dyn Guard>> {
         mem::take(Rc::get_mut(&mut self.guards).unwrap())
     }
+
+    /// Returns routing information about this route.
+    ///
+    /// Methods allowed by the route's guards are restricted further by `resource_methods`, if set.
+    pub(crate) fn info(&self, resource_methods: Option<&[Method]>) -> RouteInfo {
+        let methods = match (guard::allowed_methods(&self.guards), resource_methods) {
+            (Some(methods), Some(allowed)) => Some(
+                methods
+                    .into_iter()
+                    .filter(|method| allowed.contains(method))
+                    .collect(),
+            ),
+            (Some(methods), None) => Some(methods),
+            (None, allowed) => allowed.map(<[Method]>::to_vec),
+        };
+
+        RouteInfo {
+            methods,
+            guards: guard::guard_details(self.guards.iter()),
+            middleware: self.middleware.clone(),
+            #[cfg(feature = "openapi")]
+            operation: self.operation.clone(),
+        }
+    }
 }
 
 impl ServiceFactory<ServiceRequest> for Route {
@@ -159,6 +196,31 @@ impl Route {
         self
     }
 
+    /// Attaches OpenAPI documentation to the route.
+    ///
+    /// See the [`openapi`](crate::openapi) module for more.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{openapi::Operation, web, App};
+    ///
+    /// async fn index(query: web::Query<Vec<(String, String)>>) -> String {
+    ///     format!("{} parameters", query.len())
+    /// }
+    ///
+    /// let app = App::new().route(
+    ///     "/",
+    ///     web::get()
+    ///         .to(index)
+    ///         .openapi(Operation::new().summary("Count query parameters")),
+    /// );
+    /// ```
+    #[cfg(feature = "openapi")]
+    pub fn openapi(mut self, operation: crate::openapi::Operation) -> Self {
+        self.operation = Some(Rc::new(operation));
+        self
+    }
+
     /// Set handler function, use request extractors for parameters.
     ///
     /// # Examples
@@ -1,6 +1,6 @@
-use std::{cell::RefCell, fmt, future::Future, mem, rc::Rc};
+use std::{any, cell::RefCell, fmt, future::Future, mem, rc::Rc};
 
-use actix_http::{body::MessageBody, Extensions};
+use actix_http::{body::MessageBody, Extensions, Method};
 use actix_router::{ResourceDef, Router};
 use actix_service::{
     apply, apply_fn_factory, boxed, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt,
@@ -13,8 +13,9 @@ use crate::{
     config::ServiceConfig,
     data::Data,
     dev::AppService,
-    guard::Guard,
-    rmap::ResourceMap,
+    guard::{self, Guard},
+    method_fallback::{self, MethodFallback, ServiceGuards},
+    rmap::{ResourceMap, ServiceInfo},
     service::{
         AppServiceFactory, BoxedHttpService, BoxedHttpServiceFactory, HttpServiceFactory,
         ServiceFactoryWrapper, ServiceRequest, ServiceResponse,
@@ -60,8 +61,10 @@ pub struct Scope<T = ScopeEndpoint> {
     app_data: Option<Extensions>,
     services: Vec<Box<dyn AppServiceFactory>>,
     guards: Vec<Box<dyn Guard>>,
+    host: Option<ResourceDef>,
     default: Option<Rc<BoxedHttpServiceFactory>>,
     external: Vec<ResourceDef>,
+    middleware: Vec<&'static str>,
     factory_ref: Rc<RefCell<Option<ScopeFactory>>>,
 }
 
@@ -75,9 +78,11 @@ impl Scope {
             rdef: path.to_string(),
             app_data: None,
             guards: Vec::new(),
+            host: None,
             services: Vec::new(),
             default: None,
             external: Vec::new(),
+            middleware: Vec::new(),
             factory_ref,
         }
     }
@@ -110,6 +115,44 @@ where
         self
     }
 
+    /// Restricts the scope to requests whose host matches a pattern.
+    ///
+    /// The pattern uses the same syntax as [resource definitions][pat], like
+    /// `{tenant}.example.com`. Hosts are matched case-insensitively and without their port. Values
+    /// of the pattern's dynamic segments are added to the request's
+    /// [`match_info`](crate::HttpRequest::match_info), after those of the scope's path, so they can
+    /// be extracted using [`Path`](crate::web::Path).
+    ///
+    /// Note that the default segment pattern also matches dots; use a custom regex, like
+    /// `{tenant:[^.]+}.example.com`, to match a single label.
+    ///
+    /// URLs generated for resources in the scope using [`url_for`](crate::HttpRequest::url_for)
+    /// use the host built from the pattern, taking values for its segments after those for the
+    /// scope's path.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpRequest};
+    ///
+    /// async fn index(req: HttpRequest, tenant: web::Path<String>) -> String {
+    ///     let url = req.url_for("dashboard", [tenant.as_str()]).unwrap();
+    ///     format!("Welcome, {}! Your dashboard is at {}", tenant, url)
+    /// }
+    ///
+    /// let app = App::new().service(
+    ///     web::scope("")
+    ///         .host("{tenant:[^.]+}.example.com")
+    ///         .service(web::resource("/").to(index))
+    ///         .service(web::resource("/dashboard").name("dashboard").to(index)),
+    /// );
+    /// ```
+    ///
+    /// [pat]: crate::dev::ResourceDef
+    pub fn host(mut self, pattern: &str) -> Self {
+        self.host = Some(guard::host_pattern(pattern));
+        self
+    }
+
     /// Add scope data.
     ///
     /// Data of different types from parent contexts will still be accessible. Any `Data<T>` types
@@ -315,14 +358,19 @@ where
             > + 'static,
         B: MessageBody,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<M>());
+
         Scope {
             endpoint: apply(mw, self.endpoint),
             rdef: self.rdef,
             app_data: self.app_data,
             guards: self.guards,
+            host: self.host,
             services: self.services,
             default: self.default,
             external: self.external,
+            middleware,
             factory_ref: self.factory_ref,
         }
     }
@@ -353,14 +401,19 @@ where
         R: Future<Output = Result<ServiceResponse<B>, Error>>,
         B: MessageBody,
     {
+        let mut middleware = self.middleware;
+        middleware.push(any::type_name::<F>());
+
         Scope {
             endpoint: apply_fn_factory(self.endpoint, mw),
             rdef: self.rdef,
             app_data: self.app_data,
             guards: self.guards,
+            host: self.host,
             services: self.services,
             default: self.default,
             external: self.external,
+            middleware,
             factory_ref: self.factory_ref,
         }
     }
@@ -389,6 +442,18 @@ where
 
         let mut rmap = ResourceMap::new(ResourceDef::root_prefix(&self.rdef));
 
+        if let Some(host) = &self.host {
+            self.guards
+                .push(Box::new(guard::HostPatternGuard::new(host.clone())));
+            rmap.set_host(host.clone());
+        }
+
+        rmap.set_info(ServiceInfo {
+            guards: guard::guard_details(&self.guards),
+            middleware: self.middleware,
+            ..Default::default()
+        });
+
         // external resources
         for mut rdef in mem::take(&mut self.external) {
             rmap.add(&mut rdef, None);
@@ -397,13 +462,16 @@ where
         // complete scope pipeline creation
         *self.factory_ref.borrow_mut() = Some(ScopeFactory {
             default,
+            method_fallback: config.method_fallback(),
+            radix_router: config.radix_router(),
             services: cfg
                 .into_services()
                 .1
                 .into_iter()
-                .map(|(mut rdef, srv, guards, nested)| {
-                    rmap.add(&mut rdef, nested);
-                    (rdef, srv, RefCell::new(guards))
+                .map(|(mut rdef, srv, guards, nested, info)| {
+                    let methods = info.methods();
+                    rmap.add_service(&mut rdef, nested, info);
+                    (rdef, srv, RefCell::new(guards), methods)
                 })
                 .collect::<Vec<_>>()
                 .into_boxed_slice()
@@ -418,13 +486,24 @@ where
         };
 
         let scope_data = self.app_data.map(Rc::new);
+        let host = self.host;
 
-        // wraps endpoint service (including middleware) call and injects app data for this scope
+        // wraps endpoint service (including middleware) call, injects app data for this scope and
+        // adds segments captured from the host
         let endpoint = apply_fn_factory(self.endpoint, move |mut req: ServiceRequest, srv| {
             if let Some(ref data) = scope_data {
                 req.add_data_container(Rc::clone(data));
             }
 
+            if let Some(ref host) = host {
+                if let Some(captures) = guard::match_host_pattern(host, req.head()) {
+                    for (name, value) in captures.iter() {
+                        req.match_info_mut()
+                            .add_static(name.to_owned(), value.to_owned());
+                    }
+                }
+            }
+
             let fut = srv.call(req);
 
             async { Ok(fut.await?.map_into_boxed_body()) }
@@ -447,9 +526,12 @@ pub struct ScopeFactory {
             ResourceDef,
             BoxedHttpServiceFactory,
             RefCell<Option<Guards>>,
+            Vec<Method>,
         )],
     >,
     default: Rc<BoxedHttpServiceFactory>,
+    method_fallback: MethodFallback,
+    radix_router: bool,
 }
 
 impl ServiceFactory<ServiceRequest> for ScopeFactory {
@@ -464,40 +546,58 @@ impl ServiceFactory<ServiceRequest> for ScopeFactory {
         // construct default service factory future
         let default_fut = self.default.new_service(());
 
+        let method_fallback = self.method_fallback;
+        let radix_router = self.radix_router;
+
         // construct all services factory future with it's resource def and guards.
-        let factory_fut = join_all(self.services.iter().map(|(path, factory, guards)| {
-            let path = path.clone();
-            let guards = guards.borrow_mut().take().unwrap_or_default();
-            let factory_fut = factory.new_service(());
-            async move {
-                let service = factory_fut.await?;
-                Ok((path, guards, service))
-            }
-        }));
+        let factory_fut = join_all(
+            self.services
+                .iter()
+                .map(|(path, factory, guards, methods)| {
+                    let path = path.clone();
+                    let guards = ServiceGuards {
+                        guards: guards.borrow_mut().take().unwrap_or_default(),
+                        methods: methods.clone(),
+                    };
+                    let factory_fut = factory.new_service(());
+                    async move {
+                        let service = factory_fut.await?;
+                        Ok((path, guards, service))
+                    }
+                }),
+        );
 
         Box::pin(async move {
             let default = default_fut.await?;
 
+            let mut builder = Router::build();
+            builder.radix_tree(radix_router);
+
             // build router from the factory future result.
             let router = factory_fut
                 .await
                 .into_iter()
                 .collect::<Result<Vec<_>, _>>()?
                 .drain(..)
-                .fold(Router::build(), |mut router, (path, guards, service)| {
+                .fold(builder, |mut router, (path, guards, service)| {
                     router.push(path, service, guards);
                     router
                 })
                 .finish();
 
-            Ok(ScopeService { router, default })
+            Ok(ScopeService {
+                router,
+                default,
+                method_fallback,
+            })
         })
     }
 }
 
 pub struct ScopeService {
-    router: Router<BoxedHttpService, Vec<Box<dyn Guard>>>,
+    router: Router<BoxedHttpService, ServiceGuards>,
     default: BoxedHttpService,
+    method_fallback: MethodFallback,
 }
 
 impl Service<ServiceRequest> for ScopeService {
@@ -507,17 +607,8 @@ impl Service<ServiceRequest> for ScopeService {
 
     actix_service::always_ready!();
 
-    fn call(&self, mut req: ServiceRequest) -> Self::Future {
-        let res = self.router.recognize_fn(&mut req, |req, guards| {
-            let guard_ctx = req.guard_ctx();
-            guards.iter().all(|guard| guard.check(&guard_ctx))
-        });
-
-        if let Some((srv, _info)) = res {
-            srv.call(req)
-        } else {
-            self.default.call(req)
-        }
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        method_fallback::route(&self.router, &self.default, self.method_fallback, req)
     }
 }
 
@@ -744,6 +835,60 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[actix_rt::test]
+    async fn test_scope_host() {
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::scope("/{lang}")
+                        .host("{tenant:[^.]+}.Example.com")
+                        .service(web::resource("/{page}").name("page").to(
+                        |req: HttpRequest, path: web::Path<(String, String, String)>| async move {
+                            let (lang, tenant, page) = path.into_inner();
+                            let url = req.url_for("page", ["de", "other", "about"]).unwrap();
+                            format!("{tenant} {lang} {page} {url}")
+                        },
+                    )),
+                )
+                .service(web::scope("").host("EXAMPLE.com").default_service(web::to(
+                    |req: HttpRequest| async move { req.match_info().segment_count().to_string() },
+                ))),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/en/home")
+            .insert_header((header::HOST, "ACME.example.com:8080"))
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_body_eq!(res, b"acme en home http://other.example.com:8080/de/about");
+
+        let req = TestRequest::with_uri("/en/home")
+            .insert_header((header::HOST, "acme.example.com"))
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_body_eq!(res, b"acme en home http://other.example.com/de/about");
+
+        let req = TestRequest::with_uri("/en/home")
+            .insert_header((header::HOST, "example.com"))
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_body_eq!(res, b"0");
+
+        let req = TestRequest::with_uri("/en/home")
+            .insert_header((header::HOST, "acme.example.org"))
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let req = TestRequest::with_uri("/en/home")
+            .insert_header((header::HOST, "a.b.example.com"))
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
     #[actix_rt::test]
     async fn test_scope_variable_segment() {
         let srv = init_service(App::new().service(web::scope("/ab-{project}").service(
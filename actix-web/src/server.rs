@@ -1,12 +1,23 @@
+#[cfg(feature = "http3")]
+use std::thread;
 use std::{
     any::Any,
-    cmp, fmt, io,
+    cmp, fmt,
+    future::pending,
+    io,
     marker::PhantomData,
     net,
+    pin::pin,
     sync::{Arc, Mutex},
+    task::Poll,
     time::Duration,
 };
 
+#[cfg(feature = "http3")]
+use actix_http::h3::{
+    self,
+    reexports::{quinn, rustls},
+};
 #[cfg(any(
     feature = "openssl",
     feature = "rustls-0_20",
@@ -14,13 +25,23 @@ use std::{
     feature = "rustls-0_22",
 ))]
 use actix_http::TlsAcceptorConfig;
-use actix_http::{body::MessageBody, Extensions, HttpService, KeepAlive, Request, Response};
-use actix_server::{Server, ServerBuilder};
+use actix_http::{
+    body::{BoxBody, MessageBody},
+    header::{self, HeaderValue},
+    Extensions, H1Limits, H2Settings, HttpService, KeepAlive, Request, Response,
+};
+use actix_rt::System;
+use actix_server::{Server, ServerBuilder, ServerHandle};
 use actix_service::{
     map_config, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt as _,
 };
 #[cfg(feature = "openssl")]
 use actix_tls::accept::openssl::reexports::{AlpnError, SslAcceptor, SslAcceptorBuilder};
+use futures_core::future::LocalBoxFuture;
+use futures_util::future::{poll_fn, select, Either};
+#[cfg(feature = "http3")]
+use futures_util::future::{AbortHandle, AbortRegistration, Abortable};
+use tokio::sync::watch;
 
 use crate::{config::AppConfig, Error};
 
@@ -34,10 +55,42 @@ struct Config {
     keep_alive: KeepAlive,
     client_request_timeout: Duration,
     client_disconnect_timeout: Duration,
+    h1_limits: H1Limits,
+    h2_settings: H2Settings,
+    defer_continue: bool,
+    shutdown_signal: GracefulShutdown,
+    #[allow(dead_code)] // only dead when HTTP/3 feature is not enabled
+    shutdown_timeout: Duration,
+    #[allow(dead_code)] // only dead when HTTP/3 feature is not enabled
+    workers: usize,
+    /// Ports of QUIC endpoints, advertised to clients of TCP listeners using `Alt-Svc`.
+    quic_ports: Vec<u16>,
+    /// QUIC worker threads; stopped and joined when the config is dropped with the server's
+    /// listeners, once the server has stopped.
+    #[cfg(feature = "http3")]
+    quic_workers: Option<QuicWorkers>,
     #[allow(dead_code)] // only dead when no TLS features are enabled
     tls_handshake_timeout: Option<Duration>,
 }
 
+impl Config {
+    /// Returns the `Alt-Svc` header value advertising the server's HTTP/3 endpoints, if any.
+    fn alt_svc(&self) -> Option<HeaderValue> {
+        if self.quic_ports.is_empty() {
+            return None;
+        }
+
+        let value = self
+            .quic_ports
+            .iter()
+            .map(|port| format!("h3=\":{port}\"; ma=86400"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Some(HeaderValue::from_str(&value).unwrap())
+    }
+}
+
 /// An HTTP Server.
 ///
 /// Create new HTTP server with application factory.
@@ -84,8 +137,13 @@ where
     backlog: u32,
     sockets: Vec<Socket>,
     builder: ServerBuilder,
+    listen_os_signals: bool,
+    shutdown_tx: watch::Sender<bool>,
     #[allow(clippy::type_complexity)]
     on_connect_fn: Option<Arc<dyn Fn(&dyn Any, &mut Extensions) + Send + Sync>>,
+    #[cfg(feature = "http3")]
+    #[allow(clippy::type_complexity)]
+    quic_listeners: Vec<Box<dyn FnOnce(Vec<AbortRegistration>) -> thread::JoinHandle<()> + Send>>,
     _phantom: PhantomData<(S, B)>,
 }
 
@@ -111,6 +169,8 @@ where
     /// [`bind()`](Self::bind()) docs for more on how worker count and bind address resolution
     /// causes multiple server factory instantiations.
     pub fn new(factory: F) -> Self {
+        let (shutdown_tx, shutdown_rx) = watch::channel(false);
+
         HttpServer {
             factory,
             config: Arc::new(Mutex::new(Config {
@@ -118,12 +178,26 @@ where
                 keep_alive: KeepAlive::default(),
                 client_request_timeout: Duration::from_secs(5),
                 client_disconnect_timeout: Duration::from_secs(1),
+                h1_limits: H1Limits::default(),
+                h2_settings: H2Settings::default(),
+                defer_continue: false,
+                shutdown_signal: GracefulShutdown(shutdown_rx),
+                shutdown_timeout: Duration::from_secs(30),
+                workers: std::thread::available_parallelism()
+                    .map_or(2, std::num::NonZeroUsize::get),
+                quic_ports: Vec::new(),
+                #[cfg(feature = "http3")]
+                quic_workers: None,
                 tls_handshake_timeout: None,
             })),
             backlog: 1024,
             sockets: Vec::new(),
             builder: ServerBuilder::default(),
+            listen_os_signals: true,
+            shutdown_tx,
             on_connect_fn: None,
+            #[cfg(feature = "http3")]
+            quic_listeners: Vec::new(),
             _phantom: PhantomData,
         }
     }
@@ -144,6 +218,7 @@ where
     /// Panics if `num` is 0.
     pub fn workers(mut self, num: usize) -> Self {
         self.builder = self.builder.workers(num);
+        self.config.lock().unwrap().workers = num;
         self
     }
 
@@ -236,6 +311,56 @@ where
         self
     }
 
+    /// Sets whether `100 Continue` responses are deferred until the request payload is first read.
+    ///
+    /// By default, HTTP/1.1 requests with an `Expect: 100-continue` header get a `100 Continue`
+    /// response before the app is called. When enabled, the response is only sent once the app
+    /// starts reading the payload, which allows rejecting requests before the client sends their
+    /// payload; see [`ExpectContinue`](crate::middleware::ExpectContinue).
+    ///
+    /// Since the client may still send the payload of a request that is responded to without
+    /// reading it, the connection is closed after such responses instead of being kept alive.
+    ///
+    /// Disabled by default.
+    pub fn defer_continue(self, enabled: bool) -> Self {
+        self.config.lock().unwrap().defer_continue = enabled;
+        self
+    }
+
+    /// Sets limits on the size of HTTP/1 request heads.
+    ///
+    /// Requests exceeding the limits are rejected with a `431 Request Header Fields Too Large` or,
+    /// for long request lines, a `414 URI Too Long` response. See [`H1Limits`] for the defaults.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{http::H1Limits, App, HttpServer};
+    ///
+    /// HttpServer::new(|| App::new())
+    ///     .h1_limits(H1Limits::new().max_headers(128).max_head_size(16 * 1024));
+    /// ```
+    pub fn h1_limits(self, limits: H1Limits) -> Self {
+        self.config.lock().unwrap().h1_limits = limits;
+        self
+    }
+
+    /// Sets settings for HTTP/2 connections.
+    ///
+    /// During a graceful shutdown started by a `SIGTERM` signal, HTTP/2 connections send a `GOAWAY`
+    /// frame so that clients stop opening new streams on them, regardless of these settings.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{http::H2Settings, App, HttpServer};
+    ///
+    /// HttpServer::new(|| App::new())
+    ///     .h2_settings(H2Settings::new().max_concurrent_streams(100));
+    /// ```
+    pub fn h2_settings(self, settings: H2Settings) -> Self {
+        self.config.lock().unwrap().h2_settings = settings;
+        self
+    }
+
     /// Sets TLS handshake timeout.
     ///
     /// Defines a timeout for TLS handshake. If the TLS handshake does not complete within this
@@ -283,7 +408,11 @@ where
             backlog: self.backlog,
             sockets: self.sockets,
             builder: self.builder,
+            listen_os_signals: self.listen_os_signals,
+            shutdown_tx: self.shutdown_tx,
             on_connect_fn: Some(Arc::new(f)),
+            #[cfg(feature = "http3")]
+            quic_listeners: self.quic_listeners,
             _phantom: PhantomData,
         }
     }
@@ -309,7 +438,7 @@ where
 
     /// Disables signal handling.
     pub fn disable_signals(mut self) -> Self {
-        self.builder = self.builder.disable_signals();
+        self.listen_os_signals = false;
         self
     }
 
@@ -321,6 +450,7 @@ where
     /// By default shutdown timeout sets to 30 seconds.
     pub fn shutdown_timeout(mut self, sec: u64) -> Self {
         self.builder = self.builder.shutdown_timeout(sec);
+        self.config.lock().unwrap().shutdown_timeout = Duration::from_secs(sec);
         self
     }
 
@@ -397,7 +527,12 @@ where
     /// Resolves socket address(es) and binds server to created listener(s) for plaintext HTTP/1.x
     /// or HTTP/2 connections.
     ///
+    /// HTTP/2 is used by clients with prior knowledge and by HTTP/1.1 clients that upgrade using
+    /// `Upgrade: h2c`. Upgrades can be disabled with [`H2Settings::h2c_upgrade()`].
+    ///
     /// See [`bind()`](Self::bind()) for more details on `addrs` argument.
+    ///
+    /// [`H2Settings::h2c_upgrade()`]: crate::http::H2Settings::h2c_upgrade
     #[cfg(feature = "http2")]
     pub fn bind_auto_h2c<A: net::ToSocketAddrs>(mut self, addrs: A) -> io::Result<Self> {
         let sockets = bind_addrs(addrs, self.backlog)?;
@@ -513,6 +648,10 @@ where
                         .keep_alive(cfg.keep_alive)
                         .client_request_timeout(cfg.client_request_timeout)
                         .client_disconnect_timeout(cfg.client_disconnect_timeout)
+                        .h1_limits(cfg.h1_limits)
+                        .h2_settings(cfg.h2_settings)
+                        .defer_continue(cfg.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(cfg.shutdown_signal.clone()))
                         .local_addr(addr);
 
                     if let Some(handler) = on_connect_fn.clone() {
@@ -522,7 +661,8 @@ where
 
                     let fac = factory()
                         .into_factory()
-                        .map_err(|err| err.into().error_response());
+                        .map(alt_svc(cfg.alt_svc()))
+                        .map_err(alt_svc_error(cfg.alt_svc()));
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(false, host.clone(), addr)
@@ -557,6 +697,10 @@ where
                         .keep_alive(cfg.keep_alive)
                         .client_request_timeout(cfg.client_request_timeout)
                         .client_disconnect_timeout(cfg.client_disconnect_timeout)
+                        .h1_limits(cfg.h1_limits)
+                        .h2_settings(cfg.h2_settings)
+                        .defer_continue(cfg.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(cfg.shutdown_signal.clone()))
                         .local_addr(addr);
 
                     if let Some(handler) = on_connect_fn.clone() {
@@ -566,7 +710,8 @@ where
 
                     let fac = factory()
                         .into_factory()
-                        .map_err(|err| err.into().error_response());
+                        .map(alt_svc(cfg.alt_svc()))
+                        .map_err(alt_svc_error(cfg.alt_svc()));
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(false, host.clone(), addr)
@@ -632,7 +777,11 @@ where
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .h2_settings(c.h2_settings)
+                        .defer_continue(c.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(c.shutdown_signal.clone()));
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -642,7 +791,8 @@ where
 
                     let fac = factory()
                         .into_factory()
-                        .map_err(|err| err.into().error_response());
+                        .map(alt_svc(c.alt_svc()))
+                        .map_err(alt_svc_error(c.alt_svc()));
 
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
@@ -683,7 +833,11 @@ where
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .h2_settings(c.h2_settings)
+                        .defer_continue(c.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(c.shutdown_signal.clone()));
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -693,7 +847,8 @@ where
 
                     let fac = factory()
                         .into_factory()
-                        .map_err(|err| err.into().error_response());
+                        .map(alt_svc(c.alt_svc()))
+                        .map_err(alt_svc_error(c.alt_svc()));
 
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
@@ -749,7 +904,11 @@ where
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .h2_settings(c.h2_settings)
+                        .defer_continue(c.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(c.shutdown_signal.clone()));
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -759,7 +918,8 @@ where
 
                     let fac = factory()
                         .into_factory()
-                        .map_err(|err| err.into().error_response());
+                        .map(alt_svc(c.alt_svc()))
+                        .map_err(alt_svc_error(c.alt_svc()));
 
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
@@ -815,6 +975,10 @@ where
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .h2_settings(c.h2_settings)
+                        .defer_continue(c.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(c.shutdown_signal.clone()))
                         .local_addr(addr);
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
@@ -825,7 +989,8 @@ where
 
                     let fac = factory()
                         .into_factory()
-                        .map_err(|err| err.into().error_response());
+                        .map(alt_svc(c.alt_svc()))
+                        .map_err(alt_svc_error(c.alt_svc()));
 
                     // false positive lint (?)
                     #[allow(clippy::significant_drop_in_scrutinee)]
@@ -843,6 +1008,196 @@ where
         Ok(self)
     }
 
+    /// Binds to `addrs` for accepting incoming HTTP/3 connections over QUIC, using Rustls v0.23.
+    ///
+    /// QUIC connections to each bound address are served by a set of [`workers()`](Self::workers)
+    /// threads, separate from the workers that serve TCP listeners. QUIC sockets are not included
+    /// in [`addrs()`](Self::addrs).
+    ///
+    /// Clients discover HTTP/3 support through the `Alt-Svc` response header, so responses from all
+    /// TCP listeners of this server advertise the QUIC ports, unless the app sets its own `Alt-Svc`
+    /// header. Bind QUIC to the same port as a TLS listener so that the advertised authority is
+    /// covered by its certificate.
+    ///
+    /// ALPN protocol "h3" is added to any configured ones.
+    ///
+    /// # Shutdown
+    /// When a `SIGTERM` signal starts a graceful shutdown, QUIC workers stop accepting connections,
+    /// ask clients to close their connections using an HTTP/3 `GOAWAY` frame and wait at most the
+    /// [shutdown timeout](Self::shutdown_timeout) for in-flight requests to finish. Other shutdowns
+    /// close QUIC connections once the other workers have stopped.
+    ///
+    /// QUIC workers are stopped along with the workers of the server's TCP and Unix socket
+    /// listeners, so at least one of those must be bound; see [`run()`](Self::run).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{web, App, HttpServer};
+    /// # fn rustls_config() -> actix_http::h3::reexports::rustls::ServerConfig { unimplemented!() }
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// HttpServer::new(|| App::new().route("/", web::get().to(|| async { "hello" })))
+    ///     .bind(("0.0.0.0", 8080))?
+    ///     .bind_quic(("0.0.0.0", 8443), rustls_config())?
+    ///     .run()
+    ///     .await
+    /// # }
+    /// ```
+    #[cfg(feature = "http3")]
+    pub fn bind_quic<A: net::ToSocketAddrs>(
+        mut self,
+        addrs: A,
+        config: rustls::ServerConfig,
+    ) -> io::Result<Self> {
+        let config = h3::quic_server_config(config)?;
+        let sockets = bind_udp_addrs(addrs)?;
+
+        for socket in sockets {
+            self = self.listen_quic_inner(socket, config.clone())?;
+        }
+
+        Ok(self)
+    }
+
+    /// Binds to existing UDP socket for accepting incoming HTTP/3 connections over QUIC, using
+    /// Rustls v0.23.
+    ///
+    /// See [`bind_quic()`](Self::bind_quic) for more details.
+    #[cfg(feature = "http3")]
+    pub fn listen_quic(
+        self,
+        socket: net::UdpSocket,
+        config: rustls::ServerConfig,
+    ) -> io::Result<Self> {
+        let config = h3::quic_server_config(config)?;
+        self.listen_quic_inner(socket, config)
+    }
+
+    #[cfg(feature = "http3")]
+    fn listen_quic_inner(
+        mut self,
+        socket: net::UdpSocket,
+        config: quinn::ServerConfig,
+    ) -> io::Result<Self> {
+        let factory = self.factory.clone();
+        let cfg = self.config.clone();
+        let addr = socket.local_addr()?;
+
+        {
+            let mut c = cfg.lock().unwrap();
+            if !c.quic_ports.contains(&addr.port()) {
+                c.quic_ports.push(addr.port());
+            }
+        }
+
+        let on_connect_fn = self.on_connect_fn.clone();
+
+        self.quic_listeners.push(Box::new(move |stop| {
+            let (serve, shutdown_signal, shutdown_timeout) = {
+                let c = cfg.lock().unwrap();
+                let host = c.host.clone().unwrap_or_else(|| format!("{}", addr));
+                let keep_alive = c.keep_alive;
+                let client_request_timeout = c.client_request_timeout;
+                let client_disconnect_timeout = c.client_disconnect_timeout;
+                let shutdown_signal = c.shutdown_signal.clone();
+
+                // constructs the HTTP/3 service of a worker
+                let serve = move || {
+                    let svc = HttpService::build()
+                        .keep_alive(keep_alive)
+                        .client_request_timeout(client_request_timeout)
+                        .client_disconnect_timeout(client_disconnect_timeout)
+                        .graceful_shutdown_signal(graceful_shutdown(shutdown_signal.clone()))
+                        .local_addr(addr);
+
+                    let svc = if let Some(handler) = on_connect_fn.clone() {
+                        svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
+                    } else {
+                        svc
+                    };
+
+                    let fac = factory()
+                        .into_factory()
+                        .map_err(|err| err.into().error_response());
+
+                    let host = host.clone();
+
+                    svc.h3(map_config(fac, move |_| {
+                        AppConfig::new(true, host.clone(), addr)
+                    }))
+                };
+
+                (serve, c.shutdown_signal.clone(), c.shutdown_timeout)
+            };
+
+            // workers must not keep the config alive, since it is dropped to stop them
+            drop(cfg);
+
+            let worker = move |endpoint: quinn::Endpoint, stop: AbortRegistration| {
+                serve_quic(
+                    endpoint,
+                    serve(),
+                    shutdown_signal.clone(),
+                    shutdown_timeout,
+                    stop,
+                )
+            };
+
+            // the first worker drives the endpoint, so it starts the others and outlives them
+            thread::Builder::new()
+                .name(format!("actix-web-quic-{}", addr))
+                .spawn(move || {
+                    let res = actix_rt::System::new().block_on(async move {
+                        let endpoint = quinn::Endpoint::new(
+                            quinn::EndpointConfig::default(),
+                            Some(config),
+                            socket,
+                            Arc::new(quinn::TokioRuntime),
+                        )?;
+
+                        let mut stop = stop.into_iter();
+                        let first_stop = stop.next().expect("at least one QUIC worker");
+
+                        let workers = stop
+                            .map(|stop| {
+                                let worker = worker.clone();
+                                let endpoint = endpoint.clone();
+
+                                thread::Builder::new()
+                                    .name(format!("actix-web-quic-{}", addr))
+                                    .spawn(move || {
+                                        let res = actix_rt::System::new()
+                                            .block_on(worker(endpoint, stop));
+
+                                        if let Err(err) = res {
+                                            log::error!("QUIC listener on {addr} failed: {err}");
+                                        }
+                                    })
+                            })
+                            .collect::<io::Result<Vec<_>>>()?;
+
+                        let res = worker(endpoint, first_stop).await;
+
+                        let _ = actix_rt::task::spawn_blocking(move || {
+                            for worker in workers {
+                                let _ = worker.join();
+                            }
+                        })
+                        .await;
+
+                        res
+                    });
+
+                    if let Err(err) = res {
+                        log::error!("QUIC listener on {addr} failed: {err}");
+                    }
+                })
+                .expect("can not spawn QUIC worker thread")
+        }));
+
+        Ok(self)
+    }
+
     /// Opens Unix Domain Socket (UDS) from `uds` path and binds server to created listener.
     #[cfg(unix)]
     pub fn bind_uds<A>(mut self, uds_path: A) -> io::Result<Self>
@@ -883,6 +1238,10 @@ where
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .h1_limits(c.h1_limits)
+                        .h2_settings(c.h2_settings)
+                        .defer_continue(c.defer_continue)
+                        .graceful_shutdown_signal(graceful_shutdown(c.shutdown_signal.clone()))
                         .finish(map_config(fac, move |_| config.clone())),
                 )
             },
@@ -923,7 +1282,11 @@ where
                 let mut svc = HttpService::build()
                     .keep_alive(c.keep_alive)
                     .client_request_timeout(c.client_request_timeout)
-                    .client_disconnect_timeout(c.client_disconnect_timeout);
+                    .client_disconnect_timeout(c.client_disconnect_timeout)
+                    .h1_limits(c.h1_limits)
+                    .h2_settings(c.h2_settings)
+                    .defer_continue(c.defer_continue)
+                    .graceful_shutdown_signal(graceful_shutdown(c.shutdown_signal.clone()));
 
                 if let Some(handler) = on_connect_fn.clone() {
                     svc = svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext));
@@ -960,14 +1323,264 @@ where
     /// if workers is set to 4, and there are 2 addresses to bind, then 8 worker threads will be
     /// spawned.
     ///
+    /// # Signals
+    /// Unless [disabled](Self::disable_signals), the server stops when the process receives a
+    /// `SIGINT`, `SIGTERM` or `SIGQUIT` signal (or Ctrl-C on non-Unix platforms), after which the
+    /// `System` is stopped. Only `SIGTERM` starts a graceful shutdown, in which HTTP/2 and HTTP/3
+    /// connections send a `GOAWAY` frame before the workers stop.
+    ///
     /// # Panics
     /// This methods panics if no socket addresses were successfully bound or if no Tokio runtime
-    /// is set up.
+    /// is set up. It also panics if QUIC sockets were bound without any TCP or Unix socket
+    /// listener.
     pub fn run(self) -> Server {
-        self.builder.run()
+        #[cfg(feature = "http3")]
+        if !self.quic_listeners.is_empty() {
+            assert!(
+                !self.sockets.is_empty(),
+                "QUIC listeners require at least one TCP or Unix socket listener"
+            );
+
+            let workers = self.config.lock().unwrap().workers;
+            let mut quic_workers = QuicWorkers {
+                stop: Vec::new(),
+                threads: Vec::new(),
+            };
+
+            for start in self.quic_listeners {
+                let (stop, stop_registrations) = (0..workers)
+                    .map(|_| AbortHandle::new_pair())
+                    .unzip::<_, _, Vec<_>, _>();
+
+                quic_workers.stop.extend(stop);
+                quic_workers.threads.push(start(stop_registrations));
+            }
+
+            self.config.lock().unwrap().quic_workers = Some(quic_workers);
+        }
+
+        // signals are handled here so that workers can be told about graceful shutdowns first
+        let server = self.builder.disable_signals().run();
+
+        if self.listen_os_signals {
+            tokio::runtime::Handle::current().spawn(handle_signals(
+                server.handle(),
+                self.shutdown_tx,
+                System::try_current(),
+            ));
+        }
+
+        server
+    }
+}
+
+/// Worker threads serving QUIC listeners.
+///
+/// Dropping it stops the workers, closing their connections unless they are shutting down
+/// gracefully, and waits for them to finish.
+#[cfg(feature = "http3")]
+struct QuicWorkers {
+    stop: Vec<AbortHandle>,
+    threads: Vec<thread::JoinHandle<()>>,
+}
+
+#[cfg(feature = "http3")]
+impl Drop for QuicWorkers {
+    fn drop(&mut self) {
+        for stop in &self.stop {
+            stop.abort();
+        }
+
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
     }
 }
 
+/// Accepts QUIC connections from `endpoint` until the server shuts down or `stop` is aborted.
+#[cfg(feature = "http3")]
+async fn serve_quic<T>(
+    endpoint: quinn::Endpoint,
+    factory: T,
+    shutdown_signal: GracefulShutdown,
+    shutdown_timeout: Duration,
+    stop: AbortRegistration,
+) -> io::Result<()>
+where
+    T: ServiceFactory<quinn::Incoming, Config = ()>,
+    T::Error: fmt::Display,
+    T::InitError: fmt::Debug,
+    <T::Service as Service<quinn::Incoming>>::Future: 'static,
+{
+    let svc = factory.new_service(()).await.map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            format!("can not start HTTP/3 service: {err:?}"),
+        )
+    })?;
+
+    let mut shutdown = pin!(shutdown_signal.started());
+    let mut stop = pin!(Abortable::new(pending::<()>(), stop));
+
+    let graceful = loop {
+        let stopped = select(shutdown.as_mut(), stop.as_mut());
+
+        let incoming = match select(pin!(endpoint.accept()), stopped).await {
+            Either::Left((Some(incoming), _)) => incoming,
+            Either::Left((None, _)) => break false,
+            Either::Right((Either::Left(_), _)) => break true,
+            Either::Right((Either::Right(_), _)) => break false,
+        };
+
+        if let Err(err) = poll_fn(|cx| svc.poll_ready(cx)).await {
+            log::error!("HTTP/3 service is not ready: {err}");
+            break false;
+        }
+
+        let conn = svc.call(incoming);
+
+        actix_rt::spawn(async move {
+            if let Err(err) = conn.await {
+                log::trace!("HTTP/3 connection error: {err}");
+            }
+        });
+    };
+
+    if graceful {
+        // stop accepting connections; open ones close once their requests are finished
+        endpoint.set_server_config(None);
+        let _ = actix_rt::time::timeout(shutdown_timeout, endpoint.wait_idle()).await;
+    }
+
+    endpoint.close(0u32.into(), b"");
+    endpoint.wait_idle().await;
+
+    Ok(())
+}
+
+/// Adds an `Alt-Svc` header advertising the server's HTTP/3 endpoints to responses that do not
+/// already have one.
+fn alt_svc<R, B>(value: Option<HeaderValue>) -> impl Fn(R) -> Response<B> + Clone
+where
+    R: Into<Response<B>>,
+{
+    move |res| {
+        let mut res = res.into();
+
+        if let Some(value) = &value {
+            if !res.headers().contains_key(header::ALT_SVC) {
+                res.headers_mut().insert(header::ALT_SVC, value.clone());
+            }
+        }
+
+        res
+    }
+}
+
+/// Converts service errors to responses with an `Alt-Svc` header like [`alt_svc`].
+fn alt_svc_error<E>(value: Option<HeaderValue>) -> impl Fn(E) -> Response<BoxBody> + Clone
+where
+    E: Into<Error>,
+{
+    let alt_svc = alt_svc(value);
+    move |err| alt_svc(err.into().error_response())
+}
+
+/// Signal shared by the server's workers that resolves when the server starts a graceful shutdown.
+#[derive(Clone)]
+struct GracefulShutdown(watch::Receiver<bool>);
+
+impl GracefulShutdown {
+    /// Resolves once a graceful shutdown has started; never resolves if the server stops otherwise.
+    async fn started(mut self) {
+        while !*self.0.borrow_and_update() {
+            if self.0.changed().await.is_err() {
+                pending::<()>().await;
+            }
+        }
+    }
+}
+
+/// Adapts the server's graceful shutdown signal for use by the HTTP service.
+fn graceful_shutdown(signal: GracefulShutdown) -> impl Fn() -> LocalBoxFuture<'static, ()> {
+    move || Box::pin(signal.clone().started())
+}
+
+/// Stops the server when the process receives a termination signal, then stops the `System`.
+///
+/// Graceful shutdowns are signalled to workers before stopping them. Returns without stopping the
+/// server once it has stopped in another way, which drops all receivers of `shutdown`.
+async fn handle_signals(
+    server: ServerHandle,
+    shutdown: watch::Sender<bool>,
+    system: Option<System>,
+) {
+    let graceful = match select(pin!(stop_signal()), pin!(shutdown.closed())).await {
+        Either::Left((graceful, _)) => graceful,
+        Either::Right(_) => return,
+    };
+
+    if graceful {
+        let _ = shutdown.send(true);
+    }
+
+    server.stop(graceful).await;
+
+    if let Some(system) = system {
+        system.stop();
+    }
+}
+
+/// Resolves when the process receives a termination signal, returning true if it asks for a
+/// graceful shutdown.
+#[cfg(unix)]
+async fn stop_signal() -> bool {
+    use actix_rt::signal::unix::{signal, SignalKind};
+
+    let mut signals = [
+        (SignalKind::interrupt(), "SIGINT", false),
+        (SignalKind::terminate(), "SIGTERM", true),
+        (SignalKind::quit(), "SIGQUIT", false),
+    ]
+    .into_iter()
+    .filter_map(|(kind, name, graceful)| match signal(kind) {
+        Ok(signal) => Some((signal, name, graceful)),
+        Err(err) => {
+            log::error!("can not initialize stream handler for {name}: {err}");
+            None
+        }
+    })
+    .collect::<Vec<_>>();
+
+    let (name, graceful) = poll_fn(|cx| {
+        for (signal, name, graceful) in &mut signals {
+            if signal.poll_recv(cx).is_ready() {
+                return Poll::Ready((*name, *graceful));
+            }
+        }
+
+        Poll::Pending
+    })
+    .await;
+
+    if graceful {
+        log::info!("{name} received; starting graceful shutdown");
+    } else {
+        log::info!("{name} received; starting forced shutdown");
+    }
+
+    graceful
+}
+
+/// Resolves when the process receives a termination signal, returning true if it asks for a
+/// graceful shutdown.
+#[cfg(not(unix))]
+async fn stop_signal() -> bool {
+    let _ = actix_rt::signal::ctrl_c().await;
+    log::info!("Ctrl-C received; starting forced shutdown");
+    false
+}
+
 /// Bind TCP listeners to socket addresses resolved from `addrs` with options.
 fn bind_addrs(addrs: impl net::ToSocketAddrs, backlog: u32) -> io::Result<Vec<net::TcpListener>> {
     let mut err = None;
@@ -996,6 +1609,31 @@ fn bind_addrs(addrs: impl net::ToSocketAddrs, backlog: u32) -> io::Result<Vec<ne
     }
 }
 
+/// Bind UDP sockets to socket addresses resolved from `addrs`.
+#[cfg(feature = "http3")]
+fn bind_udp_addrs(addrs: impl net::ToSocketAddrs) -> io::Result<Vec<net::UdpSocket>> {
+    let mut err = None;
+    let mut sockets = Vec::new();
+
+    for addr in addrs.to_socket_addrs()? {
+        match net::UdpSocket::bind(addr) {
+            Ok(socket) => sockets.push(socket),
+            Err(error) => err = Some(error),
+        }
+    }
+
+    if !sockets.is_empty() {
+        Ok(sockets)
+    } else if let Some(err) = err.take() {
+        Err(err)
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::Other,
+            "Can not bind to address.",
+        ))
+    }
+}
+
 /// Creates a TCP listener from socket address and options.
 fn create_tcp_listener(addr: net::SocketAddr, backlog: u32) -> io::Result<net::TcpListener> {
     use socket2::{Domain, Protocol, Socket, Type};
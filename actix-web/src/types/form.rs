@@ -20,7 +20,11 @@ use serde::{de::DeserializeOwned, Serialize};
 #[cfg(feature = "__compress")]
 use crate::dev::Decompress;
 use crate::{
-    body::EitherBody, error::UrlencodedError, extract::FromRequest, http::header::CONTENT_LENGTH,
+    body::EitherBody,
+    error::{extractor_error, UrlencodedError},
+    extract::FromRequest,
+    http::header::CONTENT_LENGTH,
+    types::validate::{Valid, ValidExtractFut, Validate, Validator},
     web, Error, HttpMessage, HttpRequest, HttpResponse, Responder,
 };
 
@@ -136,16 +140,34 @@ where
             fut: UrlEncoded::new(req, payload).limit(limit),
             req: req.clone(),
             err_handler,
+            validator: None,
         }
     }
 }
 
+/// See [`Valid`] for example of usage as an extractor.
+impl<T> FromRequest for Valid<Form<T>>
+where
+    T: DeserializeOwned + Validate + 'static,
+{
+    type Error = Error;
+    type Future = ValidExtractFut<FormExtractFut<T>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let mut fut = Form::<T>::from_request(req, payload);
+        fut.validator = Some(T::validate);
+        ValidExtractFut { fut }
+    }
+}
+
 type FormErrHandler = Option<Rc<dyn Fn(UrlencodedError, &HttpRequest) -> Error>>;
 
 pub struct FormExtractFut<T> {
     fut: UrlEncoded<T>,
     err_handler: FormErrHandler,
     req: HttpRequest,
+    validator: Validator<T>,
 }
 
 impl<T> Future for FormExtractFut<T>
@@ -159,10 +181,17 @@ where
 
         let res = ready!(Pin::new(&mut this.fut).poll(cx));
 
+        let res = res.and_then(|item| match this.validator {
+            Some(validate) => validate(&item)
+                .map(|_| item)
+                .map_err(UrlencodedError::Validate),
+            None => Ok(item),
+        });
+
         let res = match res {
             Err(err) => match &this.err_handler {
                 Some(err_handler) => Err((err_handler)(err, &this.req)),
-                None => Err(err.into()),
+                None => Err(extractor_error(err, &this.req)),
             },
             Ok(item) => Ok(Form(item)),
         };
@@ -19,10 +19,11 @@ use serde::{de::DeserializeOwned, Serialize};
 use crate::dev::Decompress;
 use crate::{
     body::EitherBody,
-    error::{Error, JsonPayloadError},
+    error::{extractor_error, Error, JsonPayloadError},
     extract::FromRequest,
     http::header::{ContentLength, Header as _},
     request::HttpRequest,
+    types::validate::{Valid, ValidExtractFut, Validate, Validator},
     web, HttpMessage, HttpResponse, Responder,
 };
 
@@ -153,16 +154,31 @@ impl<T: DeserializeOwned> FromRequest for Json<T> {
             req: Some(req.clone()),
             fut: JsonBody::new(req, payload, ctype_fn, ctype_required).limit(limit),
             err_handler,
+            validator: None,
         }
     }
 }
 
+/// See [`Valid`] for example of usage as an extractor.
+impl<T: DeserializeOwned + Validate> FromRequest for Valid<Json<T>> {
+    type Error = Error;
+    type Future = ValidExtractFut<JsonExtractFut<T>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let mut fut = Json::<T>::from_request(req, payload);
+        fut.validator = Some(T::validate);
+        ValidExtractFut { fut }
+    }
+}
+
 type JsonErrorHandler = Option<Arc<dyn Fn(JsonPayloadError, &HttpRequest) -> Error + Send + Sync>>;
 
 pub struct JsonExtractFut<T> {
     req: Option<HttpRequest>,
     fut: JsonBody<T>,
     err_handler: JsonErrorHandler,
+    validator: Validator<T>,
 }
 
 impl<T: DeserializeOwned> Future for JsonExtractFut<T> {
@@ -173,6 +189,13 @@ impl<T: DeserializeOwned> Future for JsonExtractFut<T> {
 
         let res = ready!(Pin::new(&mut this.fut).poll(cx));
 
+        let res = res.and_then(|data| match this.validator {
+            Some(validate) => validate(&data)
+                .map(|_| data)
+                .map_err(JsonPayloadError::Validate),
+            None => Ok(data),
+        });
+
         let res = match res {
             Err(err) => {
                 let req = this.req.take().unwrap();
@@ -185,7 +208,7 @@ impl<T: DeserializeOwned> Future for JsonExtractFut<T> {
                 if let Some(err_handler) = this.err_handler.as_ref() {
                     Err((*err_handler)(err, &req))
                 } else {
-                    Err(err.into())
+                    Err(extractor_error(err, &req))
                 }
             }
             Ok(data) => Ok(Json(data)),
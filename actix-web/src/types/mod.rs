@@ -8,6 +8,7 @@ mod path;
 mod payload;
 mod query;
 mod readlines;
+mod validate;
 
 pub use self::{
     either::Either,
@@ -18,4 +19,5 @@ pub use self::{
     payload::{Payload, PayloadConfig},
     query::{Query, QueryConfig},
     readlines::Readlines,
+    validate::{Valid, Validate},
 };
@@ -9,7 +9,9 @@ use serde::de;
 
 use crate::{
     dev::Payload,
-    error::{Error, ErrorNotFound, PathError},
+    error::{extractor_error, Error, InternalError, PathError},
+    http::StatusCode,
+    types::validate::{Valid, Validate, Validator},
     web::Data,
     FromRequest, HttpRequest,
 };
@@ -73,29 +75,60 @@ where
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let error_handler = req
-            .app_data::<PathConfig>()
-            .or_else(|| req.app_data::<Data<PathConfig>>().map(Data::get_ref))
-            .and_then(|c| c.err_handler.clone());
-
-        ready(
-            de::Deserialize::deserialize(PathDeserializer::new(req.match_info()))
-                .map(Path)
-                .map_err(move |err| {
-                    log::debug!(
-                        "Failed during Path extractor deserialization. \
-                         Request path: {:?}",
-                        req.path()
-                    );
-
-                    if let Some(error_handler) = error_handler {
-                        let e = PathError::Deserialize(err);
-                        (error_handler)(e, req)
-                    } else {
-                        ErrorNotFound(err)
-                    }
-                }),
-        )
+        ready(extract_path(req, None))
+    }
+}
+
+/// See [`Valid`] for example of usage as an extractor.
+impl<T> FromRequest for Valid<Path<T>>
+where
+    T: de::DeserializeOwned + Validate,
+{
+    type Error = Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(extract_path(req, Some(T::validate)).map(Valid))
+    }
+}
+
+fn extract_path<T: de::DeserializeOwned>(
+    req: &HttpRequest,
+    validator: Validator<T>,
+) -> Result<Path<T>, Error> {
+    let error_handler = req
+        .app_data::<PathConfig>()
+        .or_else(|| req.app_data::<Data<PathConfig>>().map(Data::get_ref))
+        .and_then(|c| c.err_handler.clone());
+
+    let val: T =
+        de::Deserialize::deserialize(PathDeserializer::new(req.match_info())).map_err(|err| {
+            log::debug!(
+                "Failed during Path extractor deserialization. \
+                 Request path: {:?}",
+                req.path()
+            );
+
+            if let Some(error_handler) = &error_handler {
+                let e = PathError::Deserialize(err);
+                (error_handler)(e, req)
+            } else {
+                extractor_error(InternalError::new(err, StatusCode::NOT_FOUND), req)
+            }
+        })?;
+
+    match validator.map_or(Ok(()), |validate| validate(&val)) {
+        Ok(()) => Ok(Path(val)),
+        Err(errs) => {
+            let e = PathError::Validate(errs);
+
+            if let Some(error_handler) = error_handler {
+                Err((error_handler)(e, req))
+            } else {
+                Err(extractor_error(e, req))
+            }
+        }
     }
 }
 
@@ -156,7 +189,11 @@ mod tests {
     use serde::Deserialize;
 
     use super::*;
-    use crate::{error, http, test::TestRequest, HttpResponse};
+    use crate::{
+        error, http,
+        test::{call_service, init_service, TestRequest},
+        web, App, HttpResponse,
+    };
 
     #[derive(Deserialize, Debug, Display)]
     #[display(fmt = "MyStruct({}, {})", key, value)]
@@ -286,4 +323,25 @@ mod tests {
 
         assert_eq!(res.status(), http::StatusCode::CONFLICT);
     }
+
+    #[actix_rt::test]
+    async fn constrained_segments_not_found() {
+        let srv = init_service(App::new().route(
+            "/user/{id<u64>}",
+            web::get().to(|id: Path<u64>| async move { id.to_string() }),
+        ))
+        .await;
+
+        let req = TestRequest::with_uri("/user/42").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), http::StatusCode::OK);
+
+        let req = TestRequest::with_uri("/user/-1").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), http::StatusCode::NOT_FOUND);
+
+        let req = TestRequest::with_uri("/user/18446744073709551616").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), http::StatusCode::NOT_FOUND);
+    }
 }
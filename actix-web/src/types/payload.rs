@@ -117,6 +117,33 @@ impl Payload {
         let stream = body::BodyStream::new(self.0);
         Ok(body::to_bytes(stream).await?)
     }
+
+    /// Takes the trailer fields sent after the request body.
+    ///
+    /// Trailers are only available once the payload has been read to its end. See
+    /// [`dev::Payload::take_trailers()`] for which requests can carry trailers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use actix_web::{web, Responder};
+    /// use futures_util::StreamExt as _;
+    ///
+    /// async fn checksum_handler(mut pl: web::Payload) -> actix_web::Result<impl Responder> {
+    ///     while let Some(chunk) = pl.next().await {
+    ///         let _chunk = chunk?;
+    ///     }
+    ///
+    ///     let checksum = pl
+    ///         .take_trailers()
+    ///         .and_then(|trailers| trailers.get("x-checksum").cloned());
+    ///
+    ///     Ok(format!("checksum: {checksum:?}"))
+    /// }
+    /// ```
+    pub fn take_trailers(&mut self) -> Option<header::HeaderMap> {
+        self.0.take_trailers()
+    }
 }
 
 impl Stream for Payload {
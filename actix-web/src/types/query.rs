@@ -2,10 +2,15 @@
 
 use std::{fmt, ops, sync::Arc};
 
-use actix_utils::future::{err, ok, Ready};
+use actix_utils::future::{ready, Ready};
 use serde::de::DeserializeOwned;
 
-use crate::{dev::Payload, error::QueryPayloadError, Error, FromRequest, HttpRequest};
+use crate::{
+    dev::Payload,
+    error::{extractor_error, QueryPayloadError},
+    types::validate::{Valid, Validate, Validator},
+    Error, FromRequest, HttpRequest,
+};
 
 /// Extract typed information from the request's query.
 ///
@@ -112,32 +117,55 @@ impl<T: DeserializeOwned> FromRequest for Query<T> {
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let error_handler = req
-            .app_data::<QueryConfig>()
-            .and_then(|c| c.err_handler.clone());
-
-        serde_urlencoded::from_str::<T>(req.query_string())
-            .map(|val| ok(Query(val)))
-            .unwrap_or_else(move |e| {
-                let e = QueryPayloadError::Deserialize(e);
-
-                log::debug!(
-                    "Failed during Query extractor deserialization. \
-                     Request path: {:?}",
-                    req.path()
-                );
-
-                let e = if let Some(error_handler) = error_handler {
-                    (error_handler)(e, req)
-                } else {
-                    e.into()
-                };
-
-                err(e)
-            })
+        ready(extract_query(req, None))
     }
 }
 
+/// See [`Valid`] for example of usage as an extractor.
+impl<T: DeserializeOwned + Validate> FromRequest for Valid<Query<T>> {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(extract_query(req, Some(T::validate)).map(Valid))
+    }
+}
+
+fn extract_query<T: DeserializeOwned>(
+    req: &HttpRequest,
+    validator: Validator<T>,
+) -> Result<Query<T>, Error> {
+    let error_handler = req
+        .app_data::<QueryConfig>()
+        .and_then(|c| c.err_handler.clone());
+
+    serde_urlencoded::from_str::<T>(req.query_string())
+        .map_err(|err| {
+            log::debug!(
+                "Failed during Query extractor deserialization. \
+                 Request path: {:?}",
+                req.path()
+            );
+
+            QueryPayloadError::Deserialize(err)
+        })
+        .and_then(|val| match validator {
+            Some(validate) => validate(&val)
+                .map(|_| val)
+                .map_err(QueryPayloadError::Validate),
+            None => Ok(val),
+        })
+        .map(Query)
+        .map_err(move |e| {
+            if let Some(error_handler) = error_handler {
+                (error_handler)(e, req)
+            } else {
+                extractor_error(e, req)
+            }
+        })
+}
+
 /// Query extractor configuration.
 ///
 /// # Examples
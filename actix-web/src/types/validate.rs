@@ -0,0 +1,339 @@
+//! For validating extractor documentation, see [`Valid`].
+
+use std::{
+    fmt,
+    future::Future,
+    ops,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use crate::{error::ValidationErrors, Error};
+
+/// Types that can check their own invariants after being deserialized.
+///
+/// Implement this trait for request payload types and extract them wrapped in [`Valid`] to have
+/// the checks run automatically before your handler is called.
+///
+/// When the `validator` crate feature is enabled, this trait is implemented for all types that
+/// implement `validator::Validate`.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::ValidationErrors, web};
+///
+/// struct NewUser {
+///     name: String,
+///     age: u8,
+/// }
+///
+/// impl web::Validate for NewUser {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///
+///         if self.name.is_empty() {
+///             errors.add("name", "must not be empty");
+///         }
+///
+///         if self.age < 18 {
+///             errors.add("age", "must be at least 18");
+///         }
+///
+///         errors.into_result()
+///     }
+/// }
+/// ```
+pub trait Validate {
+    /// Checks this value, returning all failures found.
+    fn validate(&self) -> Result<(), ValidationErrors>;
+}
+
+/// Function pointer used by extractor futures to run validation on a deserialized value.
+pub(crate) type Validator<T> = Option<fn(&T) -> Result<(), ValidationErrors>>;
+
+/// Extractor wrapper that runs [`Validate`] checks after deserialization.
+///
+/// Supported for [`Json`](super::Json), [`Form`](super::Form), [`Query`](super::Query) and
+/// [`Path`](super::Path) when their inner type implements [`Validate`].
+///
+/// Validation failures are reported using the wrapped extractor's own error type (e.g.,
+/// [`JsonPayloadError::Validate`](crate::error::JsonPayloadError::Validate)) so they are passed
+/// through any error handler set on the extractor's config type. Without a custom error handler,
+/// they result in a 422 Unprocessable Entity response listing each failed field.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::ValidationErrors, post, web};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Info {
+///     username: String,
+/// }
+///
+/// impl web::Validate for Info {
+///     fn validate(&self) -> Result<(), ValidationErrors> {
+///         let mut errors = ValidationErrors::new();
+///
+///         if self.username.len() < 3 {
+///             errors.add("username", "must be at least 3 characters");
+///         }
+///
+///         errors.into_result()
+///     }
+/// }
+///
+/// /// only called when the payload deserializes and passes validation
+/// #[post("/")]
+/// async fn index(info: web::Valid<web::Json<Info>>) -> String {
+///     format!("Welcome {}!", info.username)
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Valid<T>(pub T);
+
+impl<T> Valid<T> {
+    /// Unwrap into inner extractor.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> ops::Deref for Valid<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> ops::DerefMut for Valid<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Valid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+pin_project! {
+    /// Future wrapping an extractor's future which has been configured to run validation.
+    pub struct ValidExtractFut<Fut> {
+        #[pin]
+        pub(crate) fut: Fut,
+    }
+}
+
+impl<Fut, T> Future for ValidExtractFut<Fut>
+where
+    Fut: Future<Output = Result<T, Error>>,
+{
+    type Output = Result<Valid<T>, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let res = ready!(self.project().fut.poll(cx));
+        Poll::Ready(res.map(Valid))
+    }
+}
+
+#[cfg(feature = "validator")]
+impl<T: validator::Validate> Validate for T {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        validator::Validate::validate(self).map_err(ValidationErrors::from)
+    }
+}
+
+#[cfg(feature = "validator")]
+impl From<validator::ValidationErrors> for ValidationErrors {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        fn flatten(out: &mut ValidationErrors, path: &str, errors: validator::ValidationErrors) {
+            use validator::ValidationErrorsKind as Kind;
+
+            for (field, kind) in errors.into_errors() {
+                let field = if path.is_empty() {
+                    field.to_string()
+                } else {
+                    format!("{}.{}", path, field)
+                };
+
+                match kind {
+                    Kind::Field(errs) => {
+                        for err in errs {
+                            let message = match err.message {
+                                Some(msg) => msg.into_owned(),
+                                None => err.code.into_owned(),
+                            };
+
+                            out.add(field.clone(), message);
+                        }
+                    }
+
+                    Kind::Struct(errs) => flatten(out, &field, *errs),
+
+                    Kind::List(items) => {
+                        for (idx, errs) in items {
+                            flatten(out, &format!("{}[{}]", field, idx), *errs);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut out = ValidationErrors::new();
+        flatten(&mut out, "", errors);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{
+        error::{JsonPayloadError, QueryPayloadError},
+        http::{header, StatusCode},
+        test::{self, TestRequest},
+        web, App, FromRequest, HttpResponse,
+    };
+
+    #[derive(Debug, Deserialize)]
+    struct Info {
+        name: String,
+        age: u8,
+    }
+
+    impl Validate for Info {
+        fn validate(&self) -> Result<(), ValidationErrors> {
+            let mut errors = ValidationErrors::new();
+
+            if self.name.is_empty() {
+                errors.add("name", "must not be empty");
+            }
+
+            if self.age < 18 {
+                errors.add("age", "must be at least 18");
+            }
+
+            errors.into_result()
+        }
+    }
+
+    #[test]
+    fn merge_nested_paths() {
+        let mut inner = ValidationErrors::new();
+        inner.add("zip", "invalid");
+        inner.add("", "incomplete");
+
+        let mut list = ValidationErrors::new();
+        list.add("[1]", "too long");
+
+        let mut errors = ValidationErrors::new();
+        errors.merge("address", inner);
+        errors.merge("tags", list);
+
+        let fields = errors.iter().map(|err| err.field()).collect::<Vec<_>>();
+        assert_eq!(fields, ["address.zip", "address", "tags[1]"]);
+        assert_eq!(
+            errors.to_string(),
+            "address.zip: invalid; address: incomplete; tags[1]: too long"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn query_valid() {
+        let (req, mut pl) = TestRequest::with_uri("/?name=bob&age=30").to_http_parts();
+        let info = Valid::<web::Query<Info>>::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert_eq!(info.name, "bob");
+        assert_eq!(info.into_inner().into_inner().age, 30);
+
+        let (req, mut pl) = TestRequest::with_uri("/?name=&age=3").to_http_parts();
+        let err = Valid::<web::Query<Info>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        let err = err.as_error::<QueryPayloadError>().unwrap();
+        assert!(matches!(err, QueryPayloadError::Validate(errs) if errs.iter().count() == 2));
+    }
+
+    #[actix_rt::test]
+    async fn json_invalid_response() {
+        let srv = test::init_service(App::new().route(
+            "/",
+            web::post().to(|info: Valid<web::Json<Info>>| async move { info.name.clone() }),
+        ))
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "name": "bob", "age": 30 }))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = TestRequest::post()
+            .uri("/")
+            .set_json(serde_json::json!({ "name": "", "age": 30 }))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(
+            body,
+            serde_json::json!({ "errors": [{ "field": "name", "message": "must not be empty" }] })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn json_custom_err_handler() {
+        let (req, mut pl) = TestRequest::post()
+            .set_json(serde_json::json!({ "name": "bob", "age": 3 }))
+            .app_data(web::JsonConfig::default().error_handler(|err, _| {
+                assert!(matches!(err, JsonPayloadError::Validate(_)));
+                crate::error::InternalError::from_response(err, HttpResponse::Conflict().finish())
+                    .into()
+            }))
+            .to_http_parts();
+
+        let err = Valid::<web::Json<Info>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(err.error_response().status(), StatusCode::CONFLICT);
+    }
+
+    #[actix_rt::test]
+    async fn form_and_path_invalid() {
+        let (req, mut pl) = TestRequest::post()
+            .set_form([("name", "bob"), ("age", "3")])
+            .to_http_parts();
+        let err = Valid::<web::Form<Info>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.error_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+
+        let mut req = TestRequest::with_uri("/bob/3").to_srv_request();
+        actix_router::ResourceDef::new("/{name}/{age}").capture_match_info(req.match_info_mut());
+        let (req, mut pl) = req.into_parts();
+        let err = Valid::<web::Path<Info>>::from_request(&req, &mut pl)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.error_response().status(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
@@ -9,6 +9,7 @@
 //! - [`Json`]: JSON payload
 //! - [`Form`]: URL-encoded payload
 //! - [`Bytes`]: Raw payload
+//! - [`Valid`]: Validated `Json`, `Form`, `Query` or `Path`
 //!
 //! # Responders
 //! - [`Json`]: JSON response
@@ -22,7 +23,12 @@ use actix_router::IntoPatterns;
 pub use bytes::{Buf, BufMut, Bytes, BytesMut};
 
 pub use crate::{
-    config::ServiceConfig, data::Data, redirect::Redirect, request_data::ReqData, types::*,
+    config::ServiceConfig,
+    data::Data,
+    dynamic_router::{DynamicRouter, RoutingTable},
+    redirect::Redirect,
+    request_data::ReqData,
+    types::*,
 };
 use crate::{
     error::BlockingError, http::Method, service::WebService, FromRequest, Handler, Resource,
@@ -201,6 +207,31 @@ pub fn redirect(from: impl Into<Cow<'static, str>>, to: impl Into<Cow<'static, s
     Redirect::new(from, to)
 }
 
+/// Creates a gRPC service for the fully-qualified service name `name`, like `helloworld.Greeter`.
+///
+/// See [`GrpcService`](crate::grpc::GrpcService) docs for usage details.
+///
+/// # Examples
+/// ```
+/// use actix_web::{grpc::Status, web, App, HttpRequest};
+///
+/// #[derive(Clone, PartialEq, prost::Message)]
+/// struct Ping {
+///     #[prost(uint64, tag = "1")]
+///     id: u64,
+/// }
+///
+/// async fn ping(_req: HttpRequest, msg: Ping) -> Result<Ping, Status> {
+///     Ok(msg)
+/// }
+///
+/// let app = App::new().service(web::grpc("health.Pinger").unary("Ping", ping));
+/// ```
+#[cfg(feature = "grpc")]
+pub fn grpc(name: impl Into<String>) -> crate::grpc::GrpcService {
+    crate::grpc::GrpcService::new(name)
+}
+
 /// Executes blocking function on a thread pool, returns future that resolves to result of the
 /// function execution.
 pub fn block<F, R>(f: F) -> impl Future<Output = Result<R, BlockingError>>
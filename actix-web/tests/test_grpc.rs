@@ -0,0 +1,431 @@
+#[cfg(feature = "openssl")]
+extern crate tls_openssl as openssl;
+
+use std::time::Duration;
+
+use actix_web::{
+    grpc::{Code, Status, Streaming},
+    http::{header, StatusCode},
+    web, App, HttpRequest,
+};
+use bytes::{Buf as _, BufMut as _, Bytes, BytesMut};
+use futures_util::{stream, Stream, StreamExt as _};
+use prost::Message as _;
+
+mod utils;
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct EchoRequest {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct EchoReply {
+    #[prost(string, tag = "1")]
+    message: String,
+}
+
+fn request(message: &str) -> EchoRequest {
+    EchoRequest {
+        message: message.to_owned(),
+    }
+}
+
+fn reply(message: &str) -> EchoReply {
+    EchoReply {
+        message: message.to_owned(),
+    }
+}
+
+async fn echo(_req: HttpRequest, msg: EchoRequest) -> Result<EchoReply, Status> {
+    match msg.message.as_str() {
+        "" => Err(Status::new(Code::InvalidArgument, "message is required")),
+        "sleep" => {
+            actix_rt::time::sleep(Duration::from_secs(5)).await;
+            Ok(reply("late"))
+        }
+        message => Ok(reply(message)),
+    }
+}
+
+async fn echo_all(
+    _req: HttpRequest,
+    messages: Streaming<EchoRequest>,
+) -> Result<impl Stream<Item = Result<EchoReply, Status>>, Status> {
+    Ok(messages.map(|msg| msg.map(|msg| reply(&msg.message))))
+}
+
+async fn count(
+    _req: HttpRequest,
+    mut messages: Streaming<EchoRequest>,
+) -> Result<impl Stream<Item = Result<EchoReply, Status>>, Status> {
+    let mut count = 0;
+
+    while let Some(msg) = messages.next().await {
+        msg?;
+        count += 1;
+    }
+
+    Ok(stream::once(async move { Ok(reply(&count.to_string())) }))
+}
+
+fn app() -> App<
+    impl actix_web::dev::ServiceFactory<
+        actix_web::dev::ServiceRequest,
+        Config = (),
+        Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+        Error = actix_web::Error,
+        InitError = (),
+    >,
+> {
+    App::new().service(
+        web::grpc("test.Echo")
+            .max_message_size(64)
+            .unary("Echo", echo)
+            .streaming("EchoAll", echo_all)
+            .streaming("Count", count),
+    )
+}
+
+fn encode(flag: u8, data: &[u8]) -> Bytes {
+    let mut buf = BytesMut::new();
+    buf.put_u8(flag);
+    buf.put_u32(data.len() as u32);
+    buf.extend_from_slice(data);
+    buf.freeze()
+}
+
+fn encode_all(messages: &[EchoRequest]) -> Bytes {
+    let mut buf = BytesMut::new();
+
+    for msg in messages {
+        buf.extend_from_slice(&encode(0, &msg.encode_to_vec()));
+    }
+
+    buf.freeze()
+}
+
+fn decode_all(mut body: Bytes) -> Vec<EchoReply> {
+    let mut messages = Vec::new();
+
+    while body.has_remaining() {
+        assert_eq!(body.get_u8(), 0);
+        let len = body.get_u32() as usize;
+        messages.push(EchoReply::decode(body.split_to(len)).unwrap());
+    }
+
+    messages
+}
+
+#[actix_rt::test]
+async fn unary() {
+    let srv = actix_test::start(app);
+
+    let mut res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .send_body(encode_all(&[request("hello")]))
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/grpc"
+    );
+    assert!(res
+        .headers()
+        .get("grpc-accept-encoding")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("gzip"));
+
+    let body = res.body().await.unwrap();
+    assert_eq!(decode_all(body), vec![reply("hello")]);
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn trailers_only_errors() {
+    let srv = actix_test::start(app);
+
+    let call = |path: &'static str, body: Bytes| {
+        srv.post(path)
+            .insert_header((header::CONTENT_TYPE, "application/grpc+proto"))
+            .send_body(body)
+    };
+
+    // handler error
+    let mut res = call("/test.Echo/Echo", encode_all(&[request("")]))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(
+        res.headers().get(header::CONTENT_TYPE).unwrap(),
+        "application/grpc+proto"
+    );
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "3");
+    assert_eq!(
+        res.headers().get("grpc-message").unwrap(),
+        "message is required"
+    );
+    assert!(res.body().await.unwrap().is_empty());
+
+    // unknown method
+    let res = call("/test.Echo/Missing", encode_all(&[request("hello")]))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "12");
+
+    // message exceeding maximum size
+    let res = call("/test.Echo/Echo", encode_all(&[request(&"a".repeat(100))]))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "8");
+
+    // malformed message
+    let res = call("/test.Echo/Echo", encode(0, b"\xff\xff"))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "13");
+
+    // missing message
+    let res = call("/test.Echo/Echo", Bytes::new()).await.unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "13");
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn non_grpc_requests() {
+    let srv = actix_test::start(app);
+
+    let res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/json"))
+        .send_body("{}")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+    let res = srv.get("/test.Echo/Echo").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+    let res = srv.post("/test.Other/Echo").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn streaming() {
+    let srv = actix_test::start(app);
+
+    let messages = [request("a"), request("b"), request("c")];
+
+    let mut res = srv
+        .post("/test.Echo/EchoAll")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .send_body(encode_all(&messages))
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers().get("grpc-status").is_none());
+
+    let body = res.body().await.unwrap();
+    assert_eq!(decode_all(body), vec![reply("a"), reply("b"), reply("c")]);
+
+    // messages may be split across chunks
+    let body = encode_all(&messages);
+    let chunks = stream::iter(
+        body.chunks(4)
+            .map(|chunk| Ok::<_, actix_web::Error>(Bytes::copy_from_slice(chunk)))
+            .collect::<Vec<_>>(),
+    );
+
+    let mut res = srv
+        .post("/test.Echo/Count")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .send_stream(chunks)
+        .await
+        .unwrap();
+    let body = res.body().await.unwrap();
+    assert_eq!(decode_all(body), vec![reply("3")]);
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn compressed_messages() {
+    let srv = actix_test::start(app);
+
+    let data = utils::gzip::encode(request("compressed").encode_to_vec());
+
+    let mut res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .insert_header(("grpc-encoding", "gzip"))
+        .send_body(encode(1, &data))
+        .await
+        .unwrap();
+    let body = res.body().await.unwrap();
+    assert_eq!(decode_all(body), vec![reply("compressed")]);
+
+    let res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .insert_header(("grpc-encoding", "snappy"))
+        .send_body(encode(1, &data))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "12");
+
+    // compressed flag without a message encoding
+    let res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .send_body(encode(1, &data))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "13");
+
+    let res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .insert_header(("grpc-encoding", "identity"))
+        .send_body(encode(1, &data))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "13");
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn deadline() {
+    let srv = actix_test::start(app);
+
+    let res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .insert_header(("grpc-timeout", "50m"))
+        .send_body(encode_all(&[request("sleep")]))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "4");
+
+    let res = srv
+        .post("/test.Echo/Echo")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .insert_header(("grpc-timeout", "soon"))
+        .send_body(encode_all(&[request("hello")]))
+        .await
+        .unwrap();
+    assert_eq!(res.headers().get("grpc-status").unwrap(), "3");
+
+    srv.stop().await;
+}
+
+/// Sends a gRPC request over HTTP/2, returning the response body and trailers.
+async fn call_h2(
+    client: &h2::client::SendRequest<Bytes>,
+    uri: String,
+    body: Bytes,
+) -> (Bytes, http::HeaderMap) {
+    let req = http::Request::post(uri)
+        .header(header::CONTENT_TYPE, "application/grpc")
+        .header(header::TE, "trailers")
+        .body(())
+        .unwrap();
+
+    let mut client = client.clone().ready().await.unwrap();
+    let (res, mut send) = client.send_request(req, false).unwrap();
+    send.send_data(body, true).unwrap();
+
+    let res = res.await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let mut recv = res.into_body();
+    let mut body = BytesMut::new();
+
+    while let Some(chunk) = recv.data().await {
+        let chunk = chunk.unwrap();
+        recv.flow_control().release_capacity(chunk.len()).unwrap();
+        body.extend_from_slice(&chunk);
+    }
+
+    let trailers = recv.trailers().await.unwrap().unwrap();
+
+    (body.freeze(), trailers)
+}
+
+#[actix_rt::test]
+async fn trailers_h2() {
+    let srv = actix_test::start_with(actix_test::config().h2(), app);
+
+    let stream = tokio::net::TcpStream::connect(srv.addr()).await.unwrap();
+    let (client, conn) = h2::client::handshake(stream).await.unwrap();
+    actix_rt::spawn(conn);
+
+    // successful call
+    let (body, trailers) = call_h2(
+        &client,
+        srv.url("/test.Echo/EchoAll"),
+        encode_all(&[request("a"), request("b")]),
+    )
+    .await;
+    assert_eq!(decode_all(body), vec![reply("a"), reply("b")]);
+    assert_eq!(trailers.get("grpc-status").unwrap(), "0");
+    assert!(trailers.get("grpc-message").is_none());
+
+    // stream that fails after sending a message
+    let mut messages = BytesMut::from(&encode_all(&[request("a")])[..]);
+    messages.extend_from_slice(&encode(0, b"\xff\xff"));
+
+    let (body, trailers) = call_h2(&client, srv.url("/test.Echo/EchoAll"), messages.freeze()).await;
+    assert_eq!(decode_all(body), vec![reply("a")]);
+    assert_eq!(trailers.get("grpc-status").unwrap(), "13");
+    assert!(trailers.get("grpc-message").is_some());
+
+    srv.stop().await;
+}
+
+#[cfg(feature = "openssl")]
+#[actix_rt::test]
+async fn unary_h2() {
+    use actix_web::http::Version;
+    use openssl::{
+        pkey::PKey,
+        ssl::{SslAcceptor, SslMethod},
+        x509::X509,
+    };
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+    let key = PKey::private_key_from_pem(cert.serialize_private_key_pem().as_bytes()).unwrap();
+    let cert = X509::from_pem(cert.serialize_pem().unwrap().as_bytes()).unwrap();
+
+    let mut builder = SslAcceptor::mozilla_intermediate(SslMethod::tls()).unwrap();
+    builder.set_certificate(&cert).unwrap();
+    builder.set_private_key(&key).unwrap();
+    builder.set_alpn_select_callback(|_, _| Ok(b"h2"));
+    builder.set_alpn_protos(b"\x02h2").unwrap();
+
+    let srv = actix_test::start_with(actix_test::config().openssl(builder.build()), app);
+
+    let mut res = srv
+        .post("/test.Echo/EchoAll")
+        .insert_header((header::CONTENT_TYPE, "application/grpc"))
+        .send_body(encode_all(&[request("a"), request("b")]))
+        .await
+        .unwrap();
+    assert_eq!(res.version(), Version::HTTP_2);
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = res.body().await.unwrap();
+    assert_eq!(decode_all(body), vec![reply("a"), reply("b")]);
+
+    srv.stop().await;
+}
@@ -133,3 +133,158 @@ async fn test_start_ssl() {
 
     srv.stop(false).await;
 }
+
+/// Starts a server with a TCP listener and a QUIC endpoint sharing its port, returning the server
+/// handle, the address and a QUIC client endpoint configured to trust the server.
+#[cfg(feature = "http3")]
+fn start_quic_server(
+    workers: usize,
+) -> (
+    actix_web::dev::ServerHandle,
+    std::net::SocketAddr,
+    actix_http::h3::reexports::quinn::Endpoint,
+) {
+    use std::sync::Arc;
+
+    use actix_http::h3::reexports::{
+        quinn::{self, crypto::rustls::QuicClientConfig},
+        rustls::{
+            self,
+            pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+        },
+    };
+    use actix_web::{dev::Service as _, error::ErrorBadRequest, HttpRequest};
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_owned()]).unwrap();
+    let cert_der = CertificateDer::from(cert.serialize_der().unwrap());
+    let key_der = PrivatePkcs8KeyDer::from(cert.serialize_private_key_der());
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+    let server_config = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert_der.clone()], PrivateKeyDer::Pkcs8(key_der))
+        .unwrap();
+
+    // QUIC endpoint shares its port with the TCP listener
+    let lst = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = lst.local_addr().unwrap();
+    let socket = std::net::UdpSocket::bind(addr).unwrap();
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let srv = HttpServer::new(|| {
+                    App::new()
+                        .wrap_fn(|req, srv| {
+                            let fut = (req.path() != "/error").then(|| srv.call(req));
+
+                            async move {
+                                match fut {
+                                    Some(fut) => fut.await,
+                                    None => Err(ErrorBadRequest("error")),
+                                }
+                            }
+                        })
+                        .service(web::resource("/").route(web::to(|req: HttpRequest| {
+                            assert!(!req.app_config().secure());
+                            async { HttpResponse::Ok().body("test") }
+                        })))
+                })
+                .workers(workers)
+                .shutdown_timeout(1)
+                .disable_signals()
+                .listen(lst)
+                .unwrap()
+                .listen_quic(socket, server_config)
+                .unwrap()
+                .run();
+
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap()
+    });
+    let srv = rx.recv().unwrap();
+
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add(cert_der).unwrap();
+
+    let mut client_config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    client_config.alpn_protocols = vec![b"h3".to_vec()];
+    let client_config = QuicClientConfig::try_from(client_config).unwrap();
+
+    let mut endpoint = quinn::Endpoint::client("127.0.0.1:0".parse().unwrap()).unwrap();
+    endpoint.set_default_client_config(quinn::ClientConfig::new(Arc::new(client_config)));
+
+    (srv, addr, endpoint)
+}
+
+#[actix_rt::test]
+#[cfg(feature = "http3")]
+async fn test_start_quic() {
+    use actix_web::http::{header, StatusCode};
+
+    let (srv, addr, endpoint) = start_quic_server(2);
+    let alt_svc = format!("h3=\":{}\"; ma=86400", addr.port());
+
+    // TCP responses advertise the QUIC endpoint
+    let client = awc::Client::new();
+
+    let response = client.get(format!("http://{}", addr)).send().await.unwrap();
+    assert!(response.status().is_success());
+    assert_eq!(
+        response.headers().get(header::ALT_SVC).unwrap(),
+        alt_svc.as_str()
+    );
+
+    // including responses to service errors
+    let response = client
+        .get(format!("http://{}/error", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response.headers().get(header::ALT_SVC).unwrap(),
+        alt_svc.as_str()
+    );
+
+    // connections are accepted by multiple workers
+    let mut conns = Vec::new();
+    for _ in 0..4 {
+        conns.push(endpoint.connect(addr, "localhost").unwrap().await.unwrap());
+    }
+
+    // stopping the server closes QUIC connections along with the other workers
+    srv.stop(true).await;
+
+    for conn in conns {
+        actix_rt::time::timeout(Duration::from_secs(5), conn.closed())
+            .await
+            .unwrap();
+    }
+}
+
+#[actix_rt::test]
+#[cfg(feature = "http3")]
+async fn test_stop_quic_forced() {
+    let (srv, addr, endpoint) = start_quic_server(1);
+
+    let conn = endpoint.connect(addr, "localhost").unwrap().await.unwrap();
+
+    // forced shutdown closes QUIC connections without waiting for them
+    srv.stop(false).await;
+
+    actix_rt::time::timeout(Duration::from_secs(5), conn.closed())
+        .await
+        .unwrap();
+}
@@ -0,0 +1,85 @@
+use actix_web::{
+    get,
+    openapi::{OpenApi, Schema},
+    post,
+    test::{call_and_read_body_json, init_service, TestRequest},
+    web, App, HttpResponse, Responder,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Deserialize, Serialize)]
+struct Item {
+    id: u64,
+    name: String,
+}
+
+impl Schema for Item {
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": { "id": u64::schema(), "name": String::schema() },
+            "required": ["id", "name"],
+        })
+    }
+}
+
+/// Get an item.
+///
+/// Looks up a single item
+/// by its ID.
+#[get("/items/{id}")]
+async fn get_item(id: web::Path<u64>) -> web::Json<Item> {
+    web::Json(Item {
+        id: *id,
+        name: String::new(),
+    })
+}
+
+#[post("/items")]
+async fn create_item(item: web::Json<Item>, _req: actix_web::HttpRequest) -> impl Responder {
+    HttpResponse::Created().json(item.into_inner())
+}
+
+#[actix_rt::test]
+async fn macro_routes() {
+    let srv = init_service(
+        App::new()
+            .service(get_item)
+            .service(create_item)
+            .service(OpenApi::new("/openapi.json")),
+    )
+    .await;
+
+    let req = TestRequest::get().uri("/openapi.json").to_request();
+    let doc: Value = call_and_read_body_json(&srv, req).await;
+
+    assert_eq!(
+        doc["paths"],
+        json!({
+            "/items/{id}": {
+                "get": {
+                    "summary": "Get an item.",
+                    "description": "Looks up a single item\nby its ID.",
+                    "parameters": [
+                        { "name": "id", "in": "path", "required": true, "schema": u64::schema() },
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "OK",
+                            "content": { "application/json": { "schema": Item::schema() } },
+                        },
+                    },
+                },
+            },
+            "/items": {
+                "post": {
+                    "requestBody": {
+                        "content": { "application/json": { "schema": Item::schema() } },
+                        "required": true,
+                    },
+                },
+            },
+        })
+    );
+}
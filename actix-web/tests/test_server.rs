@@ -13,8 +13,9 @@ use std::{
 
 use actix_web::{
     cookie::Cookie,
+    error,
     http::{header, StatusCode},
-    middleware::{Compress, NormalizePath, TrailingSlash},
+    middleware::{Compress, ExpectContinue, NormalizePath, TrailingSlash},
     web, App, Error, HttpResponse,
 };
 use bytes::Bytes;
@@ -848,6 +849,115 @@ async fn test_slow_request() {
     srv.stop().await;
 }
 
+#[actix_rt::test]
+async fn test_expect_continue() {
+    use std::net;
+
+    let srv = actix_test::start_with(actix_test::config().h1().defer_continue(true), || {
+        App::new().service(
+            web::resource("/upload")
+                .wrap(ExpectContinue::new(|req| {
+                    let allowed = req.headers().contains_key(header::AUTHORIZATION);
+
+                    async move {
+                        if allowed {
+                            Ok(())
+                        } else {
+                            Err(error::ErrorUnauthorized("missing credentials"))
+                        }
+                    }
+                }))
+                .route(web::post().to(|body: Bytes| async move { body })),
+        )
+    });
+
+    // rejected before the payload is sent; connection is closed after the response
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"POST /upload HTTP/1.1\r\nContent-Length: 5\r\nExpect: 100-continue\r\n\r\n")
+        .unwrap();
+    let mut data = String::new();
+    stream.read_to_string(&mut data).unwrap();
+    assert!(data.starts_with("HTTP/1.1 401 Unauthorized"));
+    assert!(data.contains("connection: close"));
+    assert!(!data.contains("100 Continue"));
+
+    // accepted; `100 Continue` is sent once the handler reads the payload
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(
+            b"POST /upload HTTP/1.1\r\nAuthorization: Bearer token\r\nConnection: close\r\n\
+            Content-Length: 5\r\nExpect: 100-continue\r\n\r\n",
+        )
+        .unwrap();
+    let mut buf = [0; 25];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"HTTP/1.1 100 Continue\r\n\r\n");
+
+    stream.write_all(b"hello").unwrap();
+    let mut data = String::new();
+    stream.read_to_string(&mut data).unwrap();
+    assert!(data.starts_with("HTTP/1.1 200 OK"));
+    assert!(data.ends_with("\r\n\r\nhello"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn test_early_hints() {
+    use std::net;
+
+    use actix_web::{
+        http::header::{HeaderMap, HeaderValue},
+        HttpRequest,
+    };
+
+    let srv = actix_test::start_with(actix_test::config().h1(), || {
+        App::new().service(web::resource("/").to(|req: HttpRequest| async move {
+            let mut hints = HeaderMap::new();
+            hints.append(
+                header::LINK,
+                HeaderValue::from_static("</style.css>; rel=preload; as=style"),
+            );
+            assert!(req.send_early_hints(hints));
+
+            let mut hints = HeaderMap::new();
+            hints.append(
+                header::LINK,
+                HeaderValue::from_static("</script.js>; rel=preload; as=script"),
+            );
+            assert!(req.send_early_hints(hints));
+
+            HttpResponse::Ok().body("page")
+        }))
+    });
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut data = String::new();
+    stream.read_to_string(&mut data).unwrap();
+
+    let hints = "\
+        HTTP/1.1 103 Early Hints\r\n\
+        link: </style.css>; rel=preload; as=style\r\n\
+        \r\n\
+        HTTP/1.1 103 Early Hints\r\n\
+        link: </script.js>; rel=preload; as=script\r\n\
+        \r\n\
+        HTTP/1.1 200 OK\r\n";
+    assert!(data.starts_with(hints));
+    assert!(data.ends_with("\r\n\r\npage"));
+
+    // client skips early hints
+    let mut res = srv.get("/").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body().await.unwrap(), "page");
+
+    srv.stop().await;
+}
+
 #[actix_rt::test]
 async fn test_normalize() {
     let srv = actix_test::start_with(actix_test::config().h1(), || {
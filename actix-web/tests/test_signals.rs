@@ -0,0 +1,81 @@
+#![cfg(unix)]
+
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use actix_rt::{
+    signal::unix::{signal, SignalKind},
+    time::sleep,
+};
+use actix_web::{http::KeepAlive, web, App, HttpResponse, HttpServer};
+
+#[actix_rt::test]
+async fn sigterm_sends_goaway() {
+    // keeps the process alive if the signal arrives before the server listens for it
+    let _sigterm = signal(SignalKind::terminate()).unwrap();
+
+    let lst = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = lst.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let join = thread::spawn(move || {
+        actix_rt::System::new().block_on(async {
+            let srv = HttpServer::new(|| {
+                App::new().route(
+                    "/",
+                    web::to(|| async {
+                        sleep(Duration::from_millis(500)).await;
+                        HttpResponse::Ok()
+                    }),
+                )
+            })
+            .workers(1)
+            // no pings, so only GOAWAY can close an idle connection
+            .keep_alive(KeepAlive::Disabled)
+            .listen_auto_h2c(lst)
+            .unwrap()
+            .run();
+
+            tx.send(()).unwrap();
+
+            srv.await
+        })
+    });
+
+    rx.recv().unwrap();
+
+    let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+    let (mut client, conn) = h2::client::handshake(stream).await.unwrap();
+    let conn = actix_rt::spawn(conn);
+
+    let req = http::Request::get(format!("http://{addr}/"))
+        .body(())
+        .unwrap();
+    let (res, _) = client.send_request(req, true).unwrap();
+
+    // give the request time to reach the handler before signalling the server
+    sleep(Duration::from_millis(100)).await;
+
+    let now = Instant::now();
+    let status = std::process::Command::new("kill")
+        .args(["-TERM", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // in-flight request is still served
+    let res = res.await.unwrap();
+    assert_eq!(res.status().as_u16(), 200);
+
+    // GOAWAY closes the connection cleanly once the in-flight stream is finished
+    conn.await.unwrap().unwrap();
+
+    // server and its system are stopped by the signal
+    join.join().unwrap().unwrap();
+
+    // server shutdown is not waiting for graceful shutdown timeout
+    assert!(now.elapsed() < Duration::from_secs(30));
+}
@@ -11,10 +11,10 @@ use actix_http::{body::MessageBody, h1::ClientCodec, Payload, RequestHeadType, R
 use actix_rt::task::JoinHandle;
 use bytes::Bytes;
 use futures_core::future::LocalBoxFuture;
-use h2::client::SendRequest;
+use h2::{client::SendRequest, Ping, PingPong};
 
 use super::{error::SendRequestError, h1proto, h2proto, pool::Acquired};
-use crate::BoxError;
+use crate::{BoxError, BoxedSocket};
 
 /// Trait alias for types impl [tokio::io::AsyncRead] and [tokio::io::AsyncWrite].
 pub trait ConnectionIo: AsyncRead + AsyncWrite + Unpin + 'static {}
@@ -139,6 +139,17 @@ impl<Io: ConnectionIo> H2Connection<Io> {
         self.acquired
             .release(ConnectionInnerType::H2(io), self.created);
     }
+
+    /// Waits until the peer's settings have been received.
+    ///
+    /// The settings are the first frame sent by a server, so they have been received once a ping
+    /// is acknowledged. Only the first call on a connection sends a ping.
+    pub(super) async fn settings_received(&mut self) -> Result<(), h2::Error> {
+        match self.io.as_mut().unwrap().ping_pong.take() {
+            Some(mut ping_pong) => ping_pong.ping(Ping::opaque()).await.map(|_| ()),
+            None => Ok(()),
+        }
+    }
 }
 
 /// `H2ConnectionInner` has two parts: `SendRequest` and `Connection`.
@@ -148,18 +159,25 @@ impl<Io: ConnectionIo> H2Connection<Io> {
 pub(super) struct H2ConnectionInner {
     handle: JoinHandle<()>,
     sender: SendRequest<Bytes>,
+    ping_pong: Option<PingPong>,
 }
 
 impl H2ConnectionInner {
     pub(super) fn new<Io: ConnectionIo>(
         sender: SendRequest<Bytes>,
-        connection: h2::client::Connection<Io>,
+        mut connection: h2::client::Connection<Io>,
     ) -> Self {
+        let ping_pong = connection.ping_pong();
+
         let handle = actix_rt::spawn(async move {
             let _ = connection.await;
         });
 
-        Self { handle, sender }
+        Self {
+            handle,
+            sender,
+            ping_pong,
+        }
     }
 }
 
@@ -268,26 +286,32 @@ where
     }
 
     /// Send request, returns Response and Framed tunnel.
+    ///
+    /// On HTTP/2 connections, tunnels are opened using extended CONNECT (RFC 8441) if the request
+    /// has a [protocol](actix_http::RequestHead::protocol) and the server supports it.
     pub fn open_tunnel<H: Into<RequestHeadType> + 'static>(
         self,
         head: H,
     ) -> LocalBoxFuture<
         'static,
-        Result<(ResponseHead, Framed<Connection<A, B>, ClientCodec>), SendRequestError>,
+        Result<(ResponseHead, Framed<BoxedSocket, ClientCodec>), SendRequestError>,
     > {
         Box::pin(async move {
             match self {
                 Connection::Tcp(ConnectionType::H1(ref _conn)) => {
                     let (head, framed) = h1proto::open_tunnel(self, head.into()).await?;
-                    Ok((head, framed))
+                    Ok((head, framed.into_map_io(|io| Box::new(io) as _)))
                 }
                 Connection::Tls(ConnectionType::H1(ref _conn)) => {
                     let (head, framed) = h1proto::open_tunnel(self, head.into()).await?;
-                    Ok((head, framed))
+                    Ok((head, framed.into_map_io(|io| Box::new(io) as _)))
                 }
-                Connection::Tls(ConnectionType::H2(mut conn)) => {
-                    conn.release();
-                    Err(SendRequestError::TunnelNotSupported)
+                Connection::Tls(ConnectionType::H2(conn)) => {
+                    let (head, tunnel) = h2proto::open_tunnel(conn, head.into()).await?;
+                    Ok((
+                        head,
+                        Framed::new(Box::new(tunnel) as _, ClientCodec::default()),
+                    ))
                 }
                 Connection::Tcp(ConnectionType::H2(_)) => {
                     unreachable!("Plain Tcp connection can be used only in Http1 protocol")
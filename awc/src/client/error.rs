@@ -112,6 +112,8 @@ pub enum SendRequestError {
     Timeout,
 
     /// Tunnels are not supported for HTTP/2 connection
+    ///
+    /// Returned when the server does not support extended CONNECT requests (RFC 8441).
     #[display(fmt = "Tunnels are not supported for http2 connection")]
     TunnelNotSupported,
 
@@ -88,9 +88,15 @@ where
     let (do_send, mut res_head) = if is_expect {
         pin_framed.send((head, body.size()).into()).await?;
 
-        let head = poll_fn(|cx| pin_framed.as_mut().poll_next(cx))
-            .await
-            .ok_or(ConnectError::Disconnected)??;
+        let head = loop {
+            let head = poll_fn(|cx| pin_framed.as_mut().poll_next(cx))
+                .await
+                .ok_or(ConnectError::Disconnected)??;
+
+            if !is_early_hints(head.status) {
+                break head;
+            }
+        };
 
         // return response head in case status code is not continue
         // and current head would be used as final response head.
@@ -111,9 +117,15 @@ where
         };
 
         // read response and init read body
-        let head = poll_fn(|cx| pin_framed.as_mut().poll_next(cx))
-            .await
-            .ok_or(ConnectError::Disconnected)??;
+        let head = loop {
+            let head = poll_fn(|cx| pin_framed.as_mut().poll_next(cx))
+                .await
+                .ok_or(ConnectError::Disconnected)??;
+
+            if !is_early_hints(head.status) {
+                break head;
+            }
+        };
 
         res_head = Some(head);
     }
@@ -136,6 +148,12 @@ where
     }
 }
 
+/// Returns true for `103 Early Hints` informational responses, which are followed by the final
+/// response.
+fn is_early_hints(status: StatusCode) -> bool {
+    status.as_u16() == 103
+}
+
 pub(crate) async fn open_tunnel<Io>(
     io: Io,
     head: RequestHeadType,
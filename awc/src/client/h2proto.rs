@@ -1,5 +1,12 @@
-use std::future::Future;
+use std::{
+    cmp,
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
+use actix_codec::{AsyncRead, AsyncWrite, ReadBuf};
 use actix_http::{
     body::{BodySize, MessageBody},
     header::HeaderMap,
@@ -7,12 +14,16 @@ use actix_http::{
 };
 use actix_utils::future::poll_fn;
 use bytes::Bytes;
+use futures_core::ready;
 use h2::{
     client::{Builder, Connection, SendRequest},
-    SendStream,
+    ext::Protocol,
+    RecvStream, SendStream,
 };
 use http::{
-    header::{HeaderValue, CONNECTION, CONTENT_LENGTH, TRANSFER_ENCODING},
+    header::{
+        HeaderValue, CONNECTION, CONTENT_LENGTH, SEC_WEBSOCKET_KEY, TRANSFER_ENCODING, UPGRADE,
+    },
     request::Request,
     Method, Version,
 };
@@ -184,6 +195,188 @@ where
     }
 }
 
+/// Opens a tunnel using an extended CONNECT request (RFC 8441).
+///
+/// The protocol of the tunnel is taken from [`RequestHead::protocol`]; requests without a protocol
+/// can not be tunneled over HTTP/2.
+///
+/// [`RequestHead::protocol`]: actix_http::RequestHead::protocol
+pub(crate) async fn open_tunnel<Io>(
+    mut io: H2Connection<Io>,
+    head: RequestHeadType,
+) -> Result<(ResponseHead, H2Tunnel), SendRequestError>
+where
+    Io: ConnectionIo,
+{
+    trace!("Opening extended CONNECT tunnel: {:?}", head);
+
+    let protocol = match head.as_ref().protocol() {
+        Some(protocol) => Protocol::from(protocol),
+        None => {
+            io.on_release(false);
+            return Err(SendRequestError::TunnelNotSupported);
+        }
+    };
+
+    if let Err(err) = io.settings_received().await {
+        io.on_release(err.is_io());
+        return Err(err.into());
+    }
+
+    if !io.is_extended_connect_protocol_enabled() {
+        io.on_release(false);
+        return Err(SendRequestError::TunnelNotSupported);
+    }
+
+    let mut req = Request::new(());
+    *req.uri_mut() = head.as_ref().uri.clone();
+    *req.method_mut() = Method::CONNECT;
+    *req.version_mut() = Version::HTTP_2;
+    req.extensions_mut().insert(protocol);
+
+    let extra_headers = head.extra_headers().cloned().unwrap_or_default();
+
+    let headers = head
+        .as_ref()
+        .headers
+        .iter()
+        .filter(|(name, _)| !extra_headers.contains_key(*name))
+        .chain(extra_headers.iter());
+
+    for (key, value) in headers {
+        match *key {
+            // omit HTTP/1.x only headers and the WebSocket key, which is not used with extended
+            // CONNECT according to https://datatracker.ietf.org/doc/html/rfc8441#section-5
+            CONNECTION | TRANSFER_ENCODING | UPGRADE | SEC_WEBSOCKET_KEY => continue,
+            _ => {}
+        }
+        req.headers_mut().append(key, value.clone());
+    }
+
+    let res = poll_fn(|cx| io.poll_ready(cx)).await;
+    if let Err(err) = res {
+        io.on_release(err.is_io());
+        return Err(SendRequestError::from(err));
+    }
+
+    let (fut, send) = match io.send_request(req, false) {
+        Ok(res) => {
+            io.on_release(false);
+            res
+        }
+        Err(err) => {
+            io.on_release(err.is_io());
+            return Err(err.into());
+        }
+    };
+
+    let (parts, recv) = fut.await?.into_parts();
+
+    let mut head = ResponseHead::new(parts.status);
+    head.version = parts.version;
+    head.headers = parts.headers.into();
+
+    Ok((
+        head,
+        H2Tunnel {
+            send,
+            recv,
+            buf: Bytes::new(),
+        },
+    ))
+}
+
+/// Byte stream over an HTTP/2 stream opened with extended CONNECT.
+pub(crate) struct H2Tunnel {
+    send: SendStream<Bytes>,
+    recv: RecvStream,
+
+    /// Received data not yet read.
+    buf: Bytes,
+}
+
+#[allow(clippy::io_other_error)] // `io::Error::other` is not available on MSRV
+fn into_io_error(err: h2::Error) -> io::Error {
+    if err.is_io() {
+        err.into_io().unwrap()
+    } else {
+        io::Error::new(io::ErrorKind::Other, err)
+    }
+}
+
+impl AsyncRead for H2Tunnel {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if this.buf.is_empty() {
+            match ready!(this.recv.poll_data(cx)) {
+                Some(Ok(chunk)) => {
+                    this.recv
+                        .flow_control()
+                        .release_capacity(chunk.len())
+                        .map_err(into_io_error)?;
+
+                    this.buf = chunk;
+                }
+                Some(Err(err)) => return Poll::Ready(Err(into_io_error(err))),
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+
+        let len = cmp::min(this.buf.len(), buf.remaining());
+        buf.put_slice(&this.buf.split_to(len));
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for H2Tunnel {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        this.send.reserve_capacity(buf.len());
+
+        match ready!(this.send.poll_capacity(cx)) {
+            Some(Ok(cap)) => {
+                let len = cmp::min(cap, buf.len());
+
+                this.send
+                    .send_data(Bytes::copy_from_slice(&buf[..len]), false)
+                    .map_err(into_io_error)?;
+
+                Poll::Ready(Ok(len))
+            }
+            Some(Err(err)) => Poll::Ready(Err(into_io_error(err))),
+            None => Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut()
+            .send
+            .send_data(Bytes::new(), true)
+            .map_err(into_io_error)?;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
 pub(crate) fn handshake<Io: ConnectionIo>(
     io: Io,
     config: &ConnectorConfig,
@@ -1,5 +1,6 @@
 use std::{
     future::Future,
+    marker::PhantomData,
     net,
     pin::Pin,
     rc::Rc,
@@ -117,6 +118,7 @@ where
         ConnectRequestFuture::Connection {
             fut,
             req: Some(req),
+            _phantom: PhantomData,
         }
     }
 }
@@ -130,7 +132,8 @@ pin_project_lite::pin_project! {
         Connection {
             #[pin]
             fut: Fut,
-            req: Option<ConnectRequest>
+            req: Option<ConnectRequest>,
+            _phantom: PhantomData<Io>
         },
         Client {
             fut: LocalBoxFuture<'static, Result<(ResponseHead, Payload), SendRequestError>>
@@ -138,7 +141,7 @@ pin_project_lite::pin_project! {
         Tunnel {
             fut: LocalBoxFuture<
                 'static,
-                Result<(ResponseHead, Framed<Connection<Io>, ClientCodec>), SendRequestError>,
+                Result<(ResponseHead, Framed<BoxedSocket, ClientCodec>), SendRequestError>,
             >,
         }
     }
@@ -153,7 +156,7 @@ where
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match self.as_mut().project() {
-            ConnectRequestProj::Connection { fut, req } => {
+            ConnectRequestProj::Connection { fut, req, .. } => {
                 let connection = ready!(fut.poll(cx))?;
                 let req = req.take().unwrap();
 
@@ -189,7 +192,6 @@ where
 
             ConnectRequestProj::Tunnel { fut } => {
                 let (head, framed) = ready!(fut.as_mut().poll(cx))?;
-                let framed = framed.into_map_io(|io| Box::new(io) as _);
                 Poll::Ready(Ok(ConnectResponse::Tunnel(head, framed)))
             }
         }
@@ -25,6 +25,14 @@
 //!     assert_eq!(response, ws::Frame::Text("Echo".as_bytes().into()));
 //! }
 //! ```
+//!
+//! # HTTP/2
+//! WebSockets are opened using HTTP/1.1 upgrade requests by default; connecting fails with
+//! [`SendRequestError::TunnelNotSupported`](crate::error::SendRequestError::TunnelNotSupported)
+//! when a secure connection negotiates HTTP/2. Use [`WebsocketsRequest::http2`] to open the
+//! WebSocket as a stream on HTTP/2 connections whose server supports extended CONNECT requests
+//! (RFC 8441), or [`Connector::max_http_version`](crate::Connector::max_http_version) to always
+//! negotiate HTTP/1.1.
 
 use std::{fmt, net::SocketAddr, str};
 
@@ -57,6 +65,7 @@ pub struct WebsocketsRequest {
     addr: Option<SocketAddr>,
     max_size: usize,
     server_mode: bool,
+    http2: bool,
     config: ClientConfig,
 
     #[cfg(feature = "cookies")]
@@ -94,6 +103,7 @@ impl WebsocketsRequest {
             protocols: None,
             max_size: 65_536,
             server_mode: false,
+            http2: false,
             #[cfg(feature = "cookies")]
             cookies: None,
         }
@@ -162,6 +172,14 @@ impl WebsocketsRequest {
         self
     }
 
+    /// Allow opening the WebSocket over HTTP/2 using an extended CONNECT request (RFC 8441).
+    ///
+    /// By default, WebSockets are only opened using HTTP/1.1 upgrade requests.
+    pub fn http2(mut self, enabled: bool) -> Self {
+        self.http2 = enabled;
+        self
+    }
+
     /// Append a header.
     ///
     /// Header gets appended to existing header.
@@ -303,6 +321,10 @@ impl WebsocketsRequest {
 
         self.head.set_connection_type(ConnectionType::Upgrade);
 
+        if self.http2 {
+            self.head.set_protocol("websocket");
+        }
+
         #[allow(clippy::declare_interior_mutable_const)]
         const HV_WEBSOCKET: HeaderValue = HeaderValue::from_static("websocket");
         self.head.headers.insert(header::UPGRADE, HV_WEBSOCKET);
@@ -349,6 +371,27 @@ impl WebsocketsRequest {
 
         let (head, framed) = res.into_tunnel_response();
 
+        let codec = |_| {
+            if server_mode {
+                ws::Codec::new().max_size(max_size)
+            } else {
+                ws::Codec::new().max_size(max_size).client_mode()
+            }
+        };
+
+        // extended CONNECT tunnels over HTTP/2 (RFC 8441) are accepted with a 200 response that
+        // has no upgrade headers or accept key
+        if head.version == Version::HTTP_2 {
+            if head.status != StatusCode::OK {
+                return Err(WsClientError::InvalidResponseStatus(head.status));
+            }
+
+            return Ok((
+                ClientResponse::new(head, Payload::None),
+                framed.into_map_codec(codec),
+            ));
+        }
+
         // verify response
         if head.status != StatusCode::SWITCHING_PROTOCOLS {
             return Err(WsClientError::InvalidResponseStatus(head.status));
@@ -408,13 +451,7 @@ impl WebsocketsRequest {
         // response and ws framed
         Ok((
             ClientResponse::new(head, Payload::None),
-            framed.into_map_codec(|_| {
-                if server_mode {
-                    ws::Codec::new().max_size(max_size)
-                } else {
-                    ws::Codec::new().max_size(max_size).client_mode()
-                }
-            }),
+            framed.into_map_codec(codec),
         ))
     }
 }
@@ -7,11 +7,15 @@ use std::sync::{
     Arc,
 };
 
-use actix_http::HttpService;
+use actix_codec::{Decoder as _, Encoder as _};
+use actix_http::{body::BodyStream, ws, Error, HttpService, Request};
 use actix_http_test::test_server;
 use actix_service::{fn_service, map_config, ServiceFactoryExt};
 use actix_utils::future::ok;
 use actix_web::{dev::AppConfig, http::Version, web, App, HttpResponse};
+use awc::error::{SendRequestError, WsClientError};
+use bytes::{Bytes, BytesMut};
+use futures_util::{stream, SinkExt as _, StreamExt as _};
 use openssl::{
     pkey::PKey,
     ssl::{SslAcceptor, SslConnector, SslMethod, SslVerifyMode},
@@ -90,3 +94,72 @@ async fn test_connection_reuse_h2() {
     // one connection
     assert_eq!(num.load(Ordering::Relaxed), 1);
 }
+
+#[actix_rt::test]
+async fn test_ws_h2() {
+    let srv = test_server(move || {
+        HttpService::build()
+            .h2(|mut req: Request| async move {
+                let mut res = ws::handshake(req.head())?;
+                let mut payload = req.take_payload();
+
+                // echo the first text frame
+                let echo = stream::once(async move {
+                    let mut codec = ws::Codec::new();
+                    let mut buf = BytesMut::new();
+
+                    loop {
+                        if let Some(ws::Frame::Text(text)) = codec.decode(&mut buf)? {
+                            let text = String::from_utf8(text.to_vec()).unwrap();
+                            let mut out = BytesMut::new();
+                            codec.encode(ws::Message::Text(text.into()), &mut out)?;
+                            return Ok::<_, Error>(out.freeze());
+                        }
+
+                        buf.extend_from_slice(&payload.next().await.unwrap()?);
+                    }
+                });
+
+                Ok::<_, Error>(res.body(BodyStream::new(echo)))
+            })
+            .openssl(tls_config())
+            .map_err(|_| ())
+    })
+    .await;
+
+    // disable ssl verification
+    let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+    builder.set_verify(SslVerifyMode::NONE);
+    let _ = builder
+        .set_alpn_protos(b"\x02h2\x08http/1.1")
+        .map_err(|e| log::error!("Can not set alpn protocol: {:?}", e));
+
+    let client = awc::Client::builder()
+        .connector(awc::Connector::new().openssl(builder.build()))
+        .finish();
+
+    // WebSockets are only opened over HTTP/2 when enabled
+    let res = client.ws(srv.surl("/")).connect().await;
+    assert!(matches!(
+        res,
+        Err(WsClientError::SendRequest(
+            SendRequestError::TunnelNotSupported
+        ))
+    ));
+
+    let (res, mut framed) = client
+        .ws(srv.surl("/"))
+        .http2(true)
+        .connect()
+        .await
+        .unwrap();
+    assert_eq!(res.status().as_u16(), 200);
+    assert_eq!(res.version(), Version::HTTP_2);
+
+    framed
+        .send(ws::Message::Text("hello".into()))
+        .await
+        .unwrap();
+    let item = framed.next().await.unwrap().unwrap();
+    assert_eq!(item, ws::Frame::Text(Bytes::from_static(b"hello")));
+}